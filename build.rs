@@ -1,168 +1,85 @@
-use std::collections::HashMap;
-use std::fs::{File, OpenOptions};
-use std::io::prelude::*;
-use std::path::Path;
-use std::string::ToString;
+#[path = "src/generator.rs"]
+mod generator;
 
-const DATA_PATH: &str = "data";
-fn main() {
-    cleanup();
-    generate_chars();
-    generate_words();
-    generate_surnames();
-    generate_heteronyms();
-}
-
-fn cleanup() {
-    std::fs::remove_dir_all(DATA_PATH).unwrap_or(());
-    std::fs::create_dir(DATA_PATH).expect("Failed to create data directory");
-}
-
-fn generate_chars() {
-    let mut data = vec![];
-
-    for path in [
-        Path::new("sources/chars.txt"),
-        Path::new("sources/patches/chars.txt"),
-    ] {
-        let mut file = File::open(path).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
-
-        for line in contents.lines() {
-            if let Some(item) = parse_line(line) {
-                data.push(item);
-            }
-        }
-    }
-
-    let chunk_size = div_ceil(data.len(), 10);
-
-    for (count, (unicode, pinyin)) in data.iter().enumerate() {
-        // unicode: "U+4E00"
-        let code_point = u32::from_str_radix(&unicode[2..], 16).unwrap();
-
-        let chunk_file_name = format!("chars_{}.txt", count / chunk_size);
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(Path::new(DATA_PATH).join(chunk_file_name))
-            .unwrap();
+use generator::{
+    generate_chars, generate_heteronyms, generate_jyutping, generate_pack, generate_static_table,
+    generate_surnames, generate_words,
+};
+use std::path::{Path, PathBuf};
 
-        if let Some(chinese) = char::from_u32(code_point) {
-            writeln!(file, "{}: {}", chinese, pinyin).expect("Failed to write chars to file");
-        }
-    }
-}
-
-fn generate_words() {
-    let mut data = HashMap::new();
-
-    for path in [
-        Path::new("sources/words.txt"),
-        Path::new("sources/patches/words.txt"),
-    ] {
-        let mut file = File::open(path).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
-
-        for line in contents.lines() {
-            if let Some((chinese, pinyin)) = parse_line(line) {
-                data.entry(chinese).or_insert(pinyin);
-            }
-        }
-    }
-
-    let chunk_size = div_ceil(data.len(), 10);
-
-    for (count, (chinese, pinyin)) in hashmap_to_sorted_vec(data).iter().enumerate() {
-        let chunk_file_name = format!("words_{}.txt", count / chunk_size);
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(Path::new(DATA_PATH).join(chunk_file_name))
-            .unwrap();
-
-        writeln!(file, "{}: {}", chinese, pinyin).expect("Failed to write words to file");
-    }
-}
-
-fn generate_surnames() {
-    let mut data = vec![];
-
-    let mut file = File::open(Path::new("sources/surnames.txt")).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
-
-    for line in contents.lines() {
-        if let Some(item) = parse_line(line) {
-            data.push(item);
-        }
-    }
-
-    // 将结果写入文件
-    let mut file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(Path::new(DATA_PATH).join("surnames.txt"))
-        .unwrap();
-
-    for (chinese, pinyin) in data.iter() {
-        writeln!(file, "{}: {}", chinese, pinyin).expect("Failed to write surnames to file");
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/generator.rs");
+    println!("cargo:rerun-if-changed=sources");
+
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+    let dir = out_dir.join("data");
+    cleanup(&dir);
+
+    generate_chars(
+        &[
+            &read_source("sources/chars.txt"),
+            &read_source("sources/patches/chars.txt"),
+        ],
+        &dir,
+    )
+    .expect("Failed to write chars to file");
+    generate_words(
+        &[
+            &read_source("sources/words.txt"),
+            &read_source("sources/patches/words.txt"),
+        ],
+        &dir,
+    )
+    .expect("Failed to write words to file");
+    generate_surnames(&read_source("sources/surnames.txt"), &dir)
+        .expect("Failed to write surnames to file");
+    generate_heteronyms(&read_source("sources/heteronyms.txt"), &dir)
+        .expect("Failed to write heteronyms to file");
+    generate_pack("neutral", &read_source("sources/packs/neutral.txt"), &dir)
+        .expect("Failed to write neutral tone pack to file");
+    generate_pack(
+        "frequency",
+        &read_source("sources/packs/frequency.txt"),
+        &dir,
+    )
+    .expect("Failed to write frequency pack to file");
+    generate_pack("slang", &read_source("sources/packs/slang.txt"), &dir)
+        .expect("Failed to write slang pack to file");
+    generate_jyutping(
+        &[
+            &read_source("sources/packs/jyutping.txt"),
+            &read_source("sources/unihan/kCantonese.txt"),
+        ],
+        &dir,
+    )
+    .expect("Failed to write jyutping pack to file");
+    generate_pack("strokes", &read_source("sources/packs/strokes.txt"), &dir)
+        .expect("Failed to write strokes pack to file");
+
+    // 单字与姓氏数据量小，直接编译成静态数组，运行时无需解析
+    if std::env::var_os("CARGO_FEATURE_STATIC_TABLES").is_some() {
+        let chars: Vec<String> = (0..10)
+            .map(|i| std::fs::read_to_string(dir.join(format!("chars_{}.txt", i))))
+            .filter_map(Result::ok)
+            .collect();
+        let chars: Vec<&str> = chars.iter().map(String::as_str).collect();
+        generate_static_table("CHARS", &chars, &out_dir.join("chars.rs"))
+            .expect("Failed to write chars table");
+        generate_static_table(
+            "SURNAMES",
+            &[&read_source(dir.join("surnames.txt"))],
+            &out_dir.join("surnames.rs"),
+        )
+        .expect("Failed to write surnames table");
     }
 }
 
-fn generate_heteronyms() {
-    // contents: "重,好....."
-    let mut file = File::open(Path::new("sources/heteronyms.txt")).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
-
-    let data = contents.split(',').collect::<Vec<&str>>();
-
-    // 将结果写入文件
-    let mut file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(Path::new(DATA_PATH).join("heteronyms.txt"))
-        .unwrap();
-
-    data.join("\n").lines().for_each(|line| {
-        writeln!(file, "{}", line).expect("Failed to write heteronyms to file");
-    });
-}
-
-fn hashmap_to_sorted_vec(map: HashMap<String, String>) -> Vec<(String, String)> {
-    let mut vec: Vec<(String, String)> = map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-    vec.sort_by(|a, b| a.0.cmp(&b.0));
-    vec
-}
-
-fn parse_line(line: &str) -> Option<(String, String)> {
-    let parts: Vec<&str> = line.split(':').map(|s| s.trim()).collect();
-    // U+41F8: chéng tīng  # 䇸
-    // 顶证: dǐng zhèng
-    // 燕: yān
-    if parts.len() == 2 && !parts[0].starts_with('#') {
-        let chinese = parts[0].trim().to_string();
-        let pinyin = parts[1]
-            .split_whitespace()
-            .take_while(|s| !s.starts_with('#'))
-            .collect::<Vec<&str>>()
-            .join(" ");
-
-        assert!(!chinese.is_empty() && !pinyin.is_empty());
-
-        return Some((chinese, pinyin.trim().parse().unwrap()));
-    }
-
-    None
+fn cleanup(dir: &Path) {
+    std::fs::remove_dir_all(dir).unwrap_or(());
+    std::fs::create_dir_all(dir).expect("Failed to create data directory");
 }
 
-fn div_ceil(num: usize, denom: usize) -> usize {
-    assert!(denom > 0);
-
-    (num + denom - 1) / denom
+fn read_source<P: AsRef<Path>>(path: P) -> String {
+    std::fs::read_to_string(path).unwrap()
 }
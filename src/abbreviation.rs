@@ -0,0 +1,106 @@
+use crate::converter::Converter;
+use crate::pinyin::{Abbr, YuFormat};
+
+// 组合数的上限，多音字很多的长文本只保留前面的组合
+const MAX_KEYS: usize = 32;
+
+// 每个字可以输入的首字母，多音字的每个读音都算；词语按词中的读音，字母与数字用小写的原字符，其余字符忽略
+fn initials(hanzi: &str) -> Vec<Vec<char>> {
+    let mut result = vec![];
+
+    for word in Converter::new(hanzi).convert() {
+        if !word.is_matched() {
+            result.extend(
+                word.word
+                    .chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .map(|c| c.to_lowercase().collect()),
+            );
+            continue;
+        }
+
+        let single = word.word.chars().nth(1).is_none();
+        let readings = match single {
+            true => vec![&word.pinyin[..]],
+            false => word.pinyin.chunks(1).collect(),
+        };
+        for pinyin in readings {
+            let mut letters: Vec<char> = vec![];
+            for p in pinyin {
+                let c = p.render(&Abbr, YuFormat::V).chars().next();
+                if let Some(c) = c.filter(|c| !letters.contains(c)) {
+                    letters.push(c);
+                }
+            }
+            result.push(letters);
+        }
+    }
+
+    result
+}
+
+// 用户只输入首字母时是否命中，abbr 可以从任意一个字开始连续匹配，不区分大小写：
+// matches_abbreviation("中国人民银行", "zg") -> true，matches_abbreviation("中国人民银行", "rmyh") -> true
+pub fn matches_abbreviation(hanzi: &str, abbr: &str) -> bool {
+    let abbr: Vec<char> = abbr
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if abbr.is_empty() {
+        return true;
+    }
+
+    initials(hanzi).windows(abbr.len()).any(|window| {
+        window
+            .iter()
+            .zip(&abbr)
+            .all(|(letters, c)| letters.contains(c))
+    })
+}
+
+// 适合存入索引的首字母串，多音字的每种组合各一个，第一个为最常用的读音：
+// abbreviation_keys("长") -> ["z", "c"]，检索时对这些键做前缀或子串匹配即可，不必再转换
+pub fn abbreviation_keys(hanzi: &str) -> Vec<String> {
+    let mut keys = vec![String::new()];
+
+    for letters in initials(hanzi) {
+        keys = keys
+            .iter()
+            .flat_map(|key| letters.iter().map(move |c| format!("{}{}", key, c)))
+            .take(MAX_KEYS)
+            .collect();
+    }
+    keys.retain(|key| !key.is_empty());
+
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{abbreviation_keys, matches_abbreviation};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_matches_abbreviation() {
+        assert!(matches_abbreviation("中国", "zg"));
+        assert!(matches_abbreviation("中国", "ZG"));
+        assert!(matches_abbreviation("中国人民银行", "rmyh"));
+        assert!(matches_abbreviation("中国人民银行", "z"));
+        assert!(matches_abbreviation("王 小明", "wxm"));
+        assert!(matches_abbreviation("iPhone 手机", "iphonesj"));
+        assert!(matches_abbreviation("任意", ""));
+        assert!(!matches_abbreviation("中国", "zgr"));
+        assert!(!matches_abbreviation("中国", "zhg"));
+        assert!(!matches_abbreviation("中国人民银行", "zr"));
+    }
+
+    #[test]
+    fn test_abbreviation_keys() {
+        assert_eq!(abbreviation_keys("中国"), vec!["zg"]);
+        assert_eq!(abbreviation_keys("长"), vec!["z", "c"]);
+        assert_eq!(abbreviation_keys("长大"), vec!["zd"]);
+        assert_eq!(abbreviation_keys("iPhone 手机"), vec!["iphonesj"]);
+        assert!(abbreviation_keys("，").is_empty());
+    }
+}
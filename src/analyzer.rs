@@ -0,0 +1,282 @@
+use crate::converter::Converter;
+use crate::pinyin::{ToneStyle, YuFormat};
+use crate::syllable::split_pinyin_best;
+use std::ops::Range;
+
+// 与 elasticsearch-analysis-pinyin 的同名参数含义相同，默认值也一致
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyzerOptions {
+    // "刘德华" -> "ldh"
+    pub keep_first_letter: bool,
+    // "刘德华" -> "l"、"d"、"h"
+    pub keep_separate_first_letter: bool,
+    // 首字母串最多保留的字符数
+    pub limit_first_letter_length: usize,
+    // "刘德华" -> "liu"、"de"、"hua"
+    pub keep_full_pinyin: bool,
+    // "刘德华" -> "liudehua"
+    pub keep_joined_full_pinyin: bool,
+    // 保留非中文的字母与数字
+    pub keep_none_chinese: bool,
+    // 连续的字母与数字作为一个词元，否则逐字输出
+    pub keep_none_chinese_together: bool,
+    // 首字母串中包含非中文："刘德华AT2016" -> "ldhat2016"
+    pub keep_none_chinese_in_first_letter: bool,
+    // 拼音连写中包含非中文
+    pub keep_none_chinese_in_joined_full_pinyin: bool,
+    // 按拼音切分非中文的字母："liudehua" -> "liu"、"de"、"hua"
+    pub none_chinese_pinyin_tokenize: bool,
+    // 保留原文
+    pub keep_original: bool,
+    pub lowercase: bool,
+    pub remove_duplicated_term: bool,
+}
+
+impl Default for AnalyzerOptions {
+    fn default() -> Self {
+        Self {
+            keep_first_letter: true,
+            keep_separate_first_letter: false,
+            limit_first_letter_length: 16,
+            keep_full_pinyin: true,
+            keep_joined_full_pinyin: false,
+            keep_none_chinese: true,
+            keep_none_chinese_together: true,
+            keep_none_chinese_in_first_letter: true,
+            keep_none_chinese_in_joined_full_pinyin: false,
+            none_chinese_pinyin_tokenize: true,
+            keep_original: false,
+            lowercase: true,
+            remove_duplicated_term: false,
+        }
+    }
+}
+
+// 偏移为原文中的字节下标，position 为词元的位置，首字母串、连写与原文的位置为 0
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub term: String,
+    pub start_offset: usize,
+    pub end_offset: usize,
+    pub position: usize,
+}
+
+// 把文本拆为汉字音节（Some）与连续的字母、数字（None），其余字符忽略
+pub(crate) fn phonetic_units(text: &str) -> Vec<(Range<usize>, Option<String>)> {
+    let mut units = vec![];
+    // 未命中的字逐个出现，连续的字母与数字要跨词合并
+    let mut run: Option<usize> = None;
+
+    for spanned in Converter::new(text).convert_spanned() {
+        let word = &spanned.word;
+        if word.is_matched() {
+            if let Some(start) = run.take() {
+                units.push((start..spanned.range.start, None));
+            }
+            for ((i, c), p) in word.word.char_indices().zip(word.syllables()) {
+                let from = spanned.range.start + i;
+                let syllable = p.format_with(ToneStyle::None, YuFormat::V);
+                units.push((from..from + c.len_utf8(), Some(syllable)));
+            }
+            continue;
+        }
+
+        for (i, c) in word.word.char_indices() {
+            let offset = spanned.range.start + i;
+            match (c.is_alphanumeric(), run) {
+                (true, None) => run = Some(offset),
+                (false, Some(start)) => {
+                    units.push((start..offset, None));
+                    run = None;
+                }
+                _ => {}
+            }
+        }
+    }
+    if let Some(start) = run {
+        units.push((start..text.len(), None));
+    }
+
+    units
+}
+
+pub fn analyze(text: &str) -> Vec<Token> {
+    analyze_with(text, &AnalyzerOptions::default())
+}
+
+pub fn analyze_with(text: &str, options: &AnalyzerOptions) -> Vec<Token> {
+    let units = phonetic_units(text);
+    let mut tokens = vec![];
+    let mut position = 0;
+    let mut first_letters = String::new();
+    let mut joined = String::new();
+    let mut push = |term: &str, range: Range<usize>, position: usize| {
+        tokens.push(Token {
+            term: term.to_string(),
+            start_offset: range.start,
+            end_offset: range.end,
+            position,
+        })
+    };
+
+    for (range, syllable) in &units {
+        let other = &text[range.clone()];
+        match syllable {
+            Some(syllable) => {
+                let first: String = syllable.chars().take(1).collect();
+                if options.keep_full_pinyin {
+                    push(syllable, range.clone(), position);
+                }
+                if options.keep_separate_first_letter {
+                    push(&first, range.clone(), position);
+                }
+                first_letters.push_str(&first);
+                joined.push_str(syllable);
+                position += 1;
+            }
+            None => {
+                if options.keep_none_chinese_in_first_letter {
+                    first_letters.push_str(other);
+                }
+                if options.keep_none_chinese_in_joined_full_pinyin {
+                    joined.push_str(other);
+                }
+                if !options.keep_none_chinese {
+                    continue;
+                }
+
+                let syllables = match options.none_chinese_pinyin_tokenize {
+                    true if other.chars().all(|c| c.is_ascii_alphabetic()) => {
+                        split_pinyin_best(other)
+                    }
+                    _ => None,
+                };
+                let parts: Vec<String> = match syllables {
+                    Some(syllables) => syllables,
+                    None if options.keep_none_chinese_together => vec![other.to_string()],
+                    None => other.chars().map(String::from).collect(),
+                };
+                let mut offset = range.start;
+                for part in parts {
+                    push(&part, offset..offset + part.len(), position);
+                    offset += part.len();
+                    position += 1;
+                }
+            }
+        }
+    }
+
+    let whole = match (units.first(), units.last()) {
+        (Some((first, _)), Some((last, _))) => first.start..last.end,
+        _ => return tokens,
+    };
+    if options.keep_first_letter && !first_letters.is_empty() {
+        let limited: String = first_letters
+            .chars()
+            .take(options.limit_first_letter_length)
+            .collect();
+        push(&limited, whole.clone(), 0);
+    }
+    if options.keep_joined_full_pinyin && !joined.is_empty() {
+        push(&joined, whole.clone(), 0);
+    }
+    if options.keep_original {
+        push(text.trim(), whole, 0);
+    }
+
+    if options.lowercase {
+        for token in tokens.iter_mut() {
+            token.term = token.term.to_lowercase();
+        }
+    }
+    if options.remove_duplicated_term {
+        let mut seen = std::collections::HashSet::new();
+        tokens.retain(|token| seen.insert(token.term.clone()));
+    }
+    tokens.sort_by_key(|token| token.position);
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analyze, analyze_with, AnalyzerOptions};
+    use pretty_assertions::assert_eq;
+
+    fn terms(tokens: &[super::Token]) -> Vec<(&str, usize, usize, usize)> {
+        tokens
+            .iter()
+            .map(|t| (t.term.as_str(), t.start_offset, t.end_offset, t.position))
+            .collect()
+    }
+
+    #[test]
+    fn test_analyze() {
+        assert_eq!(
+            terms(&analyze("刘德华")),
+            vec![
+                ("liu", 0, 3, 0),
+                ("ldh", 0, 9, 0),
+                ("de", 3, 6, 1),
+                ("hua", 6, 9, 2),
+            ]
+        );
+        assert_eq!(
+            terms(&analyze("刘德华 AT2016")),
+            vec![
+                ("liu", 0, 3, 0),
+                ("ldhat2016", 0, 16, 0),
+                ("de", 3, 6, 1),
+                ("hua", 6, 9, 2),
+                ("at2016", 10, 16, 3),
+            ]
+        );
+        assert!(analyze("，。").is_empty());
+    }
+
+    #[test]
+    fn test_analyze_with() {
+        let options = AnalyzerOptions {
+            keep_separate_first_letter: true,
+            keep_joined_full_pinyin: true,
+            keep_original: true,
+            keep_first_letter: false,
+            remove_duplicated_term: true,
+            ..Default::default()
+        };
+        let tokens = analyze_with("刘德华liudehua", &options);
+        let terms: Vec<&str> = tokens.iter().map(|t| t.term.as_str()).collect();
+        assert_eq!(
+            terms,
+            vec![
+                "liu",
+                "l",
+                "liudehua",
+                "刘德华liudehua",
+                "de",
+                "d",
+                "hua",
+                "h"
+            ]
+        );
+
+        let options = AnalyzerOptions {
+            lowercase: false,
+            none_chinese_pinyin_tokenize: false,
+            keep_none_chinese_together: false,
+            keep_full_pinyin: false,
+            keep_first_letter: false,
+            ..Default::default()
+        };
+        let terms: Vec<String> = analyze_with("中DJ", &options)
+            .into_iter()
+            .map(|t| t.term)
+            .collect();
+        assert_eq!(terms, vec!["D", "J"]);
+
+        // 长串字母按音节最少的切分输出，不会枚举全部切分
+        let text = "xian".repeat(100);
+        let tokens = analyze_with(&text, &AnalyzerOptions::default());
+        assert_eq!(tokens.iter().filter(|t| t.term == "xian").count(), 100);
+    }
+}
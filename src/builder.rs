@@ -0,0 +1,178 @@
+use crate::converter::{Config, Converter, LetterCase, Script, UnknownCharPolicy};
+use crate::engine::Engine;
+use crate::model::HeteronymModel;
+use crate::ngram::NgramModel;
+use crate::pinyin::{NeutralTone, ToneStyle, YuFormat};
+use crate::skip::SkipPattern;
+use crate::store::UserDict;
+use crate::unknown::UnknownCharHandler;
+use std::sync::Arc;
+
+// 按值链式构建 Converter，适合写在一行或闭包里：
+// ConverterBuilder::new().input("中国").tone_style(ToneStyle::Number).build().to_string()
+// 构建出的 Converter 不需要 mut 绑定，可以在线程间共享
+#[derive(Debug, Clone, Default)]
+pub struct ConverterBuilder<'a> {
+    input: &'a str,
+    config: Config,
+}
+
+impl<'a> ConverterBuilder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn input(mut self, input: &'a str) -> Self {
+        self.input = input;
+        self
+    }
+
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn script(mut self, script: Script) -> Self {
+        self.config.script = script;
+        self
+    }
+
+    pub fn tone_style(mut self, style: ToneStyle) -> Self {
+        self.config.tone_style = style;
+        self
+    }
+
+    pub fn yu_format(mut self, yu_format: YuFormat) -> Self {
+        self.config.yu_format = yu_format;
+        self
+    }
+
+    pub fn neutral_tone(mut self, neutral_tone: NeutralTone) -> Self {
+        self.config.neutral_tone = neutral_tone;
+        self
+    }
+
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.config.separator = separator.to_string();
+        self
+    }
+
+    pub fn heteronym_limit(mut self, limit: usize) -> Self {
+        self.config.heteronym_limit = Some(limit);
+        self
+    }
+
+    pub fn normalize_fullwidth(mut self, normalize: bool) -> Self {
+        self.config.normalize_fullwidth = normalize;
+        self
+    }
+
+    pub fn letter_case(mut self, letter_case: LetterCase) -> Self {
+        self.config.letter_case = letter_case;
+        self
+    }
+
+    pub fn collapse_separators(mut self, collapse: bool) -> Self {
+        self.config.collapse_separators = collapse;
+        self
+    }
+
+    pub fn trim_separators(mut self, trim: bool) -> Self {
+        self.config.trim_separators = trim;
+        self
+    }
+
+    pub fn space_latin(mut self, space: bool) -> Self {
+        self.config.space_latin = space;
+        self
+    }
+
+    pub fn unknown_char_policy(mut self, policy: UnknownCharPolicy) -> Self {
+        self.config.unknown_char_policy = policy;
+        self
+    }
+
+    pub fn on_unknown_char<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(char, usize) -> Option<String> + Send + 'static,
+    {
+        self.config.unknown_char_handler = Some(UnknownCharHandler::new(f));
+        self
+    }
+
+    pub fn heteronym_model(mut self, model: Arc<HeteronymModel>) -> Self {
+        self.config.heteronym_model = Some(model);
+        self
+    }
+
+    pub fn ngram_model(mut self, model: Arc<NgramModel>) -> Self {
+        self.config.ngram_model = Some(model);
+        self
+    }
+
+    pub fn skip_pattern(mut self, pattern: SkipPattern) -> Self {
+        self.config.skip_patterns.push(pattern);
+        self
+    }
+
+    pub fn user_dict(mut self, dict: UserDict) -> Self {
+        self.config.user_dicts.push(dict);
+        self
+    }
+
+    pub fn prefer_neutral_tone(mut self, prefer: bool) -> Self {
+        self.config.neutral_tone_words = prefer;
+        self
+    }
+
+    pub fn apply_tone_sandhi(mut self, apply: bool) -> Self {
+        self.config.tone_sandhi = apply;
+        self
+    }
+
+    pub fn apply_third_tone_sandhi(mut self, apply: bool) -> Self {
+        self.config.third_tone_sandhi = apply;
+        self
+    }
+
+    pub fn build(self) -> Converter<'a> {
+        Converter::with_config(self.input, self.config)
+    }
+
+    // 用构建器中的配置创建引擎，设置过的 input 会被忽略
+    pub fn build_engine(self) -> Engine {
+        Engine::new(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConverterBuilder;
+    use crate::converter::Converter;
+    use crate::pinyin::ToneStyle;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_converter_builder() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let converter = ConverterBuilder::new()
+            .input("中国人")
+            .tone_style(ToneStyle::Number)
+            .separator("-")
+            .build();
+        assert_send_sync(&converter);
+        assert_eq!(converter.to_string(), "zhong1-guo2-ren2");
+
+        let texts = ["你好", "重庆"];
+        let converted: Vec<_> = texts
+            .iter()
+            .map(|text| ConverterBuilder::new().input(text).build().to_string())
+            .collect();
+        assert_eq!(converted, vec!["nǐ hǎo", "chóng qìng"]);
+        assert_eq!(
+            ConverterBuilder::new().input("中国").build().config(),
+            Converter::new("中国").config()
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use crate::converter::Converter;
+use crate::pinyin::{ToneStyle, YuFormat};
+use std::cmp::Ordering;
+#[cfg(feature = "strokes")]
+use std::collections::HashMap;
+#[cfg(feature = "strokes")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "strokes")]
+static STROKES: OnceLock<HashMap<char, u8>> = OnceLock::new();
+
+// 读音与声调都相同时的排序依据
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tiebreak {
+    // 按原文的码位
+    #[default]
+    CodePoint,
+    // 先按每个汉字的笔画数，再按码位："马" 排在 "码" 之前
+    // 没有开启 strokes feature 时没有笔画数据，与 CodePoint 相同
+    Strokes,
+}
+
+// 汉字的总笔画数，数据中没有的字返回 None
+#[cfg(feature = "strokes")]
+pub fn stroke_count(c: char) -> Option<u8> {
+    STROKES
+        .get_or_init(|| {
+            crate::data::load("strokes.txt")
+                .lines()
+                .filter_map(|line| {
+                    let (c, count) = line.split_once(':')?;
+                    let mut chars = c.trim().chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => Some((c, count.trim().parse().ok()?)),
+                        _ => None,
+                    }
+                })
+                .collect()
+        })
+        .get(&c)
+        .copied()
+}
+
+// 按拼音排序用的键，直接比较字符串即可得到拼音字母序：
+// sort_key("中国") -> "zhong guo\t12\t中国"
+// 依次比较不带声调的音节、声调、原文，ü 写作 v；没有读音的字母、数字转为小写参与比较，空白与标点忽略
+pub fn sort_key(text: &str) -> String {
+    sort_key_with(text, Tiebreak::CodePoint)
+}
+
+pub fn sort_key_with(text: &str, tiebreak: Tiebreak) -> String {
+    let mut syllables = vec![];
+    let mut tones = String::new();
+    // 连续的字母、数字作为一个整体
+    let mut run = String::new();
+    let flush = |run: &mut String, syllables: &mut Vec<String>, tones: &mut String| {
+        if !run.is_empty() {
+            syllables.push(std::mem::take(run));
+            tones.push('0');
+        }
+    };
+
+    for word in Converter::new(text).convert() {
+        if word.is_matched() {
+            flush(&mut run, &mut syllables, &mut tones);
+            for p in word.syllables() {
+                syllables.push(p.format_with(ToneStyle::None, YuFormat::V));
+                tones.push_str(&p.tone_number().to_string());
+            }
+            continue;
+        }
+
+        for c in word.word.chars() {
+            match c.is_alphanumeric() {
+                true => run.extend(c.to_lowercase()),
+                false => flush(&mut run, &mut syllables, &mut tones),
+            }
+        }
+    }
+    flush(&mut run, &mut syllables, &mut tones);
+
+    // 制表符小于空格与字母，较短的音节序列排在前面
+    match tiebreak {
+        Tiebreak::CodePoint => format!("{}\t{}\t{}", syllables.join(" "), tones, text),
+        Tiebreak::Strokes => match strokes_key(text) {
+            Some(strokes) => format!("{}\t{}\t{}\t{}", syllables.join(" "), tones, strokes, text),
+            None => format!("{}\t{}\t{}", syllables.join(" "), tones, text),
+        },
+    }
+}
+
+// 每个汉字两位笔画数，数据中没有的字排在最后
+#[cfg(feature = "strokes")]
+fn strokes_key(text: &str) -> Option<String> {
+    let strokes = text
+        .chars()
+        .filter(|c| crate::is_han(*c))
+        .map(|c| format!("{:02}", stroke_count(c).unwrap_or(99)))
+        .collect();
+    Some(strokes)
+}
+
+#[cfg(not(feature = "strokes"))]
+fn strokes_key(_: &str) -> Option<String> {
+    None
+}
+
+// contacts.sort_by(|a, b| pinyin_cmp(a, b))
+pub fn pinyin_cmp(a: &str, b: &str) -> Ordering {
+    sort_key(a).cmp(&sort_key(b))
+}
+
+pub fn pinyin_cmp_with(a: &str, b: &str, tiebreak: Tiebreak) -> Ordering {
+    sort_key_with(a, tiebreak).cmp(&sort_key_with(b, tiebreak))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pinyin_cmp, pinyin_cmp_with, sort_key, Tiebreak};
+    use pretty_assertions::assert_eq;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_sort_key() {
+        assert_eq!(sort_key("中国"), "zhong guo\t12\t中国");
+        assert_eq!(sort_key("绿 Tea!"), "lv tea\t40\t绿 Tea!");
+        assert_eq!(sort_key(""), "\t\t");
+    }
+
+    #[test]
+    fn test_pinyin_cmp() {
+        let mut names = vec!["张三", "李四", "王五", "阿毛", "赵六", "Bob", "123"];
+        names.sort_by(|a, b| pinyin_cmp(a, b));
+        assert_eq!(
+            names,
+            vec!["123", "阿毛", "Bob", "李四", "王五", "张三", "赵六"]
+        );
+
+        // 声调只在音节相同时起作用
+        assert_eq!(pinyin_cmp("妈", "马"), Ordering::Less);
+        assert_eq!(pinyin_cmp("骂", "马"), Ordering::Greater);
+        assert_eq!(pinyin_cmp("马", "妈妈"), Ordering::Less);
+        assert_eq!(pinyin_cmp("西安", "先"), Ordering::Less);
+        assert_eq!(pinyin_cmp("中国", "中国"), Ordering::Equal);
+        assert_eq!(
+            pinyin_cmp_with("码", "马", Tiebreak::CodePoint),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "strokes"))]
+    fn test_pinyin_cmp_strokes_without_data() {
+        use super::sort_key_with;
+
+        assert_eq!(sort_key_with("中国", Tiebreak::Strokes), sort_key("中国"));
+        assert_eq!(
+            pinyin_cmp_with("码", "马", Tiebreak::Strokes),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "strokes")]
+    fn test_pinyin_cmp_strokes() {
+        use super::{sort_key_with, stroke_count};
+
+        assert_eq!(stroke_count('一'), Some(1));
+        assert_eq!(stroke_count('国'), Some(8));
+        assert_eq!(stroke_count('a'), None);
+        assert_eq!(
+            sort_key_with("中国", Tiebreak::Strokes),
+            "zhong guo\t12\t0408\t中国"
+        );
+
+        // 码位顺序中 "蟆" 在 "麻" 之前，按笔画数 "麻" 11 画、"蟆" 17 画
+        let mut words = vec!["蟆", "麻"];
+        words.sort_by(|a, b| pinyin_cmp_with(a, b, Tiebreak::Strokes));
+        assert_eq!(words, vec!["麻", "蟆"]);
+    }
+}
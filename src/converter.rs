@@ -0,0 +1,2198 @@
+use crate::error::PinyinError;
+use crate::katakana::to_katakana;
+use crate::loader::NeutralToneLoader;
+use crate::matcher::MatchSegment;
+use crate::model::HeteronymModel;
+use crate::ngram::NgramModel;
+use crate::numeral::{is_numeral, read_numeral};
+use crate::pinyin::{
+    parse_readings, Abbr, NeutralTone, Pinyin, PinyinWord, ToneRenderer, ToneStyle, YuFormat, T9,
+};
+use crate::romanization::Romanization;
+use crate::sandhi::{apply_third_tone, apply_yi_bu};
+use crate::shuangpin::ShuangpinScheme;
+use crate::skip::SkipPattern;
+use crate::slug::Slug;
+use crate::store::UserDict;
+use crate::tone::Tone;
+use crate::translit::Transliterate;
+use crate::unknown::{UnknownCharHandler, UnknownChars};
+use crate::zhuyin::Zhuyin;
+use crate::{is_heteronym, neutral_tone_loader, try_segment};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::ops::Range;
+use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
+
+// 输入中原有的拉丁字母如何输出
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LetterCase {
+    #[default]
+    Preserve,
+    Lower,
+    Upper,
+}
+
+// 拼音以何种文字输出
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Script {
+    #[default]
+    Pinyin,
+    // 注音符号："ㄓㄨㄥ ㄍㄨㄛˊ"，忽略 tone_style 与 yu_format
+    Zhuyin,
+    // 其他拼写方案，声调按 tone_style 输出
+    Romanization(Romanization),
+    // 片假名："チョン クオ"，不标声调
+    Katakana,
+}
+
+// 没有读音的字符（标点、字母、字典未收录的汉字）如何处理
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum UnknownCharPolicy {
+    // 原样输出
+    #[default]
+    Keep,
+    // 丢弃，只输出拼音
+    Skip,
+    // 每个字符替换为占位符
+    Replace(char),
+    // convert_checked、try_to_string 返回 UnknownCharacter 错误，convert、to_string 等不返回错误的输出按 Keep 处理
+    Error,
+}
+
+// 声调序列的记法
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ToneNotation {
+    // "3 3"
+    #[default]
+    Number,
+    // 五度标记法："214 214"
+    Contour,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub script: Script,
+    pub tone_style: ToneStyle,
+    pub yu_format: YuFormat,
+    // ToneStyle::Number 中轻声写作 ma5、ma0 还是 ma
+    pub neutral_tone: NeutralTone,
+    pub separator: String,
+    // 单字最多保留几个读音，按字典顺序（常用读音在前）截取；至少保留一个读音，Some(0) 与 Some(1) 相同
+    pub heteronym_limit: Option<usize>,
+    // 把全角字母与数字（ＡＢＣ１２３）输出为半角
+    pub normalize_fullwidth: bool,
+    pub letter_case: LetterCase,
+    // 把输入中的空白以及连续的分隔符合并为一个分隔符
+    pub collapse_separators: bool,
+    // 去掉首尾的分隔符与空白
+    pub trim_separators: bool,
+    // 输入中的拉丁单词连写，与相邻的拼音之间只用一个分隔符："用Rust写" -> "yòng Rust xiě"
+    // 默认开启，关闭后每个字母单独输出："yòng R u s t xiě"
+    pub space_latin: bool,
+    // 对 convert 以及所有文本输出生效
+    pub unknown_char_policy: UnknownCharPolicy,
+    // 在 unknown_char_policy 之前为没有读音的字符提供转写
+    pub unknown_char_handler: Option<UnknownCharHandler>,
+    // 按相邻的字为没有被词语消歧的多音字选择读音，选中的读音排在候选的第一位
+    pub heteronym_model: Option<Arc<HeteronymModel>>,
+    // 用音节 n 元语言模型为整句的多音字挑选概率最高的读音组合，在 heteronym_model 之后应用
+    pub ngram_model: Option<Arc<NgramModel>>,
+    // 在匹配之前识别并原样输出的片段，按顺序尝试
+    pub skip_patterns: Vec<SkipPattern>,
+    // 用户词典，按添加顺序排在内置词库之前，见 UserDict
+    pub user_dicts: Vec<UserDict>,
+    // 按 "一"、"不" 的变调规则改写声调："一样" -> "yí yàng"，"不是" -> "bú shì"
+    pub tone_sandhi: bool,
+    // 整词匹配到的轻声词改用轻声读音："东西" -> "dōng xi"
+    pub neutral_tone_words: bool,
+    // 上声连读变调，输出口语中的实际读音："你好" -> "ní hǎo"，与字典的本调分开
+    pub third_tone_sandhi: bool,
+}
+
+impl Config {
+    // 按 tone_style、yu_format 输出一个音节，Number 风格的轻声按 neutral_tone
+    pub(crate) fn format_pinyin(&self, pinyin: &Pinyin) -> String {
+        match self.tone_style {
+            ToneStyle::Number => pinyin.render(&self.neutral_tone, self.yu_format),
+            style => pinyin.format_with(style, self.yu_format),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            script: Script::Pinyin,
+            tone_style: ToneStyle::Mark,
+            yu_format: YuFormat::Umlaut,
+            neutral_tone: NeutralTone::Five,
+            separator: " ".to_string(),
+            heteronym_limit: None,
+            normalize_fullwidth: false,
+            letter_case: LetterCase::Preserve,
+            collapse_separators: false,
+            trim_separators: false,
+            space_latin: true,
+            unknown_char_policy: UnknownCharPolicy::Keep,
+            unknown_char_handler: None,
+            heteronym_model: None,
+            ngram_model: None,
+            skip_patterns: vec![],
+            user_dicts: vec![],
+            tone_sandhi: false,
+            neutral_tone_words: false,
+            third_tone_sandhi: false,
+        }
+    }
+}
+
+// 未被词语消歧的多音字
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heteronym {
+    // 在输入中的字符下标
+    pub index: usize,
+    // 在输入中的字节偏移
+    pub offset: usize,
+    pub hanzi: char,
+    pub candidates: Vec<Pinyin>,
+}
+
+// 供编辑器、LSP 使用的标注：输入中的字节范围、首选读音与全部候选
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub range: Range<usize>,
+    pub reading: String,
+    pub candidates: Vec<String>,
+}
+
+// 带有原文位置的转换结果，range 为字节范围，char_range 为字符范围
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedWord {
+    pub word: PinyinWord,
+    pub range: Range<usize>,
+    pub char_range: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Converter<'a> {
+    input: &'a str,
+    // 由 Engine 创建时借用引擎的配置，不必为每段文本复制
+    config: Cow<'a, Config>,
+}
+
+impl<'a> Converter<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self::with_config(input, Config::default())
+    }
+
+    pub fn with_config(input: &'a str, config: Config) -> Self {
+        Self {
+            input,
+            config: Cow::Owned(config),
+        }
+    }
+
+    pub fn script(&mut self, script: Script) -> &mut Self {
+        self.config.to_mut().script = script;
+        self
+    }
+
+    pub fn tone_style(&mut self, style: ToneStyle) -> &mut Self {
+        self.config.to_mut().tone_style = style;
+        self
+    }
+
+    pub fn yu_format(&mut self, yu_format: YuFormat) -> &mut Self {
+        self.config.to_mut().yu_format = yu_format;
+        self
+    }
+
+    pub fn neutral_tone(&mut self, neutral_tone: NeutralTone) -> &mut Self {
+        self.config.to_mut().neutral_tone = neutral_tone;
+        self
+    }
+
+    pub fn separator(&mut self, separator: &str) -> &mut Self {
+        self.config.to_mut().separator = separator.to_string();
+        self
+    }
+
+    pub fn heteronym_limit(&mut self, limit: usize) -> &mut Self {
+        self.config.to_mut().heteronym_limit = Some(limit);
+        self
+    }
+
+    pub fn normalize_fullwidth(&mut self, normalize: bool) -> &mut Self {
+        self.config.to_mut().normalize_fullwidth = normalize;
+        self
+    }
+
+    pub fn letter_case(&mut self, letter_case: LetterCase) -> &mut Self {
+        self.config.to_mut().letter_case = letter_case;
+        self
+    }
+
+    pub fn collapse_separators(&mut self, collapse: bool) -> &mut Self {
+        self.config.to_mut().collapse_separators = collapse;
+        self
+    }
+
+    pub fn trim_separators(&mut self, trim: bool) -> &mut Self {
+        self.config.to_mut().trim_separators = trim;
+        self
+    }
+
+    pub fn space_latin(&mut self, space: bool) -> &mut Self {
+        self.config.to_mut().space_latin = space;
+        self
+    }
+
+    // 等同于 unknown_char_policy(UnknownCharPolicy::Error)，false 时恢复为 Keep
+    #[deprecated(note = "use unknown_char_policy(UnknownCharPolicy::Error) instead")]
+    pub fn error_on_unknown(&mut self, error: bool) -> &mut Self {
+        self.unknown_char_policy(match error {
+            true => UnknownCharPolicy::Error,
+            false => UnknownCharPolicy::Keep,
+        })
+    }
+
+    pub fn unknown_char_policy(&mut self, policy: UnknownCharPolicy) -> &mut Self {
+        self.config.to_mut().unknown_char_policy = policy;
+        self
+    }
+
+    // converter.on_unknown_char(|c, _| kana_to_romaji(c))
+    pub fn on_unknown_char<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(char, usize) -> Option<String> + Send + 'static,
+    {
+        self.config.to_mut().unknown_char_handler = Some(UnknownCharHandler::new(f));
+        self
+    }
+
+    pub fn heteronym_model(&mut self, model: Arc<HeteronymModel>) -> &mut Self {
+        self.config.to_mut().heteronym_model = Some(model);
+        self
+    }
+
+    pub fn ngram_model(&mut self, model: Arc<NgramModel>) -> &mut Self {
+        self.config.to_mut().ngram_model = Some(model);
+        self
+    }
+
+    pub fn skip_pattern(&mut self, pattern: SkipPattern) -> &mut Self {
+        self.config.to_mut().skip_patterns.push(pattern);
+        self
+    }
+
+    pub fn user_dict(&mut self, dict: UserDict) -> &mut Self {
+        self.config.to_mut().user_dicts.push(dict);
+        self
+    }
+
+    pub fn prefer_neutral_tone(&mut self, prefer: bool) -> &mut Self {
+        self.config.to_mut().neutral_tone_words = prefer;
+        self
+    }
+
+    pub fn apply_tone_sandhi(&mut self, apply: bool) -> &mut Self {
+        self.config.to_mut().tone_sandhi = apply;
+        self
+    }
+
+    pub fn apply_third_tone_sandhi(&mut self, apply: bool) -> &mut Self {
+        self.config.to_mut().third_tone_sandhi = apply;
+        self
+    }
+
+    pub(crate) fn borrowed(input: &'a str, config: &'a Config) -> Self {
+        Self {
+            input,
+            config: Cow::Borrowed(config),
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    // "中国，" -> [中国:zhong1 guo2, ，]
+    // 没有读音的词按 unknown_char_handler 与 unknown_char_policy 处理，Skip 掉的词不出现在结果中
+    // 字典数据缺失或损坏时 panic，需要处理这类错误或使用 Error 策略时使用 convert_checked
+    pub fn convert(&self) -> Vec<PinyinWord> {
+        self.try_convert(false).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // checked 为 false 时 Error 策略按 Keep 处理
+    fn try_convert(&self, checked: bool) -> Result<Vec<PinyinWord>, PinyinError> {
+        let words = self.try_resolve(self.try_convert_raw()?, checked)?;
+        Ok(words
+            .into_iter()
+            .filter_map(|(mut word, text)| match text {
+                None => Some(word),
+                Some(text) if text.is_empty() => None,
+                Some(text) => {
+                    word.word = text;
+                    Some(word)
+                }
+            })
+            .collect())
+    }
+
+    // 与 try_convert_raw 的结果一一对应，没有读音的词附带处理后的输出文本
+    fn resolve(&self) -> Vec<(PinyinWord, Option<String>)> {
+        self.try_convert_raw()
+            .and_then(|words| self.try_resolve(words, false))
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // 所有输出共用的未知字符处理：逐字交给 unknown_char_handler，返回 None 的再按 unknown_char_policy
+    fn try_resolve(
+        &self,
+        words: Vec<PinyinWord>,
+        checked: bool,
+    ) -> Result<Vec<(PinyinWord, Option<String>)>, PinyinError> {
+        let mut offset = 0;
+        let mut resolved = Vec::with_capacity(words.len());
+        for word in words {
+            let start = offset;
+            offset += word.word.len();
+            if word.is_matched() {
+                resolved.push((word, None));
+                continue;
+            }
+
+            let text = self.resolve_unmatched(&word.word, start, checked)?;
+            resolved.push((word, Some(text)));
+        }
+        Ok(resolved)
+    }
+
+    // offset 为 text 在输入中的字节偏移，checked 为 false 时 Error 策略按 Keep 处理
+    fn resolve_unmatched(
+        &self,
+        text: &str,
+        offset: usize,
+        checked: bool,
+    ) -> Result<String, PinyinError> {
+        let mut result = String::new();
+        for (i, c) in text.char_indices() {
+            let handler = self.config.unknown_char_handler.as_ref();
+            if let Some(s) = handler.and_then(|h| h.call(c, offset + i)) {
+                result.push_str(&s);
+                continue;
+            }
+            match self.config.unknown_char_policy {
+                UnknownCharPolicy::Error if checked => {
+                    return Err(PinyinError::UnknownCharacter(c))
+                }
+                UnknownCharPolicy::Keep | UnknownCharPolicy::Error => {
+                    result.push_str(&self.format_unmatched(&text[i..i + c.len_utf8()]))
+                }
+                UnknownCharPolicy::Skip => {}
+                UnknownCharPolicy::Replace(placeholder) => result.push(placeholder),
+            }
+        }
+        Ok(result)
+    }
+
+    // 切分与消歧的原始结果，各词首尾相接，正好覆盖整个输入
+    fn try_convert_raw(&self) -> Result<Vec<PinyinWord>, PinyinError> {
+        let mut words: Vec<PinyinWord> = self
+            .segment()?
+            .into_iter()
+            .map(|(word, pinyin)| {
+                let mut pinyin = match pinyin {
+                    Some(pinyin) => parse_readings(&pinyin)?,
+                    None => vec![],
+                };
+                // 词语的拼音是逐字的音节，只有单字才是多个候选读音
+                if word.chars().count() == 1 {
+                    dedup_readings(&mut pinyin);
+                }
+                Ok(PinyinWord::new(&word, pinyin))
+            })
+            .collect::<Result<_, PinyinError>>()?;
+
+        if let Some(model) = &self.config.heteronym_model {
+            apply_heteronym_model(model, &mut words);
+        }
+        if let Some(model) = &self.config.ngram_model {
+            model.choose_readings(&mut words);
+        }
+        read_numerals(&mut words);
+        if self.config.neutral_tone_words {
+            apply_neutral_tone(neutral_tone_loader()?, &mut words)?;
+        }
+        if self.config.tone_sandhi {
+            apply_yi_bu(&mut words);
+        }
+        if self.config.third_tone_sandhi {
+            apply_third_tone(&mut words);
+        }
+        if let Some(limit) = self.config.heteronym_limit {
+            for word in words.iter_mut().filter(|w| w.word.chars().count() == 1) {
+                word.pinyin.truncate(limit.max(1));
+            }
+        }
+        Ok(words)
+    }
+
+    // 全角字母与数字先转为半角再切分，"ＡＢＣ１２３" 与 "ABC123" 切分相同，
+    // 切出的词仍取自原文，与输入中的位置一一对应，输出时再由 format_unmatched 转为半角
+    fn segment(&self) -> Result<Vec<(String, Option<String>)>, PinyinError> {
+        if !self.config.normalize_fullwidth || self.input.chars().all(|c| to_halfwidth(c) == c) {
+            return self.segment_input(self.input);
+        }
+
+        let normalized: String = self.input.chars().map(to_halfwidth).collect();
+        let mut rest = self.input;
+        let segments = self
+            .segment_input(&normalized)?
+            .into_iter()
+            .map(|(word, pinyin)| {
+                let len = rest
+                    .char_indices()
+                    .nth(word.chars().count())
+                    .map_or(rest.len(), |(i, _)| i);
+                let (word, tail) = rest.split_at(len);
+                rest = tail;
+                (word.to_string(), pinyin)
+            })
+            .collect();
+        Ok(segments)
+    }
+
+    fn segment_input(&self, input: &str) -> Result<Vec<(String, Option<String>)>, PinyinError> {
+        self.split_skipped(input, |text| self.segment_text(text))
+    }
+
+    // 先切出 skip_patterns 命中的片段，其余部分再交给 segment 分别匹配
+    fn split_skipped<T>(
+        &self,
+        input: &str,
+        mut segment: impl FnMut(&str) -> Result<Vec<(String, Option<T>)>, PinyinError>,
+    ) -> Result<Vec<(String, Option<T>)>, PinyinError> {
+        if self.config.skip_patterns.is_empty() {
+            return segment(input);
+        }
+
+        let mut result = vec![];
+        let mut start = 0;
+        let mut offsets = input.char_indices().map(|(i, _)| i).peekable();
+        while let Some(i) = offsets.next() {
+            let rest = &input[i..];
+            let len = match self
+                .config
+                .skip_patterns
+                .iter()
+                .find_map(|pattern| pattern.match_at(rest))
+            {
+                Some(len) => len,
+                None => continue,
+            };
+
+            result.extend(segment(&input[start..i])?);
+            result.push((rest[..len].to_string(), None));
+            start = i + len;
+            while offsets.next_if(|offset| *offset < start).is_some() {}
+        }
+        result.extend(segment(&input[start..])?);
+
+        Ok(result)
+    }
+
+    // 用户词典命中的词优先切出，其余部分交给内置词库
+    fn segment_text(&self, text: &str) -> Result<Vec<(String, Option<String>)>, PinyinError> {
+        if self.config.user_dicts.is_empty() {
+            return try_segment(text);
+        }
+
+        let mut result = vec![];
+        let mut start = 0;
+        let mut offsets = text.char_indices().map(|(i, _)| i).peekable();
+        while let Some(i) = offsets.next() {
+            // 多个词典都命中时取最长的词，一样长时以先添加的词典为准
+            let found = self
+                .config
+                .user_dicts
+                .iter()
+                .filter_map(|dict| dict.longest_match(&text[i..]))
+                .fold(None, |best: Option<(&str, String)>, found| match best {
+                    Some(best) if best.0.len() >= found.0.len() => Some(best),
+                    _ => Some(found),
+                });
+            let (word, pinyin) = match found {
+                Some(found) => found,
+                None => continue,
+            };
+
+            result.extend(try_segment(&text[start..i])?);
+            result.push((word.to_string(), Some(pinyin)));
+            start = i + word.len();
+            while offsets.next_if(|offset| *offset < start).is_some() {}
+        }
+        result.extend(try_segment(&text[start..])?);
+
+        Ok(result)
+    }
+
+    // 按行与句末标点分段、逐段转换，不会一次生成整篇文本的结果，适合很长的文档
+    // 各段独立转换，不带上下文：跨越分段的词语、跳过片段会被拆开，此时结果与 convert() 不同
+    pub fn convert_iter(&self) -> ConvertIter<'_> {
+        ConvertIter {
+            rest: self.input,
+            config: &self.config,
+            words: VecDeque::new(),
+        }
+    }
+
+    // 转换的同时把没有读音的汉字记录到 unknown 中
+    pub fn convert_with_unknown(&self, unknown: &mut UnknownChars) -> Vec<PinyinWord> {
+        let words = self.convert();
+        unknown.collect(&self.raw());
+        words
+    }
+
+    // 与 convert 相同，但把字典数据缺失或损坏、内部状态异常以及（可选的）未覆盖汉字作为错误返回
+    pub fn convert_checked(&self) -> Result<Vec<PinyinWord>, PinyinError> {
+        let words = self.try_convert_raw()?;
+        let mut covered = String::with_capacity(self.input.len());
+
+        for word in words.iter() {
+            covered.push_str(&word.word);
+            if !word.is_matched() {
+                continue;
+            }
+
+            let char_count = word.word.chars().count();
+            let malformed = word
+                .pinyin
+                .iter()
+                .any(|p| Pinyin::try_new(&p.pinyin, p.tone).is_err());
+            // 词语的音节数必须与字数一致
+            if malformed || (char_count > 1 && word.pinyin.len() != char_count) {
+                return Err(PinyinError::InvalidData(word.to_string()));
+            }
+        }
+
+        if covered != self.input {
+            return Err(PinyinError::Internal(format!(
+                "segments {:?} do not cover input {:?}",
+                covered, self.input
+            )));
+        }
+
+        self.try_convert(true)
+    }
+
+    fn raw(&self) -> Vec<PinyinWord> {
+        self.try_convert_raw().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // 严格模式：任何没有读音的字符（标点、字母、字典未收录的汉字）都作为错误返回
+    // 即以 UnknownCharPolicy::Error 调用 convert_checked
+    pub fn strict(&self) -> Strict<'_, 'a> {
+        Strict { converter: self }
+    }
+}
+
+// Converter::strict 返回的严格转换器
+#[derive(Debug, Clone, Copy)]
+pub struct Strict<'c, 'a> {
+    converter: &'c Converter<'a>,
+}
+
+impl Strict<'_, '_> {
+    pub fn convert(&self) -> Result<Vec<PinyinWord>, PinyinError> {
+        let mut converter = self.converter.clone();
+        converter.unknown_char_policy(UnknownCharPolicy::Error);
+        converter.convert_checked()
+    }
+}
+
+impl Converter<'_> {
+    // 每个切分出的词的音节数，与 convert_spanned() 的结果一一对应，没有读音的为 0
+    // 只做匹配，不解析、不格式化拼音
+    pub fn syllable_counts(&self) -> Vec<usize> {
+        self.segment()
+            .unwrap_or_else(|e| panic!("{}", e))
+            .iter()
+            .map(|(word, pinyin)| match pinyin {
+                None => 0,
+                Some(_) if word.chars().nth(1).is_none() => 1,
+                Some(pinyin) => pinyin.split_whitespace().count(),
+            })
+            .collect()
+    }
+
+    pub fn syllable_count(&self) -> usize {
+        self.syllable_counts().iter().sum()
+    }
+
+    // 与输入逐字对应的 (字, 读音)，词语按字展开，供注音、卡拉 OK 高亮、逐字提示使用
+    // 多音字取第一个读音，没有读音的字符为 None
+    pub fn to_pairs(&self) -> Vec<(char, Option<Pinyin>)> {
+        let mut pairs = Vec::with_capacity(self.input.len());
+        for word in self.raw() {
+            let mut syllables = word.syllables().iter().cloned();
+            pairs.extend(word.word.chars().map(|c| (c, syllables.next())));
+        }
+        pairs
+    }
+
+    // 只输出声调序列，供韵律研究、声调练习使用："你好" -> "3 3"
+    // 多音字取第一个读音，没有读音的字符不输出
+    pub fn to_tones(&self, notation: ToneNotation) -> String {
+        self.convert()
+            .iter()
+            .flat_map(|word| word.syllables())
+            .map(|p| match notation {
+                ToneNotation::Number => p.tone.to_string(),
+                ToneNotation::Contour => p.contour().to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(&self.config.separator)
+    }
+
+    // 生成在 Windows 与 Unix 上都安全的文件名："第1章：开始?" -> "di_1_zhang_kai_shi"
+    // 汉字输出不带声调的拼音，字母、数字转为小写连续保留，保留字符、空白与标点作为分隔，
+    // 超过 max_len 字节时在音节边界截断，结果不以 "." 开头或结尾，也不会是 CON、NUL 等设备名；
+    // 非汉字部分先做 NFC 规范化，避免同一文件名在 macOS 与其他系统上字节不同；max_len 为 0 时返回空字符串
+    pub fn to_filename(&self, max_len: usize) -> String {
+        if max_len == 0 {
+            return String::new();
+        }
+
+        let mut tokens: Vec<String> = vec![];
+        let mut in_text = false;
+
+        for word in self.convert() {
+            if word.is_matched() {
+                for p in word.syllables() {
+                    tokens.push(p.format_with(ToneStyle::None, YuFormat::V));
+                }
+                in_text = false;
+                continue;
+            }
+
+            for c in word.word.nfc() {
+                if !(c.is_alphanumeric() || c == '.' || c == '-') {
+                    in_text = false;
+                    continue;
+                }
+                match tokens.last_mut() {
+                    Some(last) if in_text => last.extend(c.to_lowercase()),
+                    _ => {
+                        tokens.push(c.to_lowercase().collect());
+                        in_text = true;
+                    }
+                }
+            }
+        }
+
+        let mut filename = String::new();
+        for token in tokens.iter() {
+            let token = token.trim_matches('.');
+            if token.is_empty() {
+                continue;
+            }
+            let len = filename.len() + token.len() + usize::from(!filename.is_empty());
+            if len > max_len {
+                // 第一个音节就超长时按字符截断
+                if filename.is_empty() {
+                    filename.extend(
+                        token
+                            .char_indices()
+                            .take_while(|(i, c)| i + c.len_utf8() <= max_len)
+                            .map(|(_, c)| c),
+                    );
+                }
+                break;
+            }
+            if !filename.is_empty() {
+                filename.push('_');
+            }
+            filename.push_str(token);
+        }
+
+        let stem = filename.split('.').next().unwrap_or_default();
+        let reserved = matches!(stem, "con" | "prn" | "aux" | "nul")
+            || (stem.len() == 4
+                && (stem.starts_with("com") || stem.starts_with("lpt"))
+                && stem.ends_with(|c: char| c.is_ascii_digit()));
+        if filename.is_empty() || reserved {
+            filename.insert(0, '_');
+            // 补上前缀后仍不能超过 max_len
+            while filename.len() > max_len {
+                filename.pop();
+            }
+        }
+
+        filename
+    }
+
+    // "带着希望去旅行" -> "dai-zhe-xi-wang-qu-lv-xing"
+    // 多音字只取第一个读音，输入中的字母与数字连续保留，其余字符丢弃
+    pub fn to_permalink(&self) -> String {
+        self.to_permalink_with(&|_| None)
+    }
+
+    // 先把非汉字、非 ASCII 字符转写为 ASCII 再生成 slug：
+    // to_permalink_with(&(Latin, Kana)) 把 "咖啡 Café" 转为 "ka-fei-Cafe"
+    pub fn to_permalink_with(&self, transliterator: &dyn Transliterate) -> String {
+        self.permalink_tokens(transliterator).join("-")
+    }
+
+    // 按配置生成 slug，见 Slug
+    pub fn to_slug(&self, slug: &Slug) -> String {
+        slug.join(self.permalink_tokens(slug.transliterator()))
+    }
+
+    // 每个音节一项，连续的字母与数字合为一项，只含 ASCII 字母与数字
+    fn permalink_tokens(&self, transliterator: &dyn Transliterate) -> Vec<String> {
+        let mut tokens: Vec<String> = vec![];
+        let mut in_latin = false;
+
+        for word in self.convert() {
+            if word.is_matched() {
+                for p in word.syllables() {
+                    tokens.push(p.format_with(ToneStyle::None, YuFormat::V));
+                }
+                in_latin = false;
+                continue;
+            }
+
+            let text: String = word
+                .word
+                .chars()
+                .map(|c| match c.is_ascii() {
+                    true => c.to_string(),
+                    false => transliterator
+                        .transliterate(c)
+                        .unwrap_or_else(|| c.to_string()),
+                })
+                .collect();
+            let text: String = text.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+            match tokens.last_mut() {
+                _ if text.is_empty() => in_latin = false,
+                Some(last) if in_latin => last.push_str(&text),
+                _ => {
+                    tokens.push(text);
+                    in_latin = true;
+                }
+            }
+        }
+
+        tokens
+    }
+
+    // "你好世界" -> "nhsj"，"用Rust 2021写代码" -> "yR2021xdm"
+    // 多音字只取第一个读音；字母组成的单词取首字母，数字完整保留，其余字符丢弃
+    pub fn to_abbr(&self) -> String {
+        let mut abbr = String::new();
+        // 连续的字母、数字
+        let mut run = String::new();
+
+        for word in self.convert() {
+            if word.is_matched() {
+                abbr.push_str(abbr_run(&std::mem::take(&mut run)));
+                for p in word.syllables() {
+                    abbr.push_str(&p.render(&Abbr, YuFormat::V));
+                }
+                continue;
+            }
+
+            for c in word.word.chars() {
+                match c.is_ascii_alphanumeric() {
+                    true => run.push(c),
+                    false => abbr.push_str(abbr_run(&std::mem::take(&mut run))),
+                }
+            }
+        }
+        abbr.push_str(abbr_run(&run));
+
+        abbr
+    }
+
+    // "你好" -> "64426"，用于按九宫格数字检索通讯录
+    // 多音字只取第一个读音；字母按所在的键转换，数字原样保留，其余字符丢弃
+    pub fn to_t9(&self) -> String {
+        let mut digits = String::new();
+
+        for word in self.convert() {
+            match word.is_matched() {
+                true => {
+                    for p in word.syllables() {
+                        digits.push_str(&p.render(&T9, YuFormat::V));
+                    }
+                }
+                false => digits.extend(word.word.chars().filter_map(T9::key)),
+            }
+        }
+
+        digits
+    }
+
+    // "你好，世界！" -> "nǐ hǎo, shì jiè!"
+    // 保留标点的位置并转为半角，标点前不留空格，之后留一个空格
+    pub fn to_sentence(&self) -> String {
+        let mut tokens: Vec<(String, Punctuation)> = vec![];
+
+        for (word, text) in self.resolve() {
+            let text = match text {
+                Some(text) => text,
+                None => {
+                    for p in word.syllables() {
+                        let syllable = self.config.format_pinyin(p);
+                        tokens.push((syllable, Punctuation::None));
+                    }
+                    continue;
+                }
+            };
+            if text.is_empty() {
+                continue;
+            }
+
+            // 替换为转写或占位符后不再按标点处理
+            let kind = match text == self.format_unmatched(&word.word) {
+                true => Punctuation::of(&word.word),
+                false => Punctuation::None,
+            };
+            let text = match kind {
+                Punctuation::None => text,
+                _ => to_ascii_punctuation(&word.word),
+            };
+
+            // 连续的字母、数字合并为一个单词
+            match tokens.last_mut() {
+                Some((last, Punctuation::None))
+                    if is_alphanumeric(last) && is_alphanumeric(&text) =>
+                {
+                    last.push_str(&text)
+                }
+                _ => tokens.push((text, kind)),
+            }
+        }
+
+        let mut sentence = String::new();
+        let mut space = false;
+        for (text, kind) in tokens {
+            match kind {
+                Punctuation::Space => continue,
+                Punctuation::Close => {
+                    sentence.push_str(&text);
+                    space = true;
+                }
+                Punctuation::None | Punctuation::Open => {
+                    if space {
+                        sentence.push(' ');
+                    }
+                    sentence.push_str(&text);
+                    space = kind == Punctuation::None;
+                }
+            }
+        }
+
+        sentence
+    }
+
+    // 词内音节连写，返回连写后的文本与词内音节边界（可断行处）的字节偏移：
+    // "中国人" -> ("zhōngguórén", [6, 10])，"西安" -> ("xī'ān", [3])
+    // 以 a、o、e 开头的音节前按拼写规则加隔音符号，断行位置在隔音符号之前
+    pub fn break_opportunities(&self) -> (String, Vec<usize>) {
+        let mut text = String::new();
+        let mut breaks = vec![];
+        let mut last_matched = None;
+
+        for word in self.convert() {
+            let matched = word.is_matched();
+            let token = (!matched).then(|| word.word.clone());
+
+            // 相邻的非拼音字符保持原样连在一起，空白两侧不再加分隔符
+            let blank = token
+                .as_deref()
+                .is_some_and(|t| t.chars().all(char::is_whitespace));
+            let separated = last_matched.is_some_and(|last| {
+                (last || matched) && !blank && !text.ends_with(char::is_whitespace)
+            });
+            if separated {
+                text.push_str(&self.config.separator);
+            }
+
+            match token {
+                Some(token) => text.push_str(&token),
+                None => {
+                    for (i, p) in word.syllables().iter().enumerate() {
+                        if i > 0 {
+                            breaks.push(text.len());
+                            if p.pinyin.starts_with(['a', 'o', 'e']) {
+                                text.push('\'');
+                            }
+                        }
+                        text.push_str(&self.config.format_pinyin(p));
+                    }
+                }
+            }
+            last_matched = Some(matched);
+        }
+
+        (text, breaks)
+    }
+
+    // 在词内音节之间插入软连字符（U+00AD），排版时可以在此断行并显示连字符
+    pub fn to_hyphenated(&self) -> String {
+        let (text, breaks) = self.break_opportunities();
+        let mut result = String::with_capacity(text.len() + breaks.len() * 2);
+        let mut last = 0;
+        for offset in breaks {
+            result.push_str(&text[last..offset]);
+            result.push('\u{AD}');
+            last = offset;
+        }
+        result.push_str(&text[last..]);
+        result
+    }
+
+    // 粤拼："香港银行" -> "hoeng1 gong2 ngan4 hong4"，单字取第一个读音
+    // 使用独立的粤语词库，没有读音的字符按 unknown_char_policy 输出
+    #[cfg(feature = "jyutping")]
+    pub fn jyutping(&self) -> String {
+        let segments = self
+            .split_skipped(self.input, |text| {
+                Ok(crate::jyutping::segment(text)
+                    .into_iter()
+                    .map(|(word, jyutping)| (word.to_string(), jyutping))
+                    .collect())
+            })
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        let mut offset = 0;
+        segments
+            .into_iter()
+            .map(|(text, jyutping)| {
+                offset += text.len();
+                match jyutping {
+                    Some(jyutping) => jyutping.replace(' ', &self.config.separator),
+                    None => self
+                        .resolve_unmatched(&text, offset - text.len(), false)
+                        .unwrap_or_else(|e| panic!("{}", e)),
+                }
+            })
+            .filter(|token| !token.is_empty())
+            .collect::<Vec<_>>()
+            .join(&self.config.separator)
+    }
+
+    // 区分真正转换的词与原样输出的字符："你好!" -> [Matched(你好), NotMatched('!')]
+    pub fn segments(&self) -> Vec<MatchSegment> {
+        let mut segments = vec![];
+        for word in self.raw() {
+            match word.is_matched() {
+                true => segments.push(MatchSegment::Matched(word)),
+                false => segments.extend(word.word.chars().map(MatchSegment::NotMatched)),
+            }
+        }
+        segments
+    }
+
+    // "你好，中国" -> [你好 0..6 / 0..2, ， 6..9 / 2..3, 中国 9..15 / 3..5]
+    // 各段首尾相接，正好覆盖整个输入
+    pub fn convert_spanned(&self) -> Vec<SpannedWord> {
+        let mut offset = 0;
+        let mut char_offset = 0;
+
+        self.raw()
+            .into_iter()
+            .map(|word| {
+                let range = offset..offset + word.word.len();
+                let char_range = char_offset..char_offset + word.word.chars().count();
+                offset = range.end;
+                char_offset = char_range.end;
+                SpannedWord {
+                    word,
+                    range,
+                    char_range,
+                }
+            })
+            .collect()
+    }
+
+    // 为每个命中字典的词生成标注，未命中的字符不产生标注
+    pub fn annotations(&self) -> Vec<Annotation> {
+        let format = |p: &Pinyin| self.config.format_pinyin(p);
+        let mut annotations = vec![];
+        let mut offset = 0;
+
+        for word in self.raw() {
+            let range = offset..offset + word.word.len();
+            offset = range.end;
+
+            if !word.is_matched() {
+                continue;
+            }
+
+            let reading = word
+                .syllables()
+                .iter()
+                .map(format)
+                .collect::<Vec<_>>()
+                .join(" ");
+            let candidates = if word.word.chars().count() == 1 {
+                let mut candidates = word.pinyin.iter().map(format).collect();
+                dedup_readings(&mut candidates);
+                candidates
+            } else {
+                vec![reading.clone()]
+            };
+
+            annotations.push(Annotation {
+                range,
+                reading,
+                candidates,
+            });
+        }
+
+        annotations
+    }
+
+    // 只找出没有被词语匹配消歧、仍有多个候选读音的多音字
+    pub fn heteronyms(&self) -> Vec<Heteronym> {
+        let mut heteronyms = vec![];
+        let mut index = 0;
+        let mut offset = 0;
+
+        for word in self.raw() {
+            let mut chars = word.word.chars();
+            if let (Some(hanzi), None) = (chars.next(), chars.next()) {
+                if word.pinyin.len() > 1 && is_heteronym(hanzi) {
+                    heteronyms.push(Heteronym {
+                        index,
+                        offset,
+                        hanzi,
+                        candidates: word.pinyin,
+                    });
+                }
+            }
+
+            index += word.word.chars().count();
+            offset += word.word.len();
+        }
+
+        heteronyms
+    }
+
+    // 原样输出输入文本，仅在多音字后标注候选读音："七中" -> "七(qī|qí)中"
+    pub fn annotate_heteronyms(&self) -> String {
+        let mut result = String::with_capacity(self.input.len());
+        let mut last = 0;
+
+        for heteronym in self.heteronyms() {
+            let end = heteronym.offset + heteronym.hanzi.len_utf8();
+            let mut candidates = heteronym
+                .candidates
+                .iter()
+                .map(|p| self.config.format_pinyin(p))
+                .collect::<Vec<_>>();
+            dedup_readings(&mut candidates);
+
+            result.push_str(&self.input[last..end]);
+            result.push_str(&format!("({})", candidates.join("|")));
+            last = end;
+        }
+
+        result.push_str(&self.input[last..]);
+        result
+    }
+}
+
+impl Display for Converter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string_with(&self.config.separator))
+    }
+}
+
+impl Converter<'_> {
+    // 用指定的分隔符连接结果，空白的合并与首尾裁剪由配置决定
+    pub fn to_string_with(&self, separator: &str) -> String {
+        self.try_to_string_with(separator, false)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // 与 to_string 相同，但把字典数据错误与 UnknownCharPolicy::Error 遇到的字符作为错误返回
+    pub fn try_to_string(&self) -> Result<String, PinyinError> {
+        self.try_to_string_with(&self.config.separator, true)
+    }
+
+    fn try_to_string_with(&self, separator: &str, checked: bool) -> Result<String, PinyinError> {
+        match self.config.script {
+            Script::Pinyin if self.config.tone_style == ToneStyle::Number => {
+                self.join(&self.config.neutral_tone, separator, checked)
+            }
+            Script::Pinyin => self.join(&self.config.tone_style, separator, checked),
+            Script::Zhuyin => self.join(&Zhuyin, separator, checked),
+            Script::Katakana => self.join(
+                &|p: &str, t: Tone| to_katakana(p).unwrap_or_else(|| ToneStyle::Mark.render(p, t)),
+                separator,
+                checked,
+            ),
+            Script::Romanization(romanization) => {
+                let style = self.config.tone_style;
+                self.join(
+                    &|p: &str, t: Tone| romanization.render(p, t, style),
+                    separator,
+                    checked,
+                )
+            }
+        }
+    }
+
+    // 使用自定义的声调呈现方式输出
+    pub fn render(&self, renderer: &dyn ToneRenderer) -> String {
+        self.join(renderer, &self.config.separator, false)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // "中国" -> "vs go"（小鹤双拼）
+    pub fn to_shuangpin(&self, scheme: ShuangpinScheme) -> String {
+        self.render(&scheme)
+    }
+
+    fn join(
+        &self,
+        renderer: &dyn ToneRenderer,
+        separator: &str,
+        checked: bool,
+    ) -> Result<String, PinyinError> {
+        let is_blank = |token: &str| {
+            token
+                .chars()
+                .all(|c| c.is_whitespace() || separator.contains(c))
+        };
+
+        // 按原文判断，转写或占位符替换后的文本不算拉丁单词
+        let is_latin = |word: &str| {
+            word.chars()
+                .map(|c| match self.config.normalize_fullwidth {
+                    true => to_halfwidth(c),
+                    false => c,
+                })
+                .all(|c| {
+                    c.is_ascii_alphanumeric()
+                        || (c.is_alphabetic() && ('\u{C0}'..='\u{24F}').contains(&c))
+                        || (c.is_ascii_punctuation() && !separator.contains(c))
+                })
+        };
+
+        // (输出, 是否为拉丁单词)
+        let mut tokens: Vec<(String, bool)> = vec![];
+        for (word, text) in self.try_resolve(self.try_convert_raw()?, checked)? {
+            let token = match text {
+                Some(text) => text,
+                None => self.format_word(&word, renderer, separator),
+            };
+            if token.is_empty() {
+                continue;
+            }
+            let latin = self.config.space_latin
+                && !word.is_matched()
+                && !is_blank(&token)
+                && is_latin(&word.word);
+            match tokens.last_mut() {
+                Some((last, true)) if latin => last.push_str(&token),
+                _ => tokens.push((token, latin)),
+            }
+        }
+
+        // 拉丁单词与相邻内容之间已有分隔符，两侧的空白不再输出
+        let latin: Vec<bool> = tokens.iter().map(|(_, latin)| *latin).collect();
+        let mut i = 0;
+        tokens.retain(|(token, _)| {
+            let beside_latin = (i > 0 && latin[i - 1]) || latin.get(i + 1) == Some(&true);
+            i += 1;
+            !(beside_latin && is_blank(token))
+        });
+        let mut tokens: Vec<String> = tokens.into_iter().map(|(token, _)| token).collect();
+
+        if self.config.collapse_separators {
+            tokens.retain(|token| !is_blank(token));
+        }
+
+        if self.config.trim_separators {
+            let start = tokens
+                .iter()
+                .position(|t| !is_blank(t))
+                .unwrap_or(tokens.len());
+            let end = tokens
+                .iter()
+                .rposition(|t| !is_blank(t))
+                .map_or(0, |i| i + 1);
+            tokens = tokens.drain(start..end.max(start)).collect();
+        }
+
+        Ok(tokens.join(separator))
+    }
+
+    fn format_word(
+        &self,
+        word: &PinyinWord,
+        renderer: &dyn ToneRenderer,
+        separator: &str,
+    ) -> String {
+        let mut readings: Vec<_> = word
+            .pinyin
+            .iter()
+            .map(|p| p.render(renderer, self.config.yu_format))
+            .collect();
+        // 不带声调时 "zhōng zhòng" 会变成两个相同的 "zhong"
+        if word.word.chars().count() == 1 {
+            dedup_readings(&mut readings);
+        }
+        readings.join(separator)
+    }
+
+    fn format_unmatched(&self, text: &str) -> String {
+        let text: String = if self.config.normalize_fullwidth {
+            text.chars().map(to_halfwidth).collect()
+        } else {
+            text.to_string()
+        };
+
+        match self.config.letter_case {
+            LetterCase::Preserve => text,
+            LetterCase::Lower => text.to_lowercase(),
+            LetterCase::Upper => text.to_uppercase(),
+        }
+    }
+}
+
+// Converter::convert_iter 返回的迭代器
+#[derive(Debug, Clone)]
+pub struct ConvertIter<'a> {
+    rest: &'a str,
+    config: &'a Config,
+    words: VecDeque<PinyinWord>,
+}
+
+impl Iterator for ConvertIter<'_> {
+    type Item = PinyinWord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.words.is_empty() && !self.rest.is_empty() {
+            let end = self
+                .rest
+                .find(['\n', '。', '！', '？', '；'])
+                .map_or(self.rest.len(), |i| {
+                    i + self.rest[i..].chars().next().map_or(1, char::len_utf8)
+                });
+            let (chunk, rest) = self.rest.split_at(end);
+            self.words
+                .extend(Converter::borrowed(chunk, self.config).convert());
+            self.rest = rest;
+        }
+
+        self.words.pop_front()
+    }
+}
+
+fn abbr_run(run: &str) -> &str {
+    match run.chars().all(|c| c.is_ascii_digit()) {
+        true => run,
+        false => &run[..1],
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Punctuation {
+    None,
+    Space,
+    // 开括号、前引号，紧贴后面的内容
+    Open,
+    // 逗号、句号、后引号等，紧贴前面的内容
+    Close,
+}
+
+impl Punctuation {
+    fn of(text: &str) -> Self {
+        match text.chars().next() {
+            Some(c) if c.is_whitespace() => Punctuation::Space,
+            Some('“' | '‘' | '（' | '(' | '《' | '【' | '[' | '「' | '『' | '〈') => {
+                Punctuation::Open
+            }
+            Some(
+                '，' | '。' | '！' | '？' | '：' | '；' | '、' | '”' | '’' | '）' | '》' | '】'
+                | '」' | '』' | '〉' | '…' | ',' | '.' | '!' | '?' | ':' | ';' | ')' | ']',
+            ) => Punctuation::Close,
+            _ => Punctuation::None,
+        }
+    }
+}
+
+fn to_ascii_punctuation(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '，' | '、' => ",".to_string(),
+            '。' => ".".to_string(),
+            '！' => "!".to_string(),
+            '？' => "?".to_string(),
+            '：' => ":".to_string(),
+            '；' => ";".to_string(),
+            '“' | '”' | '「' | '」' | '『' | '』' => "\"".to_string(),
+            '‘' | '’' => "'".to_string(),
+            '（' => "(".to_string(),
+            '）' => ")".to_string(),
+            '《' | '〈' => "<".to_string(),
+            '》' | '〉' => ">".to_string(),
+            '【' => "[".to_string(),
+            '】' => "]".to_string(),
+            '…' => "...".to_string(),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+fn is_alphanumeric(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+// 连续两个以上逐字匹配的数字（如 "陆佰"）按数字语境的读音输出
+fn read_numerals(words: &mut [PinyinWord]) {
+    let is_numeral_word = |word: &PinyinWord| {
+        let mut chars = word.word.chars();
+        matches!((chars.next(), chars.next()), (Some(c), None) if is_numeral(c))
+    };
+
+    let mut start = 0;
+    while start < words.len() {
+        let len = words[start..]
+            .iter()
+            .take_while(|w| is_numeral_word(w))
+            .count();
+
+        if len >= 2 {
+            let run = &mut words[start..start + len];
+            let text: String = run.iter().map(|w| w.word.as_str()).collect();
+            if let Some(numeral) = read_numeral(&text) {
+                for (word, pinyin) in run.iter_mut().zip(numeral.pinyin) {
+                    word.pinyin = vec![pinyin];
+                }
+            }
+        }
+
+        start += len.max(1);
+    }
+}
+
+fn apply_neutral_tone(
+    loader: &NeutralToneLoader,
+    words: &mut [PinyinWord],
+) -> Result<(), PinyinError> {
+    for word in words.iter_mut().filter(|w| w.pinyin.len() > 1) {
+        let pinyin = match loader.get(&word.word) {
+            Some(pinyin) => parse_readings(pinyin)?,
+            None => continue,
+        };
+        if pinyin.len() == word.word.chars().count() {
+            word.pinyin = pinyin;
+        }
+    }
+    Ok(())
+}
+
+fn apply_heteronym_model(model: &HeteronymModel, words: &mut [PinyinWord]) {
+    for i in 0..words.len() {
+        let mut chars = words[i].word.chars();
+        let hanzi = match (chars.next(), chars.next()) {
+            (Some(c), None) if words[i].pinyin.len() > 1 => c,
+            _ => continue,
+        };
+        let left = i.checked_sub(1).and_then(|j| words[j].word.chars().last());
+        let right = words.get(i + 1).and_then(|w| w.word.chars().next());
+        if let Some(best) = model.choose(hanzi, left, right, &words[i].pinyin) {
+            words[i].pinyin[..=best].rotate_right(1);
+        }
+    }
+}
+
+// 保留第一次出现的读音，候选读音很少，不需要借助 HashSet
+fn dedup_readings<T: PartialEq>(readings: &mut Vec<T>) {
+    let mut i = 0;
+    while i < readings.len() {
+        if readings[..i].contains(&readings[i]) {
+            readings.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+// 'Ａ' -> 'A', '１' -> '1'，其余字符保持不变
+fn to_halfwidth(c: char) -> char {
+    match c {
+        '０'..='９' | 'Ａ'..='Ｚ' | 'ａ'..='ｚ' => {
+            char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)
+        }
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        dedup_readings, to_halfwidth, Annotation, Converter, Heteronym, LetterCase, Script,
+        ToneNotation, UnknownCharPolicy,
+    };
+    use crate::error::PinyinError;
+    use crate::matcher::MatchSegment;
+    use crate::model::HeteronymModel;
+    use crate::ngram::NgramModel;
+    use crate::pinyin::{Abbr, NeutralTone, Pinyin};
+    use crate::pinyin::{ToneStyle, YuFormat};
+    use crate::romanization::Romanization;
+    use crate::shuangpin::ShuangpinScheme;
+    use crate::skip::SkipPattern;
+    use crate::store::UserDict;
+    use crate::tone::Tone;
+    use crate::translit::{Kana, Latin};
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_converter_to_string() {
+        let mut converter = Converter::new("中国人吃饭");
+        assert_eq!(converter.to_string(), "zhōng guó rén chī fàn");
+
+        converter.tone_style(ToneStyle::Number).separator("-");
+        assert_eq!(converter.to_string(), "zhong1-guo2-ren2-chi1-fan4");
+    }
+
+    #[test]
+    fn test_converter_convert() {
+        let words = Converter::new("中国，").convert();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].to_string(), "中国:zhong1 guo2");
+        assert!(!words[1].is_matched());
+        assert_eq!(words[1].word, "，");
+    }
+
+    #[test]
+    fn test_converter_heteronym_limit() {
+        let mut converter = Converter::new("七中国");
+        assert_eq!(converter.to_string(), "qī qí zhōng guó");
+
+        converter.heteronym_limit(1);
+        assert_eq!(converter.to_string(), "qī zhōng guó");
+
+        // 不会把多音字的读音全部去掉
+        converter.heteronym_limit(0);
+        assert_eq!(converter.to_string(), "qī zhōng guó");
+    }
+
+    #[test]
+    fn test_converter_yu_format() {
+        let mut converter = Converter::new("女儿去旅游");
+        converter
+            .tone_style(ToneStyle::Number)
+            .yu_format(YuFormat::V)
+            .heteronym_limit(1);
+        assert_eq!(converter.to_string(), "nv3 er2 qu4 lv3 you2");
+    }
+
+    #[test]
+    fn test_converter_heteronyms() {
+        let converter = Converter::new("重庆，七");
+        assert_eq!(
+            converter.heteronyms(),
+            vec![Heteronym {
+                index: 3,
+                offset: 9,
+                hanzi: '七',
+                candidates: vec![
+                    Pinyin::new("qi", Tone::First),
+                    Pinyin::new("qi", Tone::Second)
+                ],
+            }]
+        );
+        assert_eq!(converter.annotate_heteronyms(), "重庆，七(qī|qí)");
+    }
+
+    #[test]
+    fn test_converter_normalize_fullwidth() {
+        assert_eq!(to_halfwidth('Ａ'), 'A');
+        assert_eq!(to_halfwidth('ｚ'), 'z');
+        assert_eq!(to_halfwidth('９'), '9');
+        assert_eq!(to_halfwidth('，'), '，');
+
+        let mut converter = Converter::new("ＡＢＣ１２３");
+        assert_eq!(converter.to_string(), "Ａ Ｂ Ｃ １ ２ ３");
+
+        converter.normalize_fullwidth(true);
+        assert_eq!(converter.to_string(), "ABC123");
+        assert_eq!(converter.space_latin(false).to_string(), "A B C 123");
+        assert_eq!(converter.convert_spanned()[3].range, 9..18);
+
+        converter.separator("");
+        assert_eq!(converter.to_string(), "ABC123");
+    }
+
+    #[test]
+    fn test_converter_to_permalink() {
+        assert_eq!(
+            Converter::new("带着希望去旅行").to_permalink(),
+            "dai-zhe-xi-wang-qu-lv-xing"
+        );
+        assert_eq!(
+            Converter::new("用Rust 2021写代码！").to_permalink(),
+            "yong-Rust-2021-xie-dai-ma"
+        );
+    }
+
+    #[test]
+    fn test_converter_segments() {
+        let segments = Converter::new("你好, Rust").segments();
+        assert_eq!(segments.len(), 7);
+        assert!(
+            matches!(&segments[0], MatchSegment::Matched(w) if w.to_string() == "你好:ni3 hao3")
+        );
+        let rest: String = segments[1..]
+            .iter()
+            .map(|s| match s {
+                MatchSegment::NotMatched(c) => *c,
+                MatchSegment::Matched(_) => '?',
+            })
+            .collect();
+        assert_eq!(rest, ", Rust");
+    }
+
+    #[test]
+    fn test_converter_convert_spanned() {
+        let input = "你好，中国🇨🇳";
+        let spans = Converter::new(input).convert_spanned();
+        let ranges: Vec<_> = spans
+            .iter()
+            .map(|s| (s.word.word.as_str(), s.range.clone(), s.char_range.clone()))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                ("你好", 0..6, 0..2),
+                ("，", 6..9, 2..3),
+                ("中国", 9..15, 3..5),
+                ("🇨🇳", 15..23, 5..7),
+            ]
+        );
+        for span in spans {
+            assert_eq!(&input[span.range], span.word.word);
+        }
+    }
+
+    #[test]
+    fn test_converter_to_abbr() {
+        assert_eq!(Converter::new("你好世界").to_abbr(), "nhsj");
+        assert_eq!(Converter::new("带着希望去旅行").to_abbr(), "dzxwqlx");
+        assert_eq!(Converter::new("用Rust 2021写代码！").to_abbr(), "yR2021xdm");
+        assert_eq!(
+            Converter::new("你好世界")
+                .letter_case(LetterCase::Upper)
+                .to_abbr(),
+            "nhsj"
+        );
+        assert_eq!(Converter::new("中国").separator("").render(&Abbr), "zg");
+        assert_eq!(Converter::new("").to_abbr(), "");
+    }
+
+    #[test]
+    fn test_converter_letter_case() {
+        let mut converter = Converter::new("用Rust写字");
+        assert_eq!(converter.to_string(), "yòng Rust xiě zì");
+        assert_eq!(converter.to_permalink(), "yong-Rust-xie-zi");
+
+        converter.letter_case(LetterCase::Lower);
+        assert_eq!(converter.to_string(), "yòng rust xiě zì");
+        assert_eq!(converter.to_permalink(), "yong-rust-xie-zi");
+
+        converter.letter_case(LetterCase::Upper);
+        assert_eq!(converter.to_string(), "yòng RUST xiě zì");
+        assert_eq!(converter.to_permalink(), "yong-RUST-xie-zi");
+
+        converter.space_latin(false);
+        assert_eq!(converter.to_string(), "yòng R U S T xiě zì");
+    }
+
+    #[test]
+    fn test_converter_to_string_with() {
+        let mut converter = Converter::new(" 你好  世界 ");
+        assert_eq!(converter.to_string_with("-"), " -nǐ-hǎo- - -shì-jiè- ");
+
+        converter.collapse_separators(true);
+        assert_eq!(converter.to_string_with("-"), "nǐ-hǎo-shì-jiè");
+        assert_eq!(
+            Converter::new("世-界")
+                .collapse_separators(true)
+                .to_string_with("-"),
+            "shì-jiè"
+        );
+
+        converter.collapse_separators(false).trim_separators(true);
+        assert_eq!(converter.to_string_with("-"), "nǐ-hǎo- - -shì-jiè");
+    }
+
+    #[test]
+    fn test_converter_convert_checked() {
+        let words = Converter::new("你好，世界").convert_checked().unwrap();
+        assert_eq!(words, Converter::new("你好，世界").convert());
+
+        let mut converter = Converter::new("你好\u{9FEF}");
+        assert!(converter.convert_checked().is_ok());
+
+        converter.unknown_char_policy(UnknownCharPolicy::Error);
+        assert!(matches!(
+            converter.convert_checked(),
+            Err(PinyinError::UnknownCharacter('\u{9FEF}'))
+        ));
+
+        // 自定义存储中无法解析的读音作为错误返回，不会被当作音节输出
+        let store = HashMap::from([("你好".to_string(), "nǐhǎo".to_string())]);
+        let mut converter = Converter::new("你好");
+        converter.user_dict(UserDict::new(store));
+        assert!(matches!(
+            converter.convert_checked(),
+            Err(PinyinError::InvalidSyllable(s)) if s == "nǐhǎo"
+        ));
+    }
+
+    #[test]
+    fn test_converter_strict() {
+        let words = Converter::new("你好世界").strict().convert().unwrap();
+        assert_eq!(words, Converter::new("你好世界").convert());
+        assert!(Converter::new("三百").strict().convert().is_ok());
+
+        for (input, c) in [
+            ("你好，世界", '，'),
+            ("你好\u{9FEF}", '\u{9FEF}'),
+            ("A股", 'A'),
+        ] {
+            assert!(matches!(
+                Converter::new(input).strict().convert(),
+                Err(PinyinError::UnknownCharacter(u)) if u == c
+            ));
+        }
+    }
+
+    #[test]
+    fn test_converter_unknown_char_policy() {
+        let mut converter = Converter::new("你好，A股！");
+        converter.heteronym_limit(1);
+        assert_eq!(converter.to_string(), "nǐ hǎo ， A gǔ ！");
+        assert!(converter.convert_checked().is_ok());
+
+        converter.unknown_char_policy(UnknownCharPolicy::Skip);
+        assert_eq!(converter.to_string(), "nǐ hǎo gǔ");
+        assert_eq!(converter.to_sentence(), "nǐ hǎo gǔ");
+
+        // convert、to_abbr 等输出同样按策略处理
+        let words: Vec<_> = converter.convert().into_iter().map(|w| w.word).collect();
+        assert_eq!(words, ["你好", "股"]);
+        assert_eq!(converter.to_abbr(), "nhg");
+
+        converter.unknown_char_policy(UnknownCharPolicy::Replace('?'));
+        assert_eq!(converter.to_string(), "nǐ hǎo ? ? gǔ ?");
+        let words: Vec<_> = converter.convert().into_iter().map(|w| w.word).collect();
+        assert_eq!(words, ["你好", "?", "?", "股", "?"]);
+        assert_eq!(converter.to_permalink(), "ni-hao-gu");
+
+        converter.unknown_char_policy(UnknownCharPolicy::Error);
+        for result in [
+            converter.convert_checked().map(|_| ()),
+            converter.try_to_string().map(|_| ()),
+        ] {
+            assert!(matches!(result, Err(PinyinError::UnknownCharacter('，'))));
+        }
+
+        // 不返回错误的输出按 Keep 处理，不会 panic
+        let mut converter = Converter::new("中国A");
+        converter.unknown_char_policy(UnknownCharPolicy::Error);
+        assert_eq!(converter.to_string(), "zhōng guó A");
+        assert_eq!(format!("{}", converter), "zhōng guó A");
+        assert_eq!(converter.to_string_with("-"), "zhōng-guó-A");
+        assert_eq!(converter.to_permalink(), "zhong-guo-A");
+        assert_eq!(converter.convert().len(), 2);
+        assert!(converter.strict().convert().is_err());
+    }
+
+    #[test]
+    fn test_converter_on_unknown_char() {
+        let mut converter = Converter::new("寿司すし，🍣");
+        converter.heteronym_limit(1).on_unknown_char(|c, offset| {
+            let romaji = match c {
+                'す' => "su",
+                'し' => "shi",
+                _ => return None,
+            };
+            Some(format!("{}@{}", romaji, offset))
+        });
+        // 偏移是字符在输入中的位置，多次输出结果相同
+        assert_eq!(converter.to_string(), "shòu sī su@6 shi@9 ， 🍣");
+        assert_eq!(converter.to_string(), "shòu sī su@6 shi@9 ， 🍣");
+        assert_eq!(converter.to_sentence(), "shòu sī su@6 shi@9, 🍣");
+
+        converter.on_unknown_char(|c, _| match c {
+            'す' => Some("su".to_string()),
+            'し' => Some("shi".to_string()),
+            _ => None,
+        });
+        let words: Vec<_> = converter.convert().into_iter().map(|w| w.word).collect();
+        assert_eq!(words, ["寿司", "su", "shi", "，", "🍣"]);
+        assert_eq!(converter.to_abbr(), "sss");
+        assert_eq!(converter.to_permalink(), "shou-si-sushi");
+        assert_eq!(converter.to_filename(255), "shou_si_sushi");
+        assert_eq!(converter.to_hyphenated(), "shòu\u{AD}sī sushi，🍣");
+
+        converter.unknown_char_policy(UnknownCharPolicy::Skip);
+        assert_eq!(converter.to_string(), "shòu sī su shi");
+
+        // 处理函数可以带状态
+        let mut count = 0;
+        converter.on_unknown_char(move |_, _| {
+            count += 1;
+            Some(count.to_string())
+        });
+        assert_eq!(converter.to_string(), "shòu sī 1 2 3 4");
+        assert_eq!(converter.to_string(), "shòu sī 5 6 7 8");
+    }
+
+    #[test]
+    fn test_converter_apply_tone_sandhi() {
+        let mut converter = Converter::new("一样不对，一起看一看");
+        assert_eq!(converter.to_string(), "yī yàng bú duì ， yì qǐ kàn yī kàn");
+        converter.apply_tone_sandhi(true);
+        assert_eq!(converter.to_string(), "yí yàng bú duì ， yì qǐ kàn yi kàn");
+        assert_eq!(
+            Converter::new("第一不行")
+                .apply_tone_sandhi(true)
+                .to_string(),
+            "dì yī bù xíng"
+        );
+
+        let mut converter = Converter::new("我也很好");
+        converter.apply_third_tone_sandhi(true).heteronym_limit(1);
+        assert_eq!(converter.to_string(), "wó yé hén hǎo");
+        converter.apply_third_tone_sandhi(false);
+        assert_eq!(converter.to_string(), "wǒ yě hěn hǎo");
+    }
+
+    #[test]
+    fn test_converter_prefer_neutral_tone() {
+        let mut converter = Converter::new("东西、衣服");
+        assert_eq!(converter.to_string(), "dōng xī 、 yī fú");
+        converter.prefer_neutral_tone(true);
+        assert_eq!(converter.to_string(), "dōng xi 、 yī fu");
+        // 单字不受影响
+        assert_eq!(
+            Converter::new("东").prefer_neutral_tone(true).to_string(),
+            "dōng"
+        );
+    }
+
+    #[test]
+    fn test_converter_neutral_tone() {
+        let mut converter = Converter::new("妈妈");
+        converter.tone_style(ToneStyle::Number).heteronym_limit(1);
+        assert_eq!(converter.to_string(), "ma1 ma5");
+        converter.neutral_tone(NeutralTone::Zero);
+        assert_eq!(converter.to_string(), "ma1 ma0");
+        assert_eq!(converter.to_sentence(), "ma1 ma0");
+        converter.neutral_tone(NeutralTone::Omit);
+        assert_eq!(converter.to_string(), "ma1 ma");
+        // 只影响 Number 风格
+        converter.tone_style(ToneStyle::Superscript);
+        assert_eq!(converter.to_string(), "ma¹ ma⁵");
+    }
+
+    #[test]
+    fn test_converter_script() {
+        let mut converter = Converter::new("中国，女儿");
+        converter.heteronym_limit(1).script(Script::Zhuyin);
+        assert_eq!(converter.to_string(), "ㄓㄨㄥ ㄍㄨㄛˊ ， ㄋㄩˇ ㄦˊ");
+
+        converter
+            .yu_format(YuFormat::V)
+            .tone_style(ToneStyle::Number);
+        assert_eq!(converter.to_string(), "ㄓㄨㄥ ㄍㄨㄛˊ ， ㄋㄩˇ ㄦˊ");
+
+        converter
+            .script(Script::Romanization(Romanization::WadeGiles))
+            .yu_format(YuFormat::Umlaut);
+        assert_eq!(converter.to_string(), "chung1 kuo2 ， nü3 erh2");
+        assert_eq!(
+            Converter::new("重庆")
+                .script(Script::Romanization(Romanization::WadeGiles))
+                .separator("-")
+                .to_string(),
+            "ch'ung²-ch'ing⁴"
+        );
+        assert_eq!(
+            Converter::new("中国")
+                .tone_style(ToneStyle::Superscript)
+                .to_string(),
+            "zhong¹ guo²"
+        );
+        assert_eq!(
+            Converter::new("北京")
+                .script(Script::Romanization(Romanization::Palladius))
+                .tone_style(ToneStyle::None)
+                .to_string(),
+            "бэй цзин"
+        );
+        assert_eq!(
+            Converter::new("毛泽东在北京")
+                .script(Script::Katakana)
+                .to_string(),
+            "マオ ツォー トン ツァイ ペイ チン"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "jyutping")]
+    fn test_converter_jyutping() {
+        let mut converter = Converter::new("我喺香港銀行做嘢！");
+        assert_eq!(
+            converter.jyutping(),
+            "ngo5 hai2 hoeng1 gong2 ngan4 hong4 zou6 je5 ！"
+        );
+        // 人工整理的读音优先于 Unihan，其余常用字来自 kCantonese
+        assert_eq!(Converter::new("重").jyutping(), "cung4");
+        assert_eq!(Converter::new("粤语").jyutping(), "jyut6 jyu5");
+
+        converter
+            .separator("-")
+            .unknown_char_policy(UnknownCharPolicy::Skip);
+        assert_eq!(
+            converter.jyutping(),
+            "ngo5-hai2-hoeng1-gong2-ngan4-hong4-zou6-je5"
+        );
+
+        // 与 to_string 一样先切出 skip_patterns 命中的片段
+        let mut converter = Converter::new("{{行}}你好");
+        converter.skip_pattern(SkipPattern::delimited("{{", "}}"));
+        assert_eq!(converter.jyutping(), "{{行}} nei5 hou2");
+        assert_eq!(converter.to_string(), "{{行}} nǐ hǎo");
+    }
+
+    #[test]
+    fn test_converter_render() {
+        let mut converter = Converter::new("你好");
+        converter.separator("/").heteronym_limit(1);
+        assert_eq!(
+            converter.render(&|p: &str, t: Tone| format!("{}^{}", p, t)),
+            "ni^3/hao^3"
+        );
+    }
+
+    #[test]
+    fn test_converter_syllabic_nasals() {
+        // m、n、ng 标调后不再合并为同一个读音
+        assert_eq!(Converter::new("呒").to_string(), "wǔ ḿ");
+        assert_eq!(Converter::new("嗯").to_string(), "ǹ");
+    }
+
+    #[test]
+    fn test_converter_numerals() {
+        assert_eq!(
+            Converter::new("陆佰伍拾元").to_string(),
+            "liù bǎi wǔ shí yuán"
+        );
+        assert_eq!(Converter::new("四五六七").to_string(), "sì wǔ liù qī");
+    }
+
+    #[test]
+    fn test_converter_convert_iter() {
+        let input = "中国人。你好！\n重庆，七";
+        let converter = Converter::new(input);
+        let words: Vec<_> = converter.convert_iter().collect();
+        assert_eq!(words, converter.convert());
+        assert_eq!(converter.convert_iter().next().unwrap().word, "中国人");
+        assert_eq!(Converter::new("").convert_iter().count(), 0);
+
+        // 跨行的跳过片段在分段处被拆开
+        let mut converter = Converter::new("你{{a\nb}}好");
+        converter.skip_pattern(SkipPattern::delimited("{{", "}}"));
+        let words: Vec<_> = converter.convert().into_iter().map(|w| w.word).collect();
+        assert_eq!(words, ["你", "{{a\nb}}", "好"]);
+        let words: Vec<_> = converter.convert_iter().map(|w| w.word).collect();
+        assert_eq!(words, ["你", "{", "{", "a", "\n", "b", "}", "}", "好"]);
+    }
+
+    #[test]
+    fn test_converter_skip_pattern() {
+        let words: Vec<_> = Converter::new("你好{{name}}，SKU-12A号:smile:")
+            .skip_pattern(SkipPattern::delimited("{{", "}}"))
+            .skip_pattern(SkipPattern::prefix("SKU-"))
+            .skip_pattern(SkipPattern::custom(|s| {
+                s.starts_with(':').then(|| s[1..].find(':'))?.map(|i| i + 2)
+            }))
+            .convert()
+            .into_iter()
+            .map(|w| w.word)
+            .collect();
+        assert_eq!(
+            words,
+            vec!["你好", "{{name}}", "，", "SKU-12A", "号", ":smile:"]
+        );
+    }
+
+    #[test]
+    fn test_converter_user_dict() {
+        let dict = |pairs: &[(&str, &str)]| {
+            UserDict::new(
+                pairs
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect::<HashMap<_, _>>(),
+            )
+        };
+        let brands = dict(&[("长城", "zhǎng chéng"), ("长城汽车", "cháng chéng qì chē")]);
+        let names = dict(&[("长城", "cháng chéng"), ("重", "zhòng")]);
+
+        let mut converter = Converter::new("长城汽车很重，长城");
+        assert_eq!(
+            converter.to_string(),
+            "cháng chéng qì chē hěn chóng ， cháng chéng"
+        );
+        converter.user_dict(brands).user_dict(names);
+        assert_eq!(
+            converter.to_string(),
+            "cháng chéng qì chē hěn zhòng ， zhǎng chéng"
+        );
+        assert_eq!(converter.syllable_count(), 8);
+    }
+
+    #[test]
+    fn test_converter_space_latin() {
+        let mut converter = Converter::new("用 Rust 2021写C++代码，Hello World");
+        assert_eq!(
+            converter.space_latin(true).heteronym_limit(1).to_string(),
+            "yòng Rust 2021 xiě C++ dài mǎ ， Hello World"
+        );
+        assert_eq!(
+            Converter::new("用Rust写代码")
+                .space_latin(true)
+                .to_string_with("-"),
+            "yòng-Rust-xiě-xiè-dài-mǎ"
+        );
+    }
+
+    #[test]
+    fn test_converter_to_sentence() {
+        assert_eq!(
+            Converter::new("你好，世界！").to_sentence(),
+            "nǐ hǎo, shì jiè!"
+        );
+        assert_eq!(
+            Converter::new("他说：“用Rust写代码。”").to_sentence(),
+            "tā shuō: \"yòng Rust xiě dài mǎ.\""
+        );
+        assert_eq!(
+            Converter::new("（你好） 世界…").to_sentence(),
+            "(nǐ hǎo) shì jiè..."
+        );
+    }
+
+    #[test]
+    fn test_converter_annotations() {
+        assert_eq!(
+            Converter::new("// 中国七").annotations(),
+            vec![
+                Annotation {
+                    range: 3..9,
+                    reading: "zhōng guó".to_string(),
+                    candidates: vec!["zhōng guó".to_string()],
+                },
+                Annotation {
+                    range: 9..12,
+                    reading: "qī".to_string(),
+                    candidates: vec!["qī".to_string(), "qí".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_converter_dedup_readings() {
+        let mut readings = vec!["zhong", "zhong", "chong", "zhong"];
+        dedup_readings(&mut readings);
+        assert_eq!(readings, vec!["zhong", "chong"]);
+
+        assert_eq!(
+            Converter::new("中").tone_style(ToneStyle::None).to_string(),
+            "zhong"
+        );
+        assert_eq!(
+            Converter::new("中")
+                .tone_style(ToneStyle::Number)
+                .to_string(),
+            "zhong1 zhong4"
+        );
+    }
+
+    #[test]
+    fn test_converter_to_tones() {
+        let converter = Converter::new("你好，世界！");
+        assert_eq!(converter.to_tones(ToneNotation::Number), "3 3 4 4");
+        assert_eq!(converter.to_tones(ToneNotation::Contour), "214 214 51 51");
+        assert_eq!(
+            Converter::new("你好")
+                .separator("")
+                .to_tones(ToneNotation::Number),
+            "33"
+        );
+    }
+
+    #[test]
+    fn test_converter_syllable_count() {
+        let converter = Converter::new("中国人喜欢，🇨🇳七");
+        assert_eq!(converter.syllable_counts(), vec![3, 2, 0, 0, 1]);
+        assert_eq!(converter.syllable_count(), 6);
+        assert_eq!(converter.syllable_counts().len(), converter.convert().len());
+    }
+
+    #[test]
+    fn test_converter_to_t9() {
+        assert_eq!(Converter::new("你好").to_t9(), "64426");
+        assert_eq!(Converter::new("绿色").to_t9(), "5873");
+        assert_eq!(Converter::new("拨打 110，找 Tom").to_t9(), "26321109426866");
+        assert_eq!(Converter::new("，。").to_t9(), "");
+    }
+
+    #[test]
+    fn test_converter_to_shuangpin() {
+        assert_eq!(
+            Converter::new("中国").to_shuangpin(ShuangpinScheme::Xiaohe),
+            "vs go"
+        );
+        assert_eq!(
+            Converter::new("中国，你好")
+                .separator("")
+                .to_shuangpin(ShuangpinScheme::Microsoft),
+            "vsgo，nihk"
+        );
+    }
+
+    #[test]
+    fn test_converter_to_permalink_with() {
+        let converter = Converter::new("咖啡 Café，すし");
+        assert_eq!(converter.to_permalink(), "ka-fei-Caf");
+        assert_eq!(
+            converter.to_permalink_with(&(Latin, Kana)),
+            "ka-fei-Cafe-sushi"
+        );
+    }
+
+    #[test]
+    fn test_converter_to_filename() {
+        assert_eq!(
+            Converter::new("第1章：开始?").to_filename(255),
+            "di_1_zhang_kai_shi"
+        );
+        assert_eq!(
+            Converter::new("旅行 Photos/2024\\*.JPG").to_filename(255),
+            "lv_xing_photos_2024_jpg"
+        );
+        assert_eq!(
+            Converter::new("带着希望去旅行").to_filename(16),
+            "dai_zhe_xi_wang"
+        );
+        assert_eq!(Converter::new("abcdef").to_filename(4), "abcd");
+        assert_eq!(Converter::new("CON").to_filename(255), "_con");
+        assert_eq!(Converter::new("...").to_filename(255), "_");
+        assert_eq!(Converter::new("中文").to_filename(0), "");
+        assert_eq!(Converter::new("...").to_filename(0), "");
+        assert_eq!(Converter::new("CON").to_filename(3), "_co");
+        // 组合字符与预组字符得到相同的文件名
+        assert_eq!(
+            Converter::new("Cafe\u{301}").to_filename(255),
+            Converter::new("Caf\u{e9}").to_filename(255)
+        );
+        assert_eq!(Converter::new("Cafe\u{301}").to_filename(255), "caf\u{e9}");
+    }
+
+    #[test]
+    fn test_converter_break_opportunities() {
+        let converter = Converter::new("中国人喜欢西安");
+        let (text, breaks) = converter.break_opportunities();
+        assert_eq!(text, "zhōngguórén xǐhuan xī'ān");
+        let syllables: Vec<_> = breaks.iter().map(|b| &text[..*b]).collect();
+        assert_eq!(
+            syllables,
+            vec![
+                "zhōng",
+                "zhōngguó",
+                "zhōngguórén xǐ",
+                "zhōngguórén xǐhuan xī"
+            ]
+        );
+        assert_eq!(
+            converter.to_hyphenated(),
+            "zhōng\u{AD}guó\u{AD}rén xǐ\u{AD}huan xī\u{AD}'ān"
+        );
+        assert_eq!(
+            Converter::new("你好 Rust!").to_hyphenated(),
+            "nǐ\u{AD}hǎo Rust!"
+        );
+    }
+
+    #[test]
+    fn test_converter_to_pairs() {
+        assert_eq!(
+            Converter::new("重庆，七").to_pairs(),
+            vec![
+                ('重', Some(Pinyin::new("chong", Tone::Second))),
+                ('庆', Some(Pinyin::new("qing", Tone::Fourth))),
+                ('，', None),
+                ('七', Some(Pinyin::new("qi", Tone::First))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_converter_heteronym_model() {
+        let model: HeteronymModel = "七\t*\tqi1\t10\n七\t>个\tqi2\t3\n".parse().unwrap();
+        let model = Arc::new(model);
+        let words = Converter::new("七个")
+            .heteronym_model(model.clone())
+            .heteronym_limit(1)
+            .convert();
+        assert_eq!(words[0].pinyin, vec![Pinyin::new("qi", Tone::Second)]);
+        assert_eq!(
+            Converter::new("七，").heteronym_model(model).to_string(),
+            "qī qí ，"
+        );
+    }
+
+    #[test]
+    fn test_converter_ngram_model() {
+        let model: NgramModel =
+            "\\1-grams:\n-1.0\tqi1\n-1.5\tqi2\n-1.0\tge4\n\\2-grams:\n-0.1\tqi2 ge4\n"
+                .parse()
+                .unwrap();
+        let model = Arc::new(model);
+        assert_eq!(
+            Converter::new("七个")
+                .ngram_model(model.clone())
+                .heteronym_limit(1)
+                .to_string(),
+            "qí gè"
+        );
+        assert_eq!(
+            Converter::new("七，七个")
+                .ngram_model(model)
+                .heteronym_limit(1)
+                .to_string(),
+            "qī ， qí gè"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::converter::Converter;
+use crate::is_han;
+use crate::unknown::UnknownChars;
+use std::collections::HashMap;
+use std::io::BufRead;
+
+// 每个字最多保留几条例句
+const MAX_EXAMPLES: usize = 3;
+// 例句中取前后各几个字
+const CONTEXT_CHARS: usize = 5;
+
+// 评估字典对语料的覆盖情况：
+// 没有读音的汉字（次数与上下文），以及连续多个汉字全部逐字回退、没有命中任何词的片段
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CoverageReport {
+    han: usize,
+    unknown: HashMap<char, (usize, Vec<String>)>,
+    fallback: HashMap<String, usize>,
+}
+
+impl CoverageReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_text(&mut self, text: &str) {
+        let words = Converter::new(text).convert();
+        let chars: Vec<char> = text.chars().collect();
+        let mut index = 0;
+        // 当前逐字回退的连续汉字
+        let mut run = String::new();
+
+        for word in words.iter() {
+            let len = word.word.chars().count();
+            let single = len == 1 && word.word.chars().all(is_han);
+            if single && word.is_matched() {
+                run.push_str(&word.word);
+            } else {
+                self.add_fallback(&mut run);
+            }
+
+            for (i, c) in word.word.chars().enumerate().filter(|(_, c)| is_han(*c)) {
+                self.han += 1;
+                if word.is_matched() {
+                    continue;
+                }
+
+                let at = index + i;
+                let context: String = chars
+                    [at.saturating_sub(CONTEXT_CHARS)..(at + CONTEXT_CHARS + 1).min(chars.len())]
+                    .iter()
+                    .collect();
+                let (count, examples) = self.unknown.entry(c).or_default();
+                *count += 1;
+                if examples.len() < MAX_EXAMPLES && !examples.contains(&context) {
+                    examples.push(context);
+                }
+            }
+            index += len;
+        }
+        self.add_fallback(&mut run);
+    }
+
+    fn add_fallback(&mut self, run: &mut String) {
+        if run.chars().nth(1).is_some() {
+            *self.fallback.entry(run.clone()).or_insert(0) += 1;
+        }
+        run.clear();
+    }
+
+    // 逐行扫描语料，返回读入的行数
+    pub fn scan<R: BufRead>(&mut self, reader: R) -> std::io::Result<usize> {
+        let mut lines = 0;
+        for line in reader.lines() {
+            self.add_text(&line?);
+            lines += 1;
+        }
+        Ok(lines)
+    }
+
+    // 语料中的汉字总数
+    pub fn han_count(&self) -> usize {
+        self.han
+    }
+
+    // 有读音的汉字所占比例，没有汉字时为 1
+    pub fn coverage(&self) -> f64 {
+        if self.han == 0 {
+            return 1.0;
+        }
+        let unknown: usize = self.unknown.values().map(|(n, _)| n).sum();
+        (self.han - unknown) as f64 / self.han as f64
+    }
+
+    // 没有读音的汉字，按出现次数从多到少排列
+    pub fn unknown_chars(&self) -> Vec<(char, usize)> {
+        let mut entries: Vec<_> = self.unknown.iter().map(|(c, (n, _))| (*c, *n)).collect();
+        entries.sort_by(|(c1, n1), (c2, n2)| n2.cmp(n1).then(c1.cmp(c2)));
+        entries
+    }
+
+    // 没有读音的汉字出现的上下文
+    pub fn examples(&self, c: char) -> &[String] {
+        self.unknown.get(&c).map_or(&[], |(_, e)| e.as_slice())
+    }
+
+    // 只能逐字回退的连续汉字，按出现次数从多到少排列，通常是词库缺少的词
+    pub fn fallback_words(&self) -> Vec<(&str, usize)> {
+        let mut entries: Vec<_> = self
+            .fallback
+            .iter()
+            .map(|(w, n)| (w.as_str(), *n))
+            .collect();
+        entries.sort_by(|(w1, n1), (w2, n2)| n2.cmp(n1).then(w1.cmp(w2)));
+        entries
+    }
+
+    // 转为 UnknownChars，以便导出为 chars 补丁
+    pub fn to_unknown_chars(&self) -> UnknownChars {
+        let mut unknown = UnknownChars::new();
+        for (c, (count, _)) in self.unknown.iter() {
+            (0..*count).for_each(|_| unknown.record(*c));
+        }
+        unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CoverageReport;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_coverage_report() {
+        let mut report = CoverageReport::new();
+        let corpus = "中国人\u{9FEF}\n你\u{9FEF}\u{9FEF}好，\n";
+        assert_eq!(report.scan(corpus.as_bytes()).unwrap(), 2);
+
+        assert_eq!(report.han_count(), 8);
+        assert_eq!(report.unknown_chars(), vec![('\u{9FEF}', 3)]);
+        assert_eq!(
+            report.examples('\u{9FEF}'),
+            &["中国人\u{9FEF}", "你\u{9FEF}\u{9FEF}好，"]
+        );
+        assert!((report.coverage() - 5.0 / 8.0).abs() < 1e-9);
+        assert_eq!(report.to_unknown_chars().count('\u{9FEF}'), 3);
+        assert_eq!(report.fallback_words(), vec![]);
+
+        report.add_text("我看书，红桌椅");
+        report.add_text("红桌椅");
+        assert_eq!(report.fallback_words(), vec![("红桌椅", 2)]);
+    }
+}
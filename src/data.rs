@@ -0,0 +1,166 @@
+use crate::error::PinyinError;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+// 从目录读取的文件只读一次，之后与内嵌数据一样按 'static 使用
+static FILES: OnceLock<Mutex<HashMap<String, &'static str>>> = OnceLock::new();
+
+// build.rs 生成到 OUT_DIR 中的字典数据
+#[cfg(feature = "embed")]
+macro_rules! include_data {
+    ($name:literal) => {
+        include_str!(concat!(env!("OUT_DIR"), "/data/", $name))
+    };
+}
+
+// 设置运行时读取字典数据的目录，需在第一次转换前调用，目录中必须包含全部用到的数据文件
+// 未设置时读取环境变量 PINYIN_DATA_DIR，都没有时使用内嵌数据
+// 已设置过或已经读取过数据时返回错误，已加载的数据不会改变
+pub fn set_data_dir<P: Into<PathBuf>>(dir: P) -> Result<(), PinyinError> {
+    // 持有锁，避免与正在进行的第一次读取交错
+    let files = files();
+    if !files.is_empty() {
+        return Err(PinyinError::DataDir(
+            "dictionary data has already been loaded".to_string(),
+        ));
+    }
+    DATA_DIR.set(dir.into()).map_err(|dir| {
+        PinyinError::DataDir(format!("already set, cannot change to {}", dir.display()))
+    })
+}
+
+fn data_dir() -> Option<PathBuf> {
+    DATA_DIR
+        .get()
+        .cloned()
+        .or_else(|| std::env::var_os("PINYIN_DATA_DIR").map(PathBuf::from))
+}
+
+fn files() -> MutexGuard<'static, HashMap<String, &'static str>> {
+    FILES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+// name: "words_0.txt"
+pub(crate) fn try_load(name: &str) -> Result<&'static str, PinyinError> {
+    let mut files = files();
+    if let Some(contents) = files.get(name) {
+        return Ok(contents);
+    }
+
+    let contents = read(data_dir().as_deref(), name)?;
+    files.insert(name.to_string(), contents);
+    Ok(contents)
+}
+
+// 数据缺失时 panic，供不返回 Result 的转换接口使用
+pub(crate) fn load(name: &str) -> &'static str {
+    try_load(name).unwrap_or_else(|e| panic!("{}", e))
+}
+
+// 设置了目录时只读取目录中的文件，缺少的文件不会用内嵌数据补上
+pub(crate) fn read(dir: Option<&Path>, name: &str) -> Result<&'static str, PinyinError> {
+    let dir = match dir {
+        Some(dir) => dir,
+        None => return embedded(name),
+    };
+
+    let path = dir.join(name);
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => Ok(Box::leak(contents.into_boxed_str())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(PinyinError::MissingData(path.display().to_string()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(feature = "embed")]
+fn embedded(name: &str) -> Result<&'static str, PinyinError> {
+    let contents = match name {
+        "chars_0.txt" => include_data!("chars_0.txt"),
+        "chars_1.txt" => include_data!("chars_1.txt"),
+        "chars_2.txt" => include_data!("chars_2.txt"),
+        "chars_3.txt" => include_data!("chars_3.txt"),
+        "chars_4.txt" => include_data!("chars_4.txt"),
+        "chars_5.txt" => include_data!("chars_5.txt"),
+        "chars_6.txt" => include_data!("chars_6.txt"),
+        "chars_7.txt" => include_data!("chars_7.txt"),
+        "chars_8.txt" => include_data!("chars_8.txt"),
+        "chars_9.txt" => include_data!("chars_9.txt"),
+        "words_0.txt" => include_data!("words_0.txt"),
+        "words_1.txt" => include_data!("words_1.txt"),
+        "words_2.txt" => include_data!("words_2.txt"),
+        "words_3.txt" => include_data!("words_3.txt"),
+        "words_4.txt" => include_data!("words_4.txt"),
+        "words_5.txt" => include_data!("words_5.txt"),
+        "words_6.txt" => include_data!("words_6.txt"),
+        "words_7.txt" => include_data!("words_7.txt"),
+        "words_8.txt" => include_data!("words_8.txt"),
+        "words_9.txt" => include_data!("words_9.txt"),
+        "surnames.txt" => include_data!("surnames.txt"),
+        "heteronyms.txt" => include_data!("heteronyms.txt"),
+        "neutral.txt" => include_data!("neutral.txt"),
+        "frequency.txt" => include_data!("frequency.txt"),
+        #[cfg(feature = "slang")]
+        "slang.txt" => include_data!("slang.txt"),
+        #[cfg(feature = "jyutping")]
+        "jyutping.txt" => include_data!("jyutping.txt"),
+        #[cfg(feature = "strokes")]
+        "strokes.txt" => include_data!("strokes.txt"),
+        _ => return Err(PinyinError::MissingData(name.to_string())),
+    };
+    Ok(contents)
+}
+
+// 不内嵌数据时必须提供数据目录
+#[cfg(not(feature = "embed"))]
+fn embedded(name: &str) -> Result<&'static str, PinyinError> {
+    Err(PinyinError::MissingData(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read;
+    use crate::error::PinyinError;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_read() {
+        let dir = crate::temp_path("data_dir");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("custom.txt"), "重: chóng\n").unwrap();
+
+        // 只读取目录中的文件，不会退回内嵌数据
+        assert_eq!(read(Some(&dir), "custom.txt").unwrap(), "重: chóng\n");
+        assert!(matches!(
+            read(Some(&dir), "surnames.txt"),
+            Err(PinyinError::MissingData(_))
+        ));
+        #[cfg(feature = "embed")]
+        assert!(read(None, "surnames.txt").unwrap().contains("尉迟"));
+        #[cfg(not(feature = "embed"))]
+        assert!(matches!(
+            read(None, "surnames.txt"),
+            Err(PinyinError::MissingData(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // 读取过数据后不能再设置目录，也不会改变全局状态
+    #[cfg(feature = "embed")]
+    #[test]
+    fn test_set_data_dir_after_load() {
+        use super::{set_data_dir, try_load};
+
+        try_load("heteronyms.txt").unwrap();
+        assert!(matches!(
+            set_data_dir(crate::temp_path("late_data_dir")),
+            Err(PinyinError::DataDir(_))
+        ));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::loader::{Loader, SurnamesLoader, WordsLoader};
+use crate::pinyin::{parse_readings, Pinyin, ToneStyle, YuFormat};
+use crate::{SURNAMES_LOADER, WORDS_LOADER};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+static DICTIONARY: OnceLock<Dictionary<'static>> = OnceLock::new();
+
+// 按词排序的只读词典视图，用于查询与排查读音，转换时不依赖它
+#[derive(Debug, Default, Clone)]
+pub struct Dictionary<'a> {
+    entries: Vec<(&'a str, &'a str)>,
+    // 拼音 -> entries 下标，首次按拼音查询时构建
+    pinyin_index: OnceLock<HashMap<String, Vec<usize>>>,
+    // 与 entries 一一对应的解析后的拼音，查到时才解析
+    readings: OnceLock<Vec<OnceLock<Vec<Pinyin>>>>,
+}
+
+impl<'a> Dictionary<'a> {
+    pub fn new<L: Loader + ?Sized>(loader: &'a L) -> Self {
+        Self::from_entries(loader.get_chunks(1).into_iter().flatten())
+    }
+
+    // 内置的词组与姓氏，同一个词以词组数据为准
+    pub fn builtin() -> &'static Dictionary<'static> {
+        DICTIONARY.get_or_init(|| {
+            let words = WORDS_LOADER.get_or_init(WordsLoader::new);
+            let surnames = SURNAMES_LOADER.get_or_init(SurnamesLoader::new);
+            Dictionary::from_entries(
+                words
+                    .get_chunks(1)
+                    .into_iter()
+                    .chain(surnames.get_chunks(1))
+                    .flatten(),
+            )
+        })
+    }
+
+    fn from_entries<I: IntoIterator<Item = (&'a str, &'a str)>>(entries: I) -> Self {
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        // 稳定排序后去重，保留先出现的条目
+        entries.sort_by_key(|(word, _)| *word);
+        entries.dedup_by(|(w1, _), (w2, _)| w1 == w2);
+        Self {
+            entries,
+            pinyin_index: OnceLock::new(),
+            readings: OnceLock::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.entries.iter().copied()
+    }
+
+    // 只查完整的词，不分词也不逐字回退：lookup_word("重庆") -> [chóng, qìng]
+    // 单字返回它的全部候选读音
+    pub fn lookup_word(&self, word: &str) -> Option<&[Pinyin]> {
+        let i = self.entries.binary_search_by_key(&word, |(w, _)| *w).ok()?;
+        let readings = self
+            .readings
+            .get_or_init(|| (0..self.entries.len()).map(|_| OnceLock::new()).collect());
+        // 外部 Loader 的读音可能无法解析，此时视为没有读音
+        let pinyin =
+            readings[i].get_or_init(|| parse_readings(self.entries[i].1).unwrap_or_default());
+        Some(pinyin)
+    }
+
+    // 包含某个字的多字词，用来排查多音字在哪些词里有固定读音
+    // words_containing('重') -> ("重庆", "chóng qìng"), ("重要", "zhòng yào") ...
+    pub fn words_containing(&self, c: char) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.iter()
+            .filter(move |(word, _)| word.contains(c) && word.chars().nth(1).is_some())
+    }
+
+    // 按完整拼音反查词，支持 "chong2 qing4"、"chóng qìng" 与不带声调的 "chongqing"
+    pub fn words_by_pinyin(&self, query: &str) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.pinyin_index()
+            .get(&pinyin_key(query))
+            .into_iter()
+            .flatten()
+            .map(|i| self.entries[*i])
+    }
+
+    fn pinyin_index(&self) -> &HashMap<String, Vec<usize>> {
+        self.pinyin_index.get_or_init(|| {
+            let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+            for (i, (word, pinyin)) in self.entries.iter().enumerate() {
+                let syllables = match parse_readings(pinyin) {
+                    Ok(syllables) => syllables,
+                    Err(_) => continue,
+                };
+                // 单字的多个读音分别索引
+                let readings = if word.chars().nth(1).is_none() {
+                    syllables.iter().map(std::slice::from_ref).collect()
+                } else {
+                    vec![syllables.as_slice()]
+                };
+                for syllables in readings {
+                    let toned = join(syllables, ToneStyle::Number, " ");
+                    let toneless = join(syllables, ToneStyle::None, "");
+                    for key in [toned, toneless] {
+                        let indices = index.entry(key).or_default();
+                        if indices.last() != Some(&i) {
+                            indices.push(i);
+                        }
+                    }
+                }
+            }
+            index
+        })
+    }
+}
+
+fn join(syllables: &[Pinyin], style: ToneStyle, separator: &str) -> String {
+    syllables
+        .iter()
+        .map(|p| p.format_with(style, YuFormat::V))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+// 带声调（数字或声调符号）的查询按音节比较，否则忽略空格整体比较
+fn pinyin_key(query: &str) -> String {
+    let query = query.trim().to_lowercase();
+    let toned = query
+        .chars()
+        .any(|c| c.is_ascii_digit() || !(c.is_ascii() || c == 'ü'));
+    if !toned {
+        return query
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| if c == 'ü' { 'v' } else { c })
+            .collect::<String>()
+            .replace("jv", "ju")
+            .replace("qv", "qu")
+            .replace("xv", "xu")
+            .replace("yv", "yu");
+    }
+
+    let syllables: Result<Vec<_>, _> = query
+        .split_whitespace()
+        .map(|s| match s.ends_with(|c: char| c.is_ascii_digit()) {
+            true => Pinyin::from_str(s),
+            false => Pinyin::try_from_marked(s),
+        })
+        .collect();
+    match syllables {
+        Ok(syllables) => join(&syllables, ToneStyle::Number, " "),
+        // 无法解析的查询原样作为键，不会命中任何词
+        Err(_) => query,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dictionary;
+    use crate::pinyin::Pinyin;
+    use crate::tone::Tone;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_lookup_word() {
+        let dict: HashMap<String, String> = [("重庆", "chóng qìng"), ("重", "zhòng chóng")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let dictionary = Dictionary::new(&dict);
+
+        assert_eq!(
+            dictionary.lookup_word("重庆"),
+            Some(
+                &[
+                    Pinyin::new("chong", Tone::Second),
+                    Pinyin::new("qing", Tone::Fourth)
+                ][..]
+            )
+        );
+        assert_eq!(dictionary.lookup_word("重").map(|p| p.len()), Some(2));
+        assert_eq!(dictionary.lookup_word("重庆市"), None);
+        assert_eq!(dictionary.lookup_word("庆"), None);
+    }
+
+    #[test]
+    fn test_words_containing() {
+        let dict: HashMap<String, String> = [
+            ("重庆", "chóng qìng"),
+            ("重要", "zhòng yào"),
+            ("重", "zhòng"),
+            ("中国", "zhōng guó"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let dictionary = Dictionary::new(&dict);
+
+        assert_eq!(dictionary.len(), 4);
+        assert_eq!(
+            dictionary.words_containing('重').collect::<Vec<_>>(),
+            vec![("重庆", "chóng qìng"), ("重要", "zhòng yào")]
+        );
+
+        assert!(Dictionary::builtin()
+            .words_containing('重')
+            .any(|(word, pinyin)| word == "重庆" && pinyin == "chóng qìng"));
+    }
+
+    #[test]
+    fn test_words_by_pinyin() {
+        let dict: HashMap<String, String> = [
+            ("重庆", "chóng qìng"),
+            ("崇庆", "chóng qìng"),
+            ("绿", "lǜ lù"),
+            ("中国", "zhōng guó"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let dictionary = Dictionary::new(&dict);
+
+        let want = vec![("崇庆", "chóng qìng"), ("重庆", "chóng qìng")];
+        assert_eq!(
+            dictionary
+                .words_by_pinyin("chong2 qing4")
+                .collect::<Vec<_>>(),
+            want
+        );
+        assert_eq!(
+            dictionary.words_by_pinyin("chóng qìng").collect::<Vec<_>>(),
+            want
+        );
+        assert_eq!(
+            dictionary.words_by_pinyin("chongqing").collect::<Vec<_>>(),
+            want
+        );
+        assert_eq!(
+            dictionary.words_by_pinyin("Chong Qing").collect::<Vec<_>>(),
+            want
+        );
+        assert_eq!(dictionary.words_by_pinyin("chong1 qing4").count(), 0);
+
+        assert_eq!(
+            dictionary.words_by_pinyin("lv4").collect::<Vec<_>>(),
+            vec![("绿", "lǜ lù")]
+        );
+        assert_eq!(
+            dictionary.words_by_pinyin("lu").collect::<Vec<_>>(),
+            vec![("绿", "lǜ lù")]
+        );
+        assert_eq!(dictionary.words_by_pinyin("lü").count(), 1);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::loader::Loader;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+// 两份字典之间的差异，各列表均按词排序
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DictDiff {
+    // (词, 读音)
+    pub added: Vec<(String, String)>,
+    pub removed: Vec<(String, String)>,
+    // (词, 旧读音, 新读音)
+    pub changed: Vec<(String, String, String)>,
+}
+
+impl DictDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+// 比较两份字典，如内置数据与打过补丁的数据，或者两个版本的数据
+pub fn diff<A: Loader + ?Sized, B: Loader + ?Sized>(old: &A, new: &B) -> DictDiff {
+    let old = flatten(old);
+    let new = flatten(new);
+    let mut diff = DictDiff::default();
+
+    for (word, pinyin) in new.iter() {
+        match old.get(word) {
+            None => diff.added.push((word.to_string(), pinyin.to_string())),
+            Some(old_pinyin) if old_pinyin != pinyin => {
+                diff.changed
+                    .push((word.to_string(), old_pinyin.to_string(), pinyin.to_string()))
+            }
+            _ => {}
+        }
+    }
+
+    for (word, pinyin) in old.iter() {
+        if !new.contains_key(word) {
+            diff.removed.push((word.to_string(), pinyin.to_string()));
+        }
+    }
+
+    diff.added.sort();
+    diff.removed.sort();
+    diff.changed.sort();
+    diff
+}
+
+fn flatten<L: Loader + ?Sized>(loader: &L) -> HashMap<&str, &str> {
+    loader.get_chunks(1).into_iter().flatten().collect()
+}
+
+// + 新词: xīn cí
+// - 旧词: jiù cí
+// ~ 重: zhòng -> chóng
+impl Display for DictDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (word, pinyin) in self.added.iter() {
+            writeln!(f, "+ {}: {}", word, pinyin)?;
+        }
+        for (word, pinyin) in self.removed.iter() {
+            writeln!(f, "- {}: {}", word, pinyin)?;
+        }
+        for (word, old, new) in self.changed.iter() {
+            writeln!(f, "~ {}: {} -> {}", word, old, new)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    fn dict(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = dict(&[
+            ("重庆", "zhòng qìng"),
+            ("中国", "zhōng guó"),
+            ("旧词", "jiù cí"),
+        ]);
+        let new = dict(&[
+            ("重庆", "chóng qìng"),
+            ("中国", "zhōng guó"),
+            ("新词", "xīn cí"),
+        ]);
+
+        let diff = diff(&old, &new);
+        assert_eq!(
+            diff.to_string(),
+            "+ 新词: xīn cí\n- 旧词: jiù cí\n~ 重庆: zhòng qìng -> chóng qìng\n"
+        );
+        assert!(super::diff(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+use crate::converter::{Config, Converter};
+use crate::pinyin::PinyinWord;
+
+// 配置一次、反复转换的引擎，可以放在 Arc 或 static 中被多个线程共享：
+// let engine = Engine::new(config);
+// engine.convert("中国")、engine.format("你好") 不会为每段文本复制配置
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Engine {
+    config: Config,
+}
+
+impl Engine {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    // 借用引擎配置的 Converter，用于 to_permalink、annotations 等其他输出
+    pub fn converter<'a>(&'a self, text: &'a str) -> Converter<'a> {
+        Converter::borrowed(text, &self.config)
+    }
+
+    pub fn convert(&self, text: &str) -> Vec<PinyinWord> {
+        self.converter(text).convert()
+    }
+
+    // 与 Converter 的 to_string 相同
+    pub fn format(&self, text: &str) -> String {
+        self.converter(text).to_string()
+    }
+}
+
+impl From<Config> for Engine {
+    fn from(config: Config) -> Self {
+        Self::new(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Engine;
+    use crate::builder::ConverterBuilder;
+    use crate::pinyin::ToneStyle;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_engine() {
+        let engine = Arc::new(
+            ConverterBuilder::new()
+                .tone_style(ToneStyle::Number)
+                .build_engine(),
+        );
+
+        let handles: Vec<_> = ["中国", "重庆"]
+            .into_iter()
+            .map(|text| {
+                let engine = engine.clone();
+                std::thread::spawn(move || engine.format(text))
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(results, vec!["zhong1 guo2", "chong2 qing4"]);
+
+        assert_eq!(engine.convert("你好")[0].word, "你好");
+        assert_eq!(engine.converter("你好").to_permalink(), "ni-hao");
+        assert_eq!(Engine::default().format("你好"), "nǐ hǎo");
+    }
+}
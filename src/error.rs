@@ -1,7 +1,33 @@
 use thiserror::Error;
 
 #[derive(Error, Debug)]
-pub enum PingyinError {
-    #[error("parse {0} error occurred")]
-    ParseStrError(String),
+pub enum PinyinError {
+    #[error("invalid tone {got}, expected 1..=5")]
+    InvalidTone { got: u8 },
+    #[error("invalid pinyin syllable {0:?}")]
+    InvalidSyllable(String),
+    #[error("empty input")]
+    EmptyInput,
+    #[error("invalid dictionary data at line {line}: {content}")]
+    DictionaryParse { line: usize, content: String },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("unsupported encoding in {0}")]
+    Encoding(String),
+    #[error("failed to download {0}")]
+    Download(String),
+    #[error("invalid dictionary data: {0}")]
+    InvalidData(String),
+    #[error("no reading for character {0:?}")]
+    UnknownCharacter(char),
+    #[error("dictionary file {0} not found, call set_data_dir or set PINYIN_DATA_DIR")]
+    MissingData(String),
+    #[error("cannot set data directory: {0}")]
+    DataDir(String),
+    #[error("internal error: {0}")]
+    Internal(String),
 }
+
+// 旧名称，拼写有误，保留以兼容已有代码
+#[deprecated(note = "use PinyinError instead")]
+pub type PingyinError = PinyinError;
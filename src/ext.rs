@@ -0,0 +1,51 @@
+use crate::converter::Converter;
+use crate::pinyin::PinyinWord;
+
+// 直接在字符串上转换："重庆".to_pinyin() -> "chóng qìng"
+// 使用与 crate::pinyin 等快捷函数相同的默认配置，需要自定义时请使用 Converter
+pub trait ToPinyin {
+    fn to_pinyin(&self) -> String;
+
+    fn to_pinyin_words(&self) -> Vec<PinyinWord>;
+
+    fn to_pinyin_permalink(&self) -> String;
+
+    fn to_pinyin_abbr(&self) -> String;
+}
+
+impl ToPinyin for str {
+    fn to_pinyin(&self) -> String {
+        crate::pinyin(self)
+    }
+
+    fn to_pinyin_words(&self) -> Vec<PinyinWord> {
+        Converter::new(self).heteronym_limit(1).convert()
+    }
+
+    fn to_pinyin_permalink(&self) -> String {
+        crate::permalink(self)
+    }
+
+    fn to_pinyin_abbr(&self) -> String {
+        crate::abbr(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ToPinyin;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_to_pinyin() {
+        assert_eq!("重庆".to_pinyin(), "chóng qìng");
+        assert_eq!("重庆".to_pinyin_permalink(), "chong-qing");
+        assert_eq!("重庆".to_pinyin_abbr(), "cq");
+        assert_eq!(String::from("你好").to_pinyin(), "nǐ hǎo");
+
+        let words = "重庆吃".to_pinyin_words();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].to_string(), "重庆:chong2 qing4");
+        assert_eq!(words[1].to_string(), "吃:chi1");
+    }
+}
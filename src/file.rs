@@ -0,0 +1,281 @@
+use crate::{error::PinyinError, Config, Engine};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    // 已处理的字节数
+    pub processed: u64,
+    // 输入文件的总字节数
+    pub total: u64,
+}
+
+// 每次最多转换的字节数，没有换行的长文本按块转换
+const CHUNK_SIZE: usize = 64 * 1024;
+
+// 逐行转换 `path_in` 并以 UTF-8 写入 `path_out`，每处理完一行（或一块）回调一次进度
+// UTF-8 BOM 会被去掉；启用 gbk 特性时，不是合法 UTF-8 的行按 GBK 解码，超长的行按文件开头一块的编码截断
+pub fn convert_file<P, Q, F>(
+    path_in: P,
+    path_out: Q,
+    config: &Config,
+    mut progress: F,
+) -> Result<(), PinyinError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+    F: FnMut(Progress),
+{
+    let path_in = path_in.as_ref();
+    let input = File::open(path_in)?;
+    let total = input.metadata()?.len();
+    let mut reader = BufReader::with_capacity(CHUNK_SIZE, input);
+    let mut writer = BufWriter::new(File::create(path_out)?);
+
+    let mut processed = 0;
+    if reader.fill_buf()?.starts_with(BOM) {
+        reader.consume(BOM.len());
+        processed += BOM.len() as u64;
+    }
+    let encoding = Encoding::detect(reader.fill_buf()?);
+
+    let engine = Engine::new(config.clone());
+    let mut line = vec![];
+    // 上一块是否从行中间截断，以及它的输出是否以空白结尾
+    let mut cut = None;
+    // GBK 的尾字节不会是 '\n'，所以两种编码都可以安全地按行切分
+    loop {
+        let limit = CHUNK_SIZE - line.len();
+        let read = (&mut reader)
+            .take(limit as u64)
+            .read_until(b'\n', &mut line)?;
+        if line.is_empty() {
+            break;
+        }
+        // 超长的行在字符边界处截断，剩下的留给下一块
+        let whole = read < limit || line.ends_with(b"\n");
+        let end = match whole {
+            true => line.len(),
+            false => encoding.boundary(&line),
+        };
+        let rest = line.split_off(end);
+        processed += line.len() as u64;
+
+        let text = Encoding::decode_line(&line)
+            .ok_or_else(|| PinyinError::Encoding(path_in.display().to_string()))?;
+        let content = text.trim_end_matches(['\r', '\n']);
+        let output = engine.format(content);
+        // 截断处两边的拼音之间补上分隔符
+        if cut == Some(false) && !output.starts_with(char::is_whitespace) {
+            writer.write_all(config.separator.as_bytes())?;
+        }
+        writer.write_all(output.as_bytes())?;
+        writer.write_all(text[content.len()..].as_bytes())?;
+
+        cut = (!whole).then(|| output.ends_with(char::is_whitespace));
+        line = rest;
+        progress(Progress { processed, total });
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Utf8,
+    #[cfg(feature = "gbk")]
+    Gbk,
+}
+
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+impl Encoding {
+    // 不是合法 UTF-8 时按 GBK 处理（需要 gbk 特性），末尾被截断的字符不算
+    #[cfg_attr(not(feature = "gbk"), allow(unused_variables))]
+    fn detect(bytes: &[u8]) -> Self {
+        #[cfg(feature = "gbk")]
+        if std::str::from_utf8(bytes).is_err_and(|e| e.error_len().is_some()) {
+            return Self::Gbk;
+        }
+
+        Self::Utf8
+    }
+
+    // 不会落在字符中间的截断位置，优先在 ASCII 空白、标点之后截断，两种编码的多字节字符都不含这些字节
+    fn boundary(self, bytes: &[u8]) -> usize {
+        if let Some(i) = bytes.iter().rposition(|b| *b < 0x30) {
+            return i + 1;
+        }
+
+        match self {
+            Self::Utf8 => (1..=bytes.len().min(4))
+                .map(|n| bytes.len() - n)
+                .find(|i| (bytes[*i] as i8) >= -0x40)
+                .unwrap_or(bytes.len()),
+            // 单字节 00-7F，四字节的第二个字节为 30-39，其余为双字节
+            #[cfg(feature = "gbk")]
+            Self::Gbk => {
+                let mut i = 0;
+                loop {
+                    let len = match bytes[i..] {
+                        [b, ..] if b < 0x80 => 1,
+                        [_, b, ..] if (0x30..=0x39).contains(&b) => 4,
+                        _ => 2,
+                    };
+                    if i + len > bytes.len() {
+                        return i;
+                    }
+                    i += len;
+                }
+            }
+        }
+    }
+
+    // 每行单独判断编码，兼容 ASCII 开头、后面是 GBK 的混合文件
+    fn decode_line(bytes: &[u8]) -> Option<Cow<'_, str>> {
+        let text = Self::Utf8.decode(bytes);
+        #[cfg(feature = "gbk")]
+        let text = text.or_else(|| Self::Gbk.decode(bytes));
+        text
+    }
+
+    fn decode(self, bytes: &[u8]) -> Option<Cow<'_, str>> {
+        match self {
+            Self::Utf8 => std::str::from_utf8(bytes).ok().map(Cow::Borrowed),
+            #[cfg(feature = "gbk")]
+            Self::Gbk => {
+                let (text, had_errors) = encoding_rs::GBK.decode_without_bom_handling(bytes);
+                (!had_errors).then_some(text)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert_file, Progress};
+    use crate::{temp_path, Config};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_convert_file() {
+        let path_in = temp_path("convert_file_in.txt");
+        let path_out = temp_path("convert_file_out.txt");
+        std::fs::write(&path_in, "中国人\r\n吃饭\n").unwrap();
+
+        let mut reports = vec![];
+        convert_file(&path_in, &path_out, &Config::default(), |p| reports.push(p)).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path_out).unwrap(),
+            "zhōng guó rén\r\nchī fàn\n"
+        );
+        assert_eq!(
+            reports,
+            vec![
+                Progress {
+                    processed: 11,
+                    total: 18
+                },
+                Progress {
+                    processed: 18,
+                    total: 18
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_file_with_bom() {
+        let path_in = temp_path("convert_file_bom_in.txt");
+        let path_out = temp_path("convert_file_bom_out.txt");
+        std::fs::write(&path_in, "\u{FEFF}中国\n").unwrap();
+
+        convert_file(&path_in, &path_out, &Config::default(), |_| {}).unwrap();
+        assert_eq!(std::fs::read_to_string(&path_out).unwrap(), "zhōng guó\n");
+    }
+
+    #[test]
+    fn test_convert_file_without_newlines() {
+        let path_in = temp_path("convert_file_long_in.txt");
+        let path_out = temp_path("convert_file_long_out.txt");
+        // 没有换行，按块转换，截断处两边的拼音之间仍有分隔符
+        std::fs::write(&path_in, "北京".repeat(20000)).unwrap();
+
+        let mut reports = vec![];
+        convert_file(&path_in, &path_out, &Config::default(), |p| reports.push(p)).unwrap();
+        let output = std::fs::read_to_string(&path_out).unwrap();
+        assert!(output.starts_with("běi jīng běi jīng"));
+        // 截断处的字失去上下文，可能输出多个读音
+        assert!(output
+            .split(' ')
+            .all(|s| ["běi", "bèi", "jīng"].contains(&s)));
+        assert!(reports.len() > 1);
+        assert_eq!(reports.last().unwrap().processed, 120000);
+    }
+
+    #[cfg(not(feature = "gbk"))]
+    #[test]
+    fn test_convert_file_with_invalid_encoding() {
+        let path_in = temp_path("convert_file_gbk_in.txt");
+        let path_out = temp_path("convert_file_gbk_out.txt");
+        // "中国" in GBK
+        std::fs::write(&path_in, [0xD6, 0xD0, 0xB9, 0xFA]).unwrap();
+
+        let result = convert_file(&path_in, &path_out, &Config::default(), |_| {});
+        assert!(matches!(result, Err(crate::PinyinError::Encoding(_))));
+    }
+
+    #[cfg(feature = "gbk")]
+    #[test]
+    fn test_convert_file_with_gbk() {
+        let path_in = temp_path("convert_file_gbk_in.txt");
+        let path_out = temp_path("convert_file_gbk_out.txt");
+        // "中国" in GBK
+        std::fs::write(&path_in, [0xD6, 0xD0, 0xB9, 0xFA]).unwrap();
+
+        convert_file(&path_in, &path_out, &Config::default(), |_| {}).unwrap();
+        assert_eq!(std::fs::read_to_string(&path_out).unwrap(), "zhōng guó");
+    }
+
+    #[cfg(feature = "gbk")]
+    #[test]
+    fn test_convert_file_with_mixed_encoding() {
+        let path_in = temp_path("convert_file_mixed_in.txt");
+        let path_out = temp_path("convert_file_mixed_out.txt");
+        // ASCII 开头，之后是 GBK 的 "中国"
+        let mut bytes = b"title\n".to_vec();
+        bytes.extend([0xD6, 0xD0, 0xB9, 0xFA, b'\n']);
+        bytes.extend("中国".as_bytes());
+        std::fs::write(&path_in, bytes).unwrap();
+
+        convert_file(&path_in, &path_out, &Config::default(), |_| {}).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path_out).unwrap(),
+            "title\nzhōng guó\nzhōng guó"
+        );
+    }
+
+    #[cfg(feature = "gbk")]
+    #[test]
+    fn test_convert_file_gbk_without_newlines() {
+        let path_in = temp_path("convert_file_gbk_long_in.txt");
+        let path_out = temp_path("convert_file_gbk_long_out.txt");
+        // 奇数个字节的 "a" 开头，块的末尾不在 GBK 双字节的边界上
+        let mut bytes = b"a".to_vec();
+        // "北京" in GBK
+        bytes.extend([0xB1, 0xB1, 0xBE, 0xA9].repeat(20000));
+        std::fs::write(&path_in, bytes).unwrap();
+
+        convert_file(&path_in, &path_out, &Config::default(), |_| {}).unwrap();
+        let output = std::fs::read_to_string(&path_out).unwrap();
+        assert!(output.starts_with("a běi jīng běi jīng"));
+        assert!(output
+            .split(' ')
+            .skip(1)
+            .all(|s| ["běi", "bèi", "jīng"].contains(&s)));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::syllable::{split_phonological, SYLLABLES};
+
+// 模糊音规则，每条规则让两种拼写视为相同
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FuzzyRule {
+    // zh = z
+    ZhZ,
+    // ch = c
+    ChC,
+    // sh = s
+    ShS,
+    // n = l
+    NL,
+    // f = h
+    FH,
+    // r = l
+    RL,
+    // an = ang
+    AnAng,
+    // en = eng，包括零声母的 wen = weng（韵母 un = ueng）
+    EnEng,
+    // in = ing
+    InIng,
+    // ian = iang
+    IanIang,
+    // uan = uang
+    UanUang,
+    // n、l 后的 ü = u：lü = lu，其余声母后的 ü 不会与 u 混淆
+    UmlautU,
+}
+
+impl FuzzyRule {
+    // 规则涉及的声母或韵母，每对中前一个为折叠时的代表；UmlautU 与声母有关，在 fold 中单独处理
+    fn pairs(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            FuzzyRule::ZhZ => &[("z", "zh")],
+            FuzzyRule::ChC => &[("c", "ch")],
+            FuzzyRule::ShS => &[("s", "sh")],
+            FuzzyRule::NL => &[("l", "n")],
+            FuzzyRule::FH => &[("h", "f")],
+            FuzzyRule::RL => &[("l", "r")],
+            FuzzyRule::AnAng => &[("an", "ang")],
+            FuzzyRule::EnEng => &[("en", "eng"), ("un", "ueng")],
+            FuzzyRule::InIng => &[("in", "ing")],
+            FuzzyRule::IanIang => &[("ian", "iang")],
+            FuzzyRule::UanUang => &[("uan", "uang")],
+            FuzzyRule::UmlautU => &[],
+        }
+    }
+
+    fn is_initial(&self) -> bool {
+        matches!(
+            self,
+            FuzzyRule::ZhZ
+                | FuzzyRule::ChC
+                | FuzzyRule::ShS
+                | FuzzyRule::NL
+                | FuzzyRule::FH
+                | FuzzyRule::RL
+        )
+    }
+}
+
+// 一组模糊音规则，按音系拆出的声母、韵母整体替换，y、w 不算声母："yan" 的韵母为 ian
+// 默认启用输入法常见的 zh/ch/sh、n/l 与前后鼻音 an/en/in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fuzzy {
+    rules: Vec<FuzzyRule>,
+}
+
+impl Default for Fuzzy {
+    fn default() -> Self {
+        Self::new(&[
+            FuzzyRule::ZhZ,
+            FuzzyRule::ChC,
+            FuzzyRule::ShS,
+            FuzzyRule::NL,
+            FuzzyRule::AnAng,
+            FuzzyRule::EnEng,
+            FuzzyRule::InIng,
+        ])
+    }
+}
+
+impl Fuzzy {
+    pub fn new(rules: &[FuzzyRule]) -> Self {
+        Self {
+            rules: rules.to_vec(),
+        }
+    }
+
+    // fold_syllable、phonetic_key 使用的规则：除 f/h、r/l 外的全部规则，
+    // 前后鼻音都视为相同，n、l 后的 ü 与 u 也视为相同
+    pub fn phonetic() -> Self {
+        Self::new(&[
+            FuzzyRule::ZhZ,
+            FuzzyRule::ChC,
+            FuzzyRule::ShS,
+            FuzzyRule::NL,
+            FuzzyRule::AnAng,
+            FuzzyRule::EnEng,
+            FuzzyRule::InIng,
+            FuzzyRule::IanIang,
+            FuzzyRule::UanUang,
+            FuzzyRule::UmlautU,
+        ])
+    }
+
+    pub fn rules(&self) -> &[FuzzyRule] {
+        &self.rules
+    }
+
+    // 折叠为等价类中的代表，两个音节模糊相等当且仅当代表相同："zhang" -> "zan"
+    // 代表由声母与韵母拼成，不一定是合法音节（"yan" -> "ian"），只用作比较与索引的键
+    pub fn fold(&self, syllable: &str) -> String {
+        let syllable = normalize(syllable);
+        let (initial, mut fin) = split_phonological(&syllable);
+        if self.rules.contains(&FuzzyRule::UmlautU) && matches!(initial, "n" | "l") {
+            fin = fin.replacen('ü', "u", 1);
+        }
+        format!(
+            "{}{}",
+            self.fold_part(initial, true),
+            self.fold_part(&fin, false)
+        )
+    }
+
+    fn fold_part(&self, part: &str, is_initial: bool) -> String {
+        let mut part = part.to_string();
+        for rule in self.rules.iter().filter(|r| r.is_initial() == is_initial) {
+            if let Some((to, _)) = rule.pairs().iter().find(|(_, from)| part == *from) {
+                part = to.to_string();
+            }
+        }
+        part
+    }
+
+    pub fn equal(&self, a: &str, b: &str) -> bool {
+        self.fold(a) == self.fold(b)
+    }
+
+    // 与 syllable 模糊相等的全部合法音节，syllable 本身排在第一位，其余按音节表的顺序
+    // "zhan" -> ["zhan", "zan", "zang", "zhang"]
+    pub fn variants(&self, syllable: &str) -> Vec<String> {
+        let syllable = normalize(syllable);
+        let folded = self.fold(&syllable);
+
+        let mut result = vec![syllable.clone()];
+        result.extend(
+            SYLLABLES
+                .iter()
+                .filter(|s| **s != syllable && self.fold(s) == folded)
+                .map(|s| s.to_string()),
+        );
+
+        result
+    }
+}
+
+fn normalize(syllable: &str) -> String {
+    syllable.to_lowercase().replace('v', "ü")
+}
+
+// 按默认规则判断两个不带声调的音节是否模糊相等
+pub fn fuzzy_equal(a: &str, b: &str) -> bool {
+    Fuzzy::default().equal(a, b)
+}
+
+// 按默认规则列出模糊相等的合法音节
+pub fn fuzzy_variants(syllable: &str) -> Vec<String> {
+    Fuzzy::default().variants(syllable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fuzzy_equal, fuzzy_variants, Fuzzy, FuzzyRule};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_fuzzy_equal() {
+        assert!(fuzzy_equal("zhang", "zan"));
+        assert!(fuzzy_equal("shi", "si"));
+        assert!(fuzzy_equal("nan", "lang"));
+        assert!(fuzzy_equal("Xing", "xin"));
+        assert!(fuzzy_equal("lv", "nü"));
+        assert!(!fuzzy_equal("zhang", "zhong"));
+        assert!(!fuzzy_equal("fei", "hei"));
+        // ian、iang 不受 an = ang 影响，零声母的 yan、yang 也一样
+        assert!(!fuzzy_equal("xian", "xiang"));
+        assert!(!fuzzy_equal("yan", "yang"));
+        assert!(fuzzy_equal("wen", "weng"));
+        assert!(!fuzzy_equal("lü", "lu"));
+
+        let fuzzy = Fuzzy::new(&[FuzzyRule::FH, FuzzyRule::IanIang]);
+        assert!(fuzzy.equal("fei", "hei"));
+        assert!(fuzzy.equal("xian", "xiang"));
+        assert!(!fuzzy.equal("zhang", "zang"));
+
+        let fuzzy = Fuzzy::new(&[FuzzyRule::NL, FuzzyRule::RL]);
+        assert!(fuzzy.equal("ren", "nen"));
+
+        let fuzzy = Fuzzy::phonetic();
+        assert!(fuzzy.equal("yan", "yang"));
+        assert!(fuzzy.equal("wen", "weng"));
+        assert!(fuzzy.equal("nü", "lu"));
+        // 零声母的 yu、wu 不受 ü = u 影响
+        assert!(!fuzzy.equal("yu", "wu"));
+        assert!(!fuzzy.equal("yuan", "wan"));
+        assert_eq!(fuzzy.fold("zhong"), "zong");
+        assert_eq!(fuzzy.fold("ng"), "ng");
+    }
+
+    #[test]
+    fn test_fuzzy_variants() {
+        assert_eq!(fuzzy_variants("zhan"), vec!["zhan", "zan", "zang", "zhang"]);
+        assert_eq!(fuzzy_variants("lin"), vec!["lin", "ling", "nin", "ning"]);
+        assert_eq!(fuzzy_variants("xiong"), vec!["xiong"]);
+        // 不存在的音节不会出现在结果中
+        assert_eq!(fuzzy_variants("shei"), vec!["shei"]);
+        assert_eq!(
+            Fuzzy::new(&[FuzzyRule::FH]).variants("fu"),
+            vec!["fu", "hu"]
+        );
+        assert_eq!(
+            Fuzzy::new(&[FuzzyRule::IanIang]).variants("yan"),
+            vec!["yan", "yang"]
+        );
+        assert_eq!(
+            Fuzzy::phonetic().variants("nv"),
+            vec!["nü", "lu", "lü", "nu"]
+        );
+    }
+}
@@ -0,0 +1,303 @@
+// 字典数据生成流水线，build.rs 与运行时的数据更新共用这份实现
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+const CHUNKS: usize = 10;
+
+// sources 依次为原始数据与补丁，同一个字后出现的读音生效
+pub fn generate_chars(sources: &[&str], dir: &Path) -> io::Result<usize> {
+    let mut data = vec![];
+    for contents in sources {
+        data.extend(contents.lines().filter_map(parse_line));
+    }
+
+    let chunk_size = div_ceil(data.len(), CHUNKS);
+    let mut chunks = vec![String::new(); CHUNKS];
+
+    for (count, (unicode, pinyin)) in data.iter().enumerate() {
+        // unicode: "U+4E00"
+        let code_point = unicode
+            .get(2..)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok());
+
+        if let Some(chinese) = code_point.and_then(char::from_u32) {
+            chunks[count / chunk_size].push_str(&format!("{}: {}\n", chinese, pinyin));
+        }
+    }
+
+    write_chunks(dir, "chars", &chunks)?;
+    Ok(data.len())
+}
+
+// sources 依次为原始数据与补丁，同一个词先出现的读音生效
+pub fn generate_words(sources: &[&str], dir: &Path) -> io::Result<usize> {
+    let mut data = HashMap::new();
+    for contents in sources {
+        for (chinese, pinyin) in contents.lines().filter_map(parse_line) {
+            data.entry(chinese).or_insert(pinyin);
+        }
+    }
+
+    let chunk_size = div_ceil(data.len(), CHUNKS);
+    let mut chunks = vec![String::new(); CHUNKS];
+
+    let data = hashmap_to_sorted_vec(data);
+    for (count, (chinese, pinyin)) in data.iter().enumerate() {
+        chunks[count / chunk_size].push_str(&format!("{}: {}\n", chinese, pinyin));
+    }
+
+    write_chunks(dir, "words", &chunks)?;
+    Ok(data.len())
+}
+
+pub fn generate_surnames(source: &str, dir: &Path) -> io::Result<usize> {
+    let mut file = File::create(dir.join("surnames.txt"))?;
+    let mut count = 0;
+    for (chinese, pinyin) in source.lines().filter_map(parse_line) {
+        writeln!(file, "{}: {}", chinese, pinyin)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+// source: "重,好....."
+pub fn generate_heteronyms(source: &str, dir: &Path) -> io::Result<usize> {
+    let mut file = File::create(dir.join("heteronyms.txt"))?;
+    let mut count = 0;
+    for line in source.split(',').collect::<Vec<_>>().join("\n").lines() {
+        writeln!(file, "{}", line)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+// 生成按词排序的 Rust 静态数组，contents 为 data/ 中 "中: zhōng" 格式的数据，同一个词后出现的生效
+// pub static CHARS: &[(&str, &str)] = &[("〇", "líng yuán xīng"), ...];
+pub fn generate_static_table(name: &str, contents: &[&str], path: &Path) -> io::Result<usize> {
+    let mut data = HashMap::new();
+    for contents in contents {
+        data.extend(contents.lines().filter_map(parse_line));
+    }
+
+    let data = hashmap_to_sorted_vec(data);
+    let mut file = File::create(path)?;
+    writeln!(file, "pub static {}: &[(&str, &str)] = &[", name)?;
+    for (chinese, pinyin) in data.iter() {
+        writeln!(file, "    ({:?}, {:?}),", chinese, pinyin)?;
+    }
+    writeln!(file, "];")?;
+
+    Ok(data.len())
+}
+
+// 可选的词库扩展包，如 slang -> slang.txt
+pub fn generate_pack(name: &str, source: &str, dir: &Path) -> io::Result<usize> {
+    let mut data = HashMap::new();
+    for (chinese, pinyin) in source.lines().filter_map(parse_line) {
+        data.entry(chinese).or_insert(pinyin);
+    }
+
+    let data = hashmap_to_sorted_vec(data);
+    let mut file = File::create(dir.join(format!("{}.txt", name)))?;
+    for (chinese, pinyin) in data.iter() {
+        writeln!(file, "{}: {}", chinese, pinyin)?;
+    }
+
+    Ok(data.len())
+}
+
+// 粤拼数据：sources 依次为人工整理的词语与常用字读音、Unihan 的 kCantonese，同一个字先出现的读音生效
+// Unihan 的行形如 "U+4E2D\tkCantonese\tzung1"，其他字段的行被忽略，可以直接传入完整的 Unihan_Readings.txt
+pub fn generate_jyutping(sources: &[&str], dir: &Path) -> io::Result<usize> {
+    let mut data = HashMap::new();
+    for contents in sources {
+        for line in contents.lines() {
+            let entry = match line.split('\t').collect::<Vec<_>>()[..] {
+                [unicode, "kCantonese", jyutping] => unicode
+                    .strip_prefix("U+")
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .and_then(char::from_u32)
+                    .map(|c| (c.to_string(), jyutping.trim().to_string())),
+                [_, _, _] => None,
+                _ => parse_line(line),
+            };
+            if let Some((chinese, jyutping)) = entry {
+                data.entry(chinese).or_insert(jyutping);
+            }
+        }
+    }
+
+    let data = hashmap_to_sorted_vec(data);
+    let mut file = File::create(dir.join("jyutping.txt"))?;
+    for (chinese, jyutping) in data.iter() {
+        writeln!(file, "{}: {}", chinese, jyutping)?;
+    }
+
+    Ok(data.len())
+}
+
+pub fn parse_line(line: &str) -> Option<(String, String)> {
+    let parts: Vec<&str> = line.split(':').map(|s| s.trim()).collect();
+    // U+41F8: chéng tīng  # 䇸
+    // 顶证: dǐng zhèng
+    // 燕: yān
+    if parts.len() == 2 && !parts[0].starts_with('#') {
+        let chinese = parts[0].trim().to_string();
+        let pinyin = parts[1]
+            .split_whitespace()
+            .take_while(|s| !s.starts_with('#'))
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        if chinese.is_empty() || pinyin.is_empty() {
+            return None;
+        }
+
+        return Some((chinese, pinyin));
+    }
+
+    None
+}
+
+// 先删除上次生成的 name_*.txt，数据变少时不会留下过期的分块
+// 空的分块也要写出，加载时需要全部 name_0..9.txt
+fn write_chunks(dir: &Path, name: &str, chunks: &[String]) -> io::Result<()> {
+    remove_chunks(dir, name)?;
+    for (i, chunk) in chunks.iter().enumerate() {
+        File::create(dir.join(format!("{}_{}.txt", name, i)))?.write_all(chunk.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn remove_chunks(dir: &Path, name: &str) -> io::Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        let is_chunk = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|file| {
+                file.strip_prefix(name)?
+                    .strip_prefix('_')?
+                    .strip_suffix(".txt")
+            })
+            .is_some_and(|i| !i.is_empty() && i.bytes().all(|b| b.is_ascii_digit()));
+        if is_chunk {
+            std::fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn hashmap_to_sorted_vec(map: HashMap<String, String>) -> Vec<(String, String)> {
+    let mut vec: Vec<(String, String)> = map.into_iter().collect();
+    vec.sort_by(|a, b| a.0.cmp(&b.0));
+    vec
+}
+
+// `usize::div_ceil` is newer than our MSRV
+#[allow(clippy::manual_div_ceil)]
+fn div_ceil(num: usize, denom: usize) -> usize {
+    assert!(denom > 0);
+
+    (num + denom - 1) / denom
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        generate_chars, generate_jyutping, generate_static_table, generate_words, parse_line,
+    };
+    use crate::loader::load_chunks_with;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("U+41F8: chéng tīng  # 䇸"),
+            Some(("U+41F8".to_string(), "chéng tīng".to_string()))
+        );
+        assert_eq!(
+            parse_line("顶证: dǐng zhèng"),
+            Some(("顶证".to_string(), "dǐng zhèng".to_string()))
+        );
+        assert_eq!(parse_line("# version: 0.12.0"), None);
+        assert_eq!(parse_line("顶证:"), None);
+    }
+
+    #[test]
+    fn test_generate() {
+        let dir = crate::temp_path("generate");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("chars_12.txt"), "丢: diū\n").unwrap();
+        std::fs::write(dir.join("chars_extra.txt"), "丢: diū\n").unwrap();
+
+        let count = generate_chars(&["U+4E2D: zhōng zhòng  # 中", "U+4E2D: zhōng"], &dir).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("chars_0.txt")).unwrap(),
+            "中: zhōng zhòng\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("chars_1.txt")).unwrap(),
+            "中: zhōng\n"
+        );
+        assert!(!dir.join("chars_12.txt").exists());
+        assert!(dir.join("chars_extra.txt").exists());
+
+        let count = generate_words(&["中国: zhōng guó", "中国: zhōng guō"], &dir).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("words_0.txt")).unwrap(),
+            "中国: zhōng guó\n"
+        );
+
+        let unihan = "# Unihan\nU+884C\tkCantonese\thang4 hong4\nU+884C\tkMandarin\txíng\n\
+                      U+4E2D\tkCantonese\tzung1 zung3";
+        let count = generate_jyutping(&["行: hang4\n行人: hang4 jan4", unihan], &dir).unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("jyutping.txt")).unwrap(),
+            "中: zung1 zung3\n行: hang4\n行人: hang4 jan4\n"
+        );
+
+        let path = dir.join("chars.rs");
+        let count =
+            generate_static_table("CHARS", &["中: zhōng zhòng\n〇: líng", "中: zhōng"], &path)
+                .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "pub static CHARS: &[(&str, &str)] = &[\n    (\"〇\", \"líng\"),\n    (\"中\", \"zhōng\"),\n];\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_generate_and_load() {
+        let dir = crate::temp_path("generate_and_load");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // 只有一个字，其余分块为空
+        generate_chars(&["U+4E2D: zhōng zhòng"], &dir).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("chars_9.txt")).unwrap(),
+            ""
+        );
+
+        let entries =
+            load_chunks_with("chars", |file| crate::data::read(Some(&dir), file)).unwrap();
+        assert_eq!(entries, vec![("中", "zhōng zhòng")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,277 @@
+use crate::converter::Converter;
+use crate::fuzzy::Fuzzy;
+use crate::pinyin::{ToneStyle, YuFormat};
+use std::ops::Range;
+
+// 用户输入的拼音或首字母命中了候选文本中的哪些字，返回这些字在候选文本中的字节范围，用于高亮：
+// highlight("beij", "北京大学") -> Some([0..6])，highlight("bj dx", "北京大学") -> Some([0..6, 6..12])
+// 每个字可以只输入读音的开头，多音字的任一读音都可以命中；空格分开的部分按顺序分别匹配，有一部分匹配不到时返回 None
+pub fn highlight(query: &str, candidate: &str) -> Option<Vec<Range<usize>>> {
+    let options = FindOptions {
+        initials: false,
+        ..Default::default()
+    };
+    highlight_with(query, candidate, &options)
+}
+
+// 按 options 生成每个字的拼写后再匹配，如 fuzzy 为 Some 时 "zongguo" 可以高亮 "中国"
+pub fn highlight_with(
+    query: &str,
+    candidate: &str,
+    options: &FindOptions,
+) -> Option<Vec<Range<usize>>> {
+    let chars = spellings(candidate, options);
+    let mut ranges: Vec<Range<usize>> = vec![];
+    let mut from = 0;
+
+    for part in query.split_whitespace() {
+        let part: String = part
+            .chars()
+            .filter(|c| !matches!(c, '\'' | '’'))
+            .flat_map(char::to_lowercase)
+            .map(|c| if c == 'ü' { 'v' } else { c })
+            .collect();
+        // 只有隔音符号的部分不参与匹配
+        if part.is_empty() {
+            continue;
+        }
+        let (start, end) = (from..chars.len())
+            .find_map(|start| Some((start, match_from(&chars[start..], &part)? + start)))?;
+
+        let range = chars[start].0.start..chars[end - 1].0.end;
+        match ranges.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => ranges.push(range),
+        }
+        from = end;
+    }
+
+    Some(ranges)
+}
+
+// 从第一个字开始连续匹配完 query 时返回用到的字数
+fn match_from(chars: &[(Range<usize>, Vec<String>)], query: &str) -> Option<usize> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let (_, spellings) = chars.first()?;
+
+    for spelling in spellings {
+        // query 在这个字上结束：输入的是读音的开头
+        if spelling.starts_with(query) {
+            return Some(1);
+        }
+        // 这个字用掉读音的一部分或全部，优先用掉更长的部分
+        for len in (1..=spelling.len()).rev() {
+            if spelling.is_char_boundary(len) && query.starts_with(&spelling[..len]) {
+                if let Some(n) = match_from(&chars[1..], &query[len..]) {
+                    return Some(n + 1);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// find_matches 与 highlight_with 的匹配方式，默认不区分声调、每个字都可以只输入首字母
+#[derive(Debug, Clone, PartialEq)]
+pub struct FindOptions {
+    // 按模糊音匹配："zongguo" 命中 "中国"
+    pub fuzzy: Option<Fuzzy>,
+    // 查询中可以用数字标调："zhong1guo2"，没有标调的音节仍匹配任意声调
+    pub tone_sensitive: bool,
+    // 每个字可以只输入首字母或声母："zhg"、"zg"
+    pub initials: bool,
+}
+
+impl Default for FindOptions {
+    fn default() -> Self {
+        Self {
+            fuzzy: None,
+            tone_sensitive: false,
+            initials: true,
+        }
+    }
+}
+
+// 文本中所有读音与查询相符的片段，返回从左到右互不重叠的字节范围，用于高亮：
+// find_matches("中国人在中国", "zhongguo") -> [0..6, 12..18]
+// 与 highlight 不同，每个字必须输入完整的音节或首字母，查询中的空格与隔音符号会被忽略
+pub fn find_matches(haystack: &str, pinyin_query: &str) -> Vec<Range<usize>> {
+    find_matches_with(haystack, pinyin_query, &FindOptions::default())
+}
+
+pub fn find_matches_with(
+    haystack: &str,
+    pinyin_query: &str,
+    options: &FindOptions,
+) -> Vec<Range<usize>> {
+    let query: String = pinyin_query
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\'' && *c != '’')
+        .filter(|c| options.tone_sensitive || !c.is_ascii_digit())
+        .flat_map(char::to_lowercase)
+        .map(|c| if c == 'ü' { 'v' } else { c })
+        .collect();
+    if query.is_empty() {
+        return vec![];
+    }
+
+    let chars = spellings(haystack, options);
+    let mut ranges = vec![];
+    let mut start = 0;
+    while start < chars.len() {
+        match match_exact(&chars[start..], &query) {
+            Some(n) => {
+                ranges.push(chars[start].0.start..chars[start + n - 1].0.end);
+                start += n;
+            }
+            None => start += 1,
+        }
+    }
+
+    ranges
+}
+
+// 每个字的字节范围与按 options 可以匹配的全部拼写，长的排在前面，
+// 没有读音的字符用小写的原字符匹配；highlight 与 find_matches 共用
+fn spellings(haystack: &str, options: &FindOptions) -> Vec<(Range<usize>, Vec<String>)> {
+    let mut chars = vec![];
+
+    for spanned in Converter::new(haystack).convert_spanned() {
+        let word = &spanned.word;
+        let single = word.word.chars().nth(1).is_none();
+
+        for (i, (offset, c)) in word.word.char_indices().enumerate() {
+            let range = spanned.range.start + offset..spanned.range.start + offset + c.len_utf8();
+            if !word.is_matched() {
+                chars.push((range, vec![c.to_lowercase().collect()]));
+                continue;
+            }
+
+            let readings = match single {
+                true => &word.pinyin[..],
+                false => word.pinyin.get(i..=i).unwrap_or_default(),
+            };
+            let mut result: Vec<String> = vec![];
+            for p in readings {
+                let syllable = p.format_with(ToneStyle::None, YuFormat::V);
+                let variants = match &options.fuzzy {
+                    Some(fuzzy) => fuzzy
+                        .variants(&syllable)
+                        .into_iter()
+                        .map(|s| s.replace('ü', "v"))
+                        .collect(),
+                    None => vec![syllable],
+                };
+                for variant in variants {
+                    if options.tone_sensitive {
+                        result.push(format!("{}{}", variant, p.tone_number()));
+                    }
+                    if options.initials {
+                        for prefix in ["zh", "ch", "sh"] {
+                            if variant.starts_with(prefix) && variant != prefix {
+                                result.push(prefix.to_string());
+                            }
+                        }
+                        result.push(variant.chars().take(1).collect());
+                    }
+                    result.push(variant);
+                }
+            }
+            result.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+            result.dedup();
+            chars.push((range, result));
+        }
+    }
+
+    chars
+}
+
+// 从第一个字开始恰好用完整个 query 时返回用到的字数
+fn match_exact(chars: &[(Range<usize>, Vec<String>)], query: &str) -> Option<usize> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let (_, spellings) = chars.first()?;
+
+    spellings.iter().find_map(|spelling| {
+        let rest = query.strip_prefix(spelling.as_str())?;
+        Some(match_exact(&chars[1..], rest)? + 1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_matches, find_matches_with, highlight, highlight_with, FindOptions};
+    use crate::fuzzy::Fuzzy;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_highlight() {
+        assert_eq!(highlight("beij", "北京大学"), Some(vec![0..6]));
+        assert_eq!(highlight("bjdx", "北京大学"), Some(vec![0..12]));
+        assert_eq!(highlight("bj dx", "北京大学"), Some(vec![0..12]));
+        assert_eq!(highlight("daxue", "北京大学"), Some(vec![6..12]));
+        assert_eq!(highlight("bj xue", "北京大学"), Some(vec![0..6, 9..12]));
+        assert_eq!(highlight("Xi'an", "去西安"), Some(vec![3..9]));
+        assert_eq!(highlight("rust", "用Rust写"), Some(vec![3..7]));
+        assert_eq!(highlight("shanghai", "北京大学"), None);
+        // 全角字符的偏移仍指向原文
+        assert_eq!(highlight("bj", "ＡＢ北京"), Some(vec![6..12]));
+        assert_eq!(highlight("zongguo", "中国"), None);
+        // 只有隔音符号的部分被忽略
+        assert_eq!(highlight("'", "北京"), Some(vec![]));
+        assert_eq!(highlight("bj '", "北京"), Some(vec![0..6]));
+        assert_eq!(highlight("b ' j", "北京"), Some(vec![0..6]));
+        assert_eq!(highlight("Xi’an", "去西安"), Some(vec![3..9]));
+
+        let options = FindOptions {
+            fuzzy: Some(Fuzzy::default()),
+            ..Default::default()
+        };
+        assert_eq!(
+            highlight_with("zongguo", "中国", &options),
+            Some(vec![0..6])
+        );
+        assert_eq!(highlight_with("zg", "中国", &options), Some(vec![0..6]));
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_find_matches() {
+        assert_eq!(find_matches("中国人在中国", "zhongguo"), vec![0..6, 12..18]);
+        assert_eq!(find_matches("中国人在中国", "zg"), vec![0..6, 12..18]);
+        assert_eq!(find_matches("中国人在中国", "zhong guo ren"), vec![0..9]);
+        assert_eq!(find_matches("去西安", "Xi'an"), vec![3..9]);
+        assert_eq!(find_matches("用Rust写", "rustxie"), vec![3..10]);
+        assert_eq!(find_matches("中国人", "zhon"), vec![]);
+        assert_eq!(find_matches("中国人", ""), vec![]);
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_find_matches_with() {
+        let options = FindOptions {
+            fuzzy: Some(Fuzzy::default()),
+            ..Default::default()
+        };
+        assert_eq!(find_matches_with("中国", "zongguo", &options), vec![0..6]);
+        assert_eq!(find_matches("中国", "zongguo"), vec![]);
+
+        let options = FindOptions {
+            tone_sensitive: true,
+            initials: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            find_matches_with("中国", "zhong1guo2", &options),
+            vec![0..6]
+        );
+        assert_eq!(find_matches_with("中国", "zhong1guo", &options), vec![0..6]);
+        assert_eq!(find_matches_with("中国", "zhong4guo2", &options), vec![]);
+        assert_eq!(find_matches_with("中国", "zg", &options), vec![]);
+    }
+}
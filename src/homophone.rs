@@ -0,0 +1,75 @@
+use crate::char_table;
+use crate::pinyin::{parse_readings, Pinyin};
+use crate::tone::Tone;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// 不带声调的音节 -> 读这个音的字及声调，按码位排列
+static INDEX: OnceLock<HashMap<String, Vec<(char, Tone)>>> = OnceLock::new();
+
+pub(crate) fn index() -> &'static HashMap<String, Vec<(char, Tone)>> {
+    INDEX.get_or_init(|| {
+        let mut index: HashMap<String, Vec<(char, Tone)>> = HashMap::new();
+        for (c, readings) in char_table().iter() {
+            for p in parse_readings(readings).into_iter().flatten() {
+                index.entry(p.pinyin).or_default().push((c, p.tone));
+            }
+        }
+        index
+    })
+}
+
+// 与 ch 同音的字，多音字的每个读音都算；tone_sensitive 为 false 时不区分声调
+// 基本区（U+4E00..=U+9FFF）的字排在扩展区之前，不含 ch 本身
+pub fn homophones(ch: char, tone_sensitive: bool) -> Vec<char> {
+    let readings: Vec<Pinyin> = match char_table().get(ch) {
+        Some(readings) => parse_readings(readings).unwrap_or_default(),
+        None => return vec![],
+    };
+
+    let mut result: Vec<char> = readings
+        .iter()
+        .filter_map(|p| Some((p, index().get(&p.pinyin)?)))
+        .flat_map(|(p, chars)| {
+            chars
+                .iter()
+                .filter(move |(_, tone)| !tone_sensitive || *tone == p.tone)
+                .map(|(c, _)| *c)
+        })
+        .filter(|c| *c != ch)
+        .collect();
+    result.sort_by_key(|c| (!('\u{4E00}'..='\u{9FFF}').contains(c), *c));
+    result.dedup();
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::homophones;
+
+    #[test]
+    fn test_homophones() {
+        let same_tone = homophones('中', true);
+        assert!(same_tone.contains(&'钟'));
+        assert!(same_tone.contains(&'重'));
+        assert!(!same_tone.contains(&'中'));
+        assert!(!same_tone.contains(&'肿'));
+
+        let any_tone = homophones('中', false);
+        assert!(any_tone.contains(&'肿'));
+        assert!(any_tone.len() > same_tone.len());
+
+        // 基本区在前
+        let first_ext = any_tone
+            .iter()
+            .position(|c| *c < '\u{4E00}' || *c > '\u{9FFF}');
+        if let Some(i) = first_ext {
+            assert!(any_tone[i..]
+                .iter()
+                .all(|c| *c < '\u{4E00}' || *c > '\u{9FFF}'));
+        }
+
+        assert!(homophones('a', false).is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+use crate::converter::{Config, Converter};
+use crate::is_han;
+
+// 内容不是普通文本的元素，原样跳过
+const RAW_TEXT_TAGS: [&str; 4] = ["script", "style", "textarea", "title"];
+// 已经有注音的元素不再重复标注
+const RUBY_TAGS: [&str; 3] = ["ruby", "rt", "rp"];
+
+// 为 HTML 片段中的汉字加上 <ruby> 注音，标签、属性、注释以及 script、style 的内容保持不变
+// "<p title=\"中国\">中国</p>" -> "<p title=\"中国\"><ruby>中<rt>zhōng</rt>国<rt>guó</rt></ruby></p>"
+pub fn ruby_html(html: &str, config: &Config) -> String {
+    let mut result = String::with_capacity(html.len() * 2);
+    let mut rest = html;
+    let mut ruby_depth = 0usize;
+
+    while !rest.is_empty() {
+        let text_end = markup_start(rest);
+        let (text, markup) = rest.split_at(text_end);
+        match ruby_depth {
+            0 => annotate_text(text, config, &mut result),
+            _ => result.push_str(text),
+        }
+        if markup.is_empty() {
+            break;
+        }
+
+        if markup.starts_with("<!--") {
+            let end = markup.find("-->").map_or(markup.len(), |i| i + 3);
+            result.push_str(&markup[..end]);
+            rest = &markup[end..];
+            continue;
+        }
+
+        let end = tag_end(markup);
+        let tag = &markup[..end];
+        result.push_str(tag);
+        rest = &markup[end..];
+
+        let (name, closing) = tag_name(tag);
+        if RUBY_TAGS.contains(&name.as_str()) && !tag.ends_with("/>") {
+            ruby_depth = match closing {
+                true => ruby_depth.saturating_sub(1),
+                false => ruby_depth + 1,
+            };
+        } else if !closing && RAW_TEXT_TAGS.contains(&name.as_str()) {
+            // 一直跳到对应的结束标签
+            let end = rest
+                .to_ascii_lowercase()
+                .find(&format!("</{}", name))
+                .unwrap_or(rest.len());
+            result.push_str(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+
+    result
+}
+
+// 第一个标签或注释开始的位置；"<" 后面不是字母、"/"、"!" 或 "?" 时只是普通文本，如 "1 < 2"
+fn markup_start(html: &str) -> usize {
+    html.match_indices('<')
+        .map(|(i, _)| i)
+        .find(|&i| {
+            html[i + 1..]
+                .starts_with(|c: char| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'))
+        })
+        .unwrap_or(html.len())
+}
+
+// 标签结束的位置（含 '>'），引号中的 '>' 不算
+fn tag_end(markup: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in markup.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    markup.len()
+}
+
+// "<RUBY class=x>" -> ("ruby", false)，"</rt>" -> ("rt", true)
+fn tag_name(tag: &str) -> (String, bool) {
+    let tag = tag.trim_start_matches('<');
+    let (closing, tag) = match tag.strip_prefix('/') {
+        Some(tag) => (true, tag),
+        None => (false, tag),
+    };
+    let name = tag
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    (name, closing)
+}
+
+fn annotate_text(text: &str, config: &Config, result: &mut String) {
+    if !text.chars().any(is_han) {
+        result.push_str(text);
+        return;
+    }
+
+    for word in Converter::with_config(text, config.clone()).convert() {
+        let syllables = word.syllables();
+        if !word.word.chars().all(is_han) || syllables.len() != word.word.chars().count() {
+            result.push_str(&word.word);
+            continue;
+        }
+
+        result.push_str("<ruby>");
+        for (c, p) in word.word.chars().zip(syllables) {
+            result.push(c);
+            result.push_str("<rt>");
+            result.push_str(&config.format_pinyin(p));
+            result.push_str("</rt>");
+        }
+        result.push_str("</ruby>");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ruby_html;
+    use crate::converter::Config;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ruby_html() {
+        let config = Config::default();
+        assert_eq!(
+            ruby_html("<p title=\"中国>\">中国 &amp; A</p>", &config),
+            "<p title=\"中国>\"><ruby>中<rt>zhōng</rt>国<rt>guó</rt></ruby> &amp; A</p>"
+        );
+        assert_eq!(
+            ruby_html("<!-- 中 --><SCRIPT>var s = \"中\";</script>", &config),
+            "<!-- 中 --><SCRIPT>var s = \"中\";</script>"
+        );
+        assert_eq!(
+            ruby_html("<ruby>汉<rt>hàn</rt></ruby>字<br/>", &config),
+            "<ruby>汉<rt>hàn</rt></ruby><ruby>字<rt>zì</rt></ruby><br/>"
+        );
+        // 不构成标签的 "<" 按文本处理，后面的汉字照常标注
+        assert_eq!(
+            ruby_html("<p>1 < 2 中国</p>", &config),
+            "<p>1 < 2 <ruby>中<rt>zhōng</rt>国<rt>guó</rt></ruby></p>"
+        );
+        assert_eq!(
+            ruby_html("中<国", &config),
+            "<ruby>中<rt>zhōng</rt></ruby><<ruby>国<rt>guó</rt></ruby>"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use crate::converter::Converter;
+use crate::data;
+use crate::homophone;
+use crate::pinyin::{ToneStyle, YuFormat};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// 字词 -> 近似词频
+static FREQUENCIES: OnceLock<HashMap<&'static str, u32>> = OnceLock::new();
+// 词频表中的词语及其不带声调的音节
+static WORDS: OnceLock<Vec<(&'static str, Vec<String>)>> = OnceLock::new();
+
+fn frequencies() -> &'static HashMap<&'static str, u32> {
+    FREQUENCIES.get_or_init(|| {
+        data::load("frequency.txt")
+            .lines()
+            .filter_map(|line| {
+                let (word, count) = line.split_once(':')?;
+                Some((word.trim(), count.trim().parse().ok()?))
+            })
+            .collect()
+    })
+}
+
+fn words() -> &'static [(&'static str, Vec<String>)] {
+    WORDS.get_or_init(|| {
+        frequencies()
+            .keys()
+            .filter(|word| word.chars().nth(1).is_some())
+            .map(|word| {
+                let syllables = Converter::new(word)
+                    .convert()
+                    .iter()
+                    .flat_map(|w| w.syllables().to_vec())
+                    .map(|p| p.format_with(ToneStyle::None, YuFormat::V))
+                    .collect();
+                (*word, syllables)
+            })
+            .collect()
+    })
+}
+
+// 输入法候选：按拼音或简拼给出字词及得分，得分高的在前
+// candidates("nihao") -> [("你好", 1.0 倍词频), ...]，candidates("nh") 同样给出 "你好"
+// 得分为词频与表中最高词频之比，用到简拼或只输入了最后一个音节开头的候选得分减半
+// 词语只来自词频表，单字来自全部字表，不在词频表中的单字得分为 0
+pub fn candidates(input: &str) -> Vec<(String, f32)> {
+    let input: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\'')
+        .flat_map(char::to_lowercase)
+        .map(|c| if c == 'ü' { 'v' } else { c })
+        .collect();
+    if input.is_empty() {
+        return vec![];
+    }
+
+    let frequencies = frequencies();
+    let max = frequencies.values().copied().max().unwrap_or(1) as f32;
+    let score = |word: &str, full: bool| {
+        let frequency = frequencies.get(word).copied().unwrap_or(0) as f32 / max;
+        match full {
+            true => frequency,
+            false => frequency / 2.0,
+        }
+    };
+
+    let mut scores: HashMap<String, f32> = HashMap::new();
+    let mut add = |word: String, score: f32| {
+        let entry = scores.entry(word).or_insert(score);
+        *entry = entry.max(score);
+    };
+
+    for (word, syllables) in words() {
+        if let Some(full) = match_syllables(syllables, &input) {
+            add(word.to_string(), score(word, full));
+        }
+    }
+    for (syllable, chars) in homophone::index() {
+        let syllable = syllable.replace('ü', "v");
+        if !syllable.starts_with(&input) {
+            continue;
+        }
+        for (c, _) in chars {
+            let mut buf = [0; 4];
+            let word: &str = c.encode_utf8(&mut buf);
+            add(word.to_string(), score(word, syllable == input));
+        }
+    }
+
+    let mut result: Vec<(String, f32)> = scores.into_iter().collect();
+    result.sort_by(|(a, x), (b, y)| {
+        y.total_cmp(x)
+            .then_with(|| is_extension(a).cmp(&is_extension(b)))
+            .then_with(|| a.cmp(b))
+    });
+
+    result
+}
+
+// 基本区以外的字排在后面
+fn is_extension(word: &str) -> bool {
+    word.chars()
+        .any(|c| !('\u{4E00}'..='\u{9FFF}').contains(&c))
+}
+
+// 每个音节输入完整拼写、首字母或 zh/ch/sh，最后一个音节可以只输入开头
+// 能用完 input 时返回是否全部为完整拼写
+fn match_syllables(syllables: &[String], input: &str) -> Option<bool> {
+    let (syllable, rest) = syllables.split_first()?;
+    if rest.is_empty() {
+        return match (input == syllable, !input.is_empty()) {
+            (true, _) => Some(true),
+            (false, true) if syllable.starts_with(input) => Some(false),
+            _ => None,
+        };
+    }
+
+    if let Some(full) = input
+        .strip_prefix(syllable.as_str())
+        .and_then(|remain| match_syllables(rest, remain))
+    {
+        return Some(full);
+    }
+    let mut initials = vec![];
+    for prefix in ["zh", "ch", "sh"] {
+        if syllable.starts_with(prefix) {
+            initials.push(prefix);
+        }
+    }
+    initials.push(syllable.get(..1)?);
+
+    initials.into_iter().find_map(|initial| {
+        let remain = input.strip_prefix(initial)?;
+        match_syllables(rest, remain).map(|_| false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{candidates, match_syllables};
+    use pretty_assertions::assert_eq;
+
+    fn words(input: &str, n: usize) -> Vec<String> {
+        candidates(input)
+            .into_iter()
+            .take(n)
+            .map(|(word, _)| word)
+            .collect()
+    }
+
+    #[test]
+    fn test_candidates() {
+        assert_eq!(words("nihao", 1), vec!["你好"]);
+        assert_eq!(words("ni'hao", 1), vec!["你好"]);
+        assert_eq!(words("nh", 1), vec!["你好"]);
+        assert_eq!(words("zhongguo", 1), vec!["中国"]);
+        assert_eq!(words("zg", 2), vec!["这个", "中国"]);
+        assert_eq!(words("de", 1), vec!["的"]);
+        assert_eq!(words("shi", 1), vec!["是"]);
+        assert!(candidates("").is_empty());
+        assert!(candidates("xyz").is_empty());
+
+        let result = candidates("ni");
+        assert_eq!(result[0].0, "你");
+        assert!(result.windows(2).all(|w| w[0].1 >= w[1].1));
+        assert!(!result.iter().any(|(word, _)| word == "你们"));
+        assert_eq!(words("nim", 1), vec!["你们"]);
+    }
+
+    #[test]
+    fn test_match_syllables() {
+        let syllables = vec!["zhong".to_string(), "guo".to_string()];
+        assert_eq!(match_syllables(&syllables, "zhongguo"), Some(true));
+        assert_eq!(match_syllables(&syllables, "zhongg"), Some(false));
+        assert_eq!(match_syllables(&syllables, "zhg"), Some(false));
+        assert_eq!(match_syllables(&syllables, "zg"), Some(false));
+        assert_eq!(match_syllables(&syllables, "zhong"), None);
+        assert_eq!(match_syllables(&syllables, "zhongguoren"), None);
+    }
+}
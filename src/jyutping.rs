@@ -0,0 +1,57 @@
+use crate::loader::JyutpingLoader;
+use crate::matcher::Matcher;
+use std::sync::OnceLock;
+
+static LOADER: OnceLock<JyutpingLoader> = OnceLock::new();
+static MATCHER: OnceLock<Matcher> = OnceLock::new();
+
+// 按粤语词库切分："香港银行" -> [("香港", "hoeng1 gong2"), ("银行", "ngan4 hong4")]
+// 单字取第一个读音，没有读音的字符单独成段且为 None
+pub(crate) fn segment(input: &str) -> Vec<(&str, Option<&'static str>)> {
+    let loader = LOADER.get_or_init(JyutpingLoader::new);
+    let matcher = MATCHER.get_or_init(|| Matcher::new(loader));
+
+    let mut result = vec![];
+    let mut last = 0;
+    for m in matcher.find_iter(input) {
+        segment_chars(&input[last..m.start], loader, &mut result);
+        result.push((m.word, Some(m.pinyin)));
+        last = m.end;
+    }
+    segment_chars(&input[last..], loader, &mut result);
+
+    result
+}
+
+fn segment_chars<'t>(
+    text: &'t str,
+    loader: &JyutpingLoader,
+    result: &mut Vec<(&'t str, Option<&'static str>)>,
+) {
+    for (i, c) in text.char_indices() {
+        let jyutping = loader
+            .get(c)
+            .and_then(|readings| readings.split(' ').next());
+        result.push((&text[i..i + c.len_utf8()], jyutping));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::segment;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_segment() {
+        assert_eq!(
+            segment("香港银行，行人"),
+            vec![
+                ("香港", Some("hoeng1 gong2")),
+                ("银行", Some("ngan4 hong4")),
+                ("，", None),
+                ("行人", Some("hang4 jan4")),
+            ]
+        );
+        assert_eq!(segment("重A"), vec![("重", Some("cung4")), ("A", None)]);
+    }
+}
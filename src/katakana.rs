@@ -0,0 +1,193 @@
+// 拼音音节的片假名转写，用于在日文中标注中国人名、地名：
+// 按新闻报道的惯例不区分送气与不送气（b、p 都写作パ行，zh、ch、j、q 都写作チ），单元音韵母写作长音
+// "zhong" -> "チョン"，"xi" -> "シー"，"mao" -> "マオ"
+
+// 声母与ア、イ、ウ、エ、オ段的假名
+const INITIALS: [(&str, [&str; 5]); 22] = [
+    ("zh", ["チャ", "チ", "チュ", "チェ", "チョ"]),
+    ("ch", ["チャ", "チ", "チュ", "チェ", "チョ"]),
+    ("sh", ["シャ", "シ", "シュ", "シェ", "ショ"]),
+    ("b", ["パ", "ピ", "プ", "ペ", "ポ"]),
+    ("p", ["パ", "ピ", "プ", "ペ", "ポ"]),
+    ("m", ["マ", "ミ", "ム", "メ", "モ"]),
+    ("f", ["ファ", "フィ", "フ", "フェ", "フォ"]),
+    ("d", ["タ", "ティ", "トゥ", "テ", "ト"]),
+    ("t", ["タ", "ティ", "トゥ", "テ", "ト"]),
+    ("n", ["ナ", "ニ", "ヌ", "ネ", "ノ"]),
+    ("l", ["ラ", "リ", "ル", "レ", "ロ"]),
+    ("g", ["カ", "キ", "ク", "ケ", "コ"]),
+    ("k", ["カ", "キ", "ク", "ケ", "コ"]),
+    ("h", ["ハ", "ヒ", "フ", "ヘ", "ホ"]),
+    ("j", ["チャ", "チ", "チュ", "チェ", "チョ"]),
+    ("q", ["チャ", "チ", "チュ", "チェ", "チョ"]),
+    ("x", ["シャ", "シ", "シュ", "シェ", "ショ"]),
+    ("r", ["ラ", "リ", "ル", "レ", "ロ"]),
+    ("z", ["ツァ", "ツィ", "ツ", "ツェ", "ツォ"]),
+    ("c", ["ツァ", "ツィ", "ツ", "ツェ", "ツォ"]),
+    ("s", ["サ", "スィ", "ス", "セ", "ソ"]),
+    ("", ["ア", "イ", "ウ", "エ", "オ"]),
+];
+
+// 韵母：取声母的哪一段，后面再接什么
+const FINALS: [(&str, usize, &str); 34] = [
+    ("a", 0, "ー"),
+    ("o", 4, "ー"),
+    ("e", 4, "ー"),
+    ("ê", 3, "ー"),
+    ("ai", 0, "イ"),
+    ("ei", 3, "イ"),
+    ("ao", 0, "オ"),
+    ("ou", 4, "ウ"),
+    ("an", 0, "ン"),
+    ("en", 3, "ン"),
+    ("ang", 0, "ン"),
+    ("eng", 4, "ン"),
+    ("ong", 4, "ン"),
+    ("i", 1, "ー"),
+    ("ia", 1, "ア"),
+    ("ie", 1, "エ"),
+    ("iao", 1, "アオ"),
+    ("iu", 1, "ウ"),
+    ("ian", 1, "エン"),
+    ("in", 1, "ン"),
+    ("iang", 1, "アン"),
+    ("ing", 1, "ン"),
+    ("iong", 1, "ョン"),
+    ("u", 2, "ー"),
+    ("ua", 2, "ア"),
+    ("uo", 2, "オ"),
+    ("uai", 2, "アイ"),
+    ("ui", 2, "イ"),
+    ("uan", 2, "アン"),
+    ("un", 2, "ン"),
+    ("uang", 2, "アン"),
+    ("ü", 1, "ュイ"),
+    ("üe", 1, "ュエ"),
+    ("üan", 1, "ュアン"),
+];
+
+// j、q、x 与 i、ü 拼合为拗音：jia -> チャ，xue -> シュエ
+const PALATAL_FINALS: [(&str, usize, &str); 14] = [
+    ("i", 1, "ー"),
+    ("ia", 0, ""),
+    ("ie", 3, ""),
+    ("iao", 0, "オ"),
+    ("iu", 1, "ウ"),
+    ("ian", 3, "ン"),
+    ("in", 1, "ン"),
+    ("iang", 0, "ン"),
+    ("ing", 1, "ン"),
+    ("iong", 4, "ン"),
+    ("ü", 2, "ー"),
+    ("üe", 2, "エ"),
+    ("üan", 2, "アン"),
+    ("ün", 2, "ン"),
+];
+
+// 零声母与整体认读的音节
+const SYLLABLES: [(&str, &str); 31] = [
+    ("zhi", "チー"),
+    ("chi", "チー"),
+    ("shi", "シー"),
+    ("ri", "リー"),
+    ("zi", "ツー"),
+    ("ci", "ツー"),
+    ("si", "スー"),
+    ("er", "アル"),
+    ("yi", "イー"),
+    ("ya", "ヤー"),
+    ("yao", "ヤオ"),
+    ("ye", "イエ"),
+    ("you", "ヨウ"),
+    ("yan", "イエン"),
+    ("yin", "イン"),
+    ("yang", "ヤン"),
+    ("ying", "イン"),
+    ("yong", "ヨン"),
+    ("yu", "ユー"),
+    ("yue", "ユエ"),
+    ("yuan", "ユアン"),
+    ("yun", "ユン"),
+    ("wu", "ウー"),
+    ("wa", "ワー"),
+    ("wo", "ウォー"),
+    ("wai", "ワイ"),
+    ("wei", "ウェイ"),
+    ("wan", "ワン"),
+    ("wen", "ウェン"),
+    ("wang", "ワン"),
+    ("weng", "ウォン"),
+];
+
+// 不带声调的拼音音节转为片假名，pinyin 中的 ü 可以写作 v；叹词 ng、hm 等返回 None
+pub fn to_katakana(pinyin: &str) -> Option<String> {
+    let pinyin = pinyin.to_lowercase().replace('v', "ü");
+    if let Some((_, kana)) = SYLLABLES.iter().find(|(p, _)| *p == pinyin) {
+        return Some(kana.to_string());
+    }
+
+    let (initial, kana) = INITIALS.iter().find(|(p, _)| pinyin.starts_with(p))?;
+    let fin = &pinyin[initial.len()..];
+    let (fin, finals) = match (*initial, fin.strip_prefix('u')) {
+        ("j" | "q" | "x", Some(rest)) => (format!("ü{}", rest), &PALATAL_FINALS[..]),
+        ("j" | "q" | "x", None) => (fin.to_string(), &PALATAL_FINALS[..]),
+        _ => (fin.to_string(), &FINALS[..]),
+    };
+    let (_, row, tail) = finals.iter().find(|(f, _, _)| *f == fin)?;
+
+    Some(format!("{}{}", kana[*row], tail))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_katakana;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_to_katakana() {
+        let cases = [
+            ("zhong", "チョン"),
+            ("guo", "クオ"),
+            ("bei", "ペイ"),
+            ("jing", "チン"),
+            ("xi", "シー"),
+            ("jin", "チン"),
+            ("ping", "ピン"),
+            ("mao", "マオ"),
+            ("ze", "ツォー"),
+            ("dong", "トン"),
+            ("deng", "トン"),
+            ("xiao", "シャオ"),
+            ("jiang", "チャン"),
+            ("xue", "シュエ"),
+            ("xu", "シュー"),
+            ("quan", "チュアン"),
+            ("xiong", "ション"),
+            ("li", "リー"),
+            ("liu", "リウ"),
+            ("lian", "リエン"),
+            ("nü", "ニュイ"),
+            ("lv", "リュイ"),
+            ("zhang", "チャン"),
+            ("chen", "チェン"),
+            ("shang", "シャン"),
+            ("hai", "ハイ"),
+            ("ren", "レン"),
+            ("min", "ミン"),
+            ("shi", "シー"),
+            ("zi", "ツー"),
+            ("wang", "ワン"),
+            ("yi", "イー"),
+            ("an", "アン"),
+            ("ou", "オウ"),
+            ("er", "アル"),
+            ("huang", "フアン"),
+            ("duo", "トゥオ"),
+        ];
+        for (pinyin, want) in cases {
+            assert_eq!(to_katakana(pinyin).as_deref(), Some(want), "{}", pinyin);
+        }
+        assert_eq!(to_katakana("ng"), None);
+        assert_eq!(to_katakana("hm"), None);
+    }
+}
@@ -1,32 +1,238 @@
+mod abbreviation;
+mod analyzer;
+mod builder;
+mod collation;
+mod converter;
+mod coverage;
+mod data;
+mod dictionary;
+mod diff;
+mod engine;
 mod error;
+mod ext;
+mod file;
+mod fuzzy;
+mod generator;
+mod highlight;
+mod homophone;
+#[cfg(feature = "html")]
+mod html;
+mod ime;
+#[cfg(feature = "jyutping")]
+mod jyutping;
+mod katakana;
 mod loader;
+mod markdown;
 mod matcher;
+mod model;
+mod ngram;
+mod numeral;
+mod phonetic;
+mod pingze;
 mod pinyin;
-use loader::{CharsLoader, SurnamesLoader, WordsLoader};
-use matcher::Matcher;
+mod romanization;
+mod sandhi;
+mod shuangpin;
+mod skip;
+mod slug;
+mod stats;
+mod store;
+mod syllable;
+mod table;
+#[cfg(feature = "tantivy")]
+mod tokenizer;
+mod tone;
+mod translit;
+mod unknown;
+#[cfg(feature = "net")]
+mod updater;
+mod verbatim;
+mod zhuyin;
+pub use abbreviation::{abbreviation_keys, matches_abbreviation};
+pub use analyzer::{analyze, analyze_with, AnalyzerOptions, Token};
+pub use builder::ConverterBuilder;
+#[cfg(feature = "strokes")]
+pub use collation::stroke_count;
+pub use collation::{pinyin_cmp, pinyin_cmp_with, sort_key, sort_key_with, Tiebreak};
+pub use converter::{
+    Annotation, Config, ConvertIter, Converter, Heteronym, LetterCase, Script, SpannedWord, Strict,
+    ToneNotation, UnknownCharPolicy,
+};
+pub use coverage::CoverageReport;
+pub use data::set_data_dir;
+pub use dictionary::Dictionary;
+pub use diff::{diff, DictDiff};
+pub use engine::Engine;
+#[allow(deprecated)]
+pub use error::{PingyinError, PinyinError};
+pub use ext::ToPinyin;
+pub use file::{convert_file, Progress};
+pub use fuzzy::{fuzzy_equal, fuzzy_variants, Fuzzy, FuzzyRule};
+pub use generator::{
+    generate_chars, generate_heteronyms, generate_jyutping, generate_pack, generate_static_table,
+    generate_surnames, generate_words, parse_line,
+};
+pub use highlight::{find_matches, find_matches_with, highlight, highlight_with, FindOptions};
+pub use homophone::homophones;
+#[cfg(feature = "html")]
+pub use html::ruby_html;
+pub use ime::candidates;
+pub use katakana::to_katakana;
+#[cfg(feature = "jyutping")]
+pub use loader::JyutpingLoader;
+#[cfg(feature = "slang")]
+pub use loader::SlangLoader;
+pub use loader::{CharsLoader, FileLoader, Loader, NeutralToneLoader, SurnamesLoader, WordsLoader};
+pub use markdown::convert_markdown;
+pub use matcher::{FindIter, Match, MatchSegment, Matcher};
+pub use model::HeteronymModel;
+#[cfg(feature = "trainer")]
+pub use model::ModelTrainer;
+pub use ngram::NgramModel;
+pub use numeral::{is_numeral, read_numeral, Numeral};
+pub use phonetic::{fold_syllable, phonetic_key, phonetic_key_with, similarity, similarity_with};
+pub use pingze::{match_regulated, ping_ze, ping_ze_pattern, PingZe, REGULATED_TEMPLATES};
+pub use pinyin::{
+    Abbr, MarkPlacement, MarkWith, NeutralTone, Pinyin, PinyinWord, StandardPlacement,
+    ToneRenderer, ToneStyle, YuFormat, T9,
+};
+#[cfg(feature = "parallel")]
 use rayon::iter::*;
+pub use romanization::Romanization;
+pub use shuangpin::ShuangpinScheme;
+pub use skip::SkipPattern;
+pub use slug::{PinyinSlug, Slug};
+pub use stats::CorpusStats;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::OnceLock;
+pub use store::{DictStore, StoreMatcher, UserDict};
+pub use syllable::{
+    is_valid_syllable, split_pinyin, split_pinyin_best, validate_syllable, Syllable,
+};
+pub use table::CharTable;
+#[cfg(feature = "tantivy")]
+pub use tokenizer::{PinyinTokenStream, PinyinTokenizer};
+pub use tone::Tone;
+pub use translit::{Kana, Latin, Transliterate};
+use unicode_segmentation::UnicodeSegmentation;
+pub use unknown::{UnknownCharHandler, UnknownChars};
+#[cfg(feature = "net")]
+pub use updater::{update_data, validate_source, Sources, UpdateReport};
+use verbatim::match_verbatim;
+pub use zhuyin::{parse_zhuyin, to_zhuyin, Zhuyin};
 
 // 已经线程安全
 static WORDS_LOADER: OnceLock<WordsLoader> = OnceLock::new();
 static SURNAMES_LOADER: OnceLock<SurnamesLoader> = OnceLock::new();
 static CHARS_LOADER: OnceLock<CharsLoader> = OnceLock::new();
+static NEUTRAL_TONE_LOADER: OnceLock<NeutralToneLoader> = OnceLock::new();
+#[cfg(feature = "slang")]
+static SLANG_LOADER: OnceLock<SlangLoader> = OnceLock::new();
 static MATCHERS: OnceLock<Vec<Matcher>> = OnceLock::new();
+static CHAR_TABLE: OnceLock<CharTable> = OnceLock::new();
+static HETERONYMS: OnceLock<HashSet<char>> = OnceLock::new();
+
+// 是否为汉字（CJK 统一表意文字及其扩展区、兼容区与 〇）
+pub fn is_han(c: char) -> bool {
+    matches!(c,
+        '\u{3007}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2A6DF}'
+        | '\u{2A700}'..='\u{2EBEF}'
+        | '\u{2F800}'..='\u{2FA1F}'
+        | '\u{30000}'..='\u{323AF}')
+}
+
+// 是否为多音字
+pub fn is_heteronym(c: char) -> bool {
+    HETERONYMS
+        .get_or_init(|| {
+            data::load("heteronyms.txt")
+                .lines()
+                .filter_map(|line| line.trim().chars().next())
+                .collect()
+        })
+        .contains(&c)
+}
 
 pub fn match_word_pinyin(word: &str) -> Vec<(String, String)> {
-    let matchers = MATCHERS.get_or_init(|| {
-        Vec::from([
-            Matcher::new(WORDS_LOADER.get_or_init(WordsLoader::new)),
-            Matcher::new(SURNAMES_LOADER.get_or_init(SurnamesLoader::new)),
-            Matcher::new(CHARS_LOADER.get_or_init(CharsLoader::new)),
-        ])
-    });
+    let mut results = match_words(word);
+    let table = char_table();
+    let mut seen = HashSet::new();
+    for c in word.chars() {
+        if let Some(pinyin) = table.get(c).filter(|_| seen.insert(c)) {
+            results.push((c.to_string(), pinyin.to_string()));
+        }
+    }
+    results.sort_by(|(k1, _), (k2, _)| k2.cmp(k1));
+
+    results
+}
+
+// OnceLock::get_or_try_init 尚未稳定；初始化失败时不缓存，下次调用会重试
+fn get_or_try_init<T>(
+    cell: &'static OnceLock<T>,
+    init: impl FnOnce() -> Result<T, PinyinError>,
+) -> Result<&'static T, PinyinError> {
+    if let Some(value) = cell.get() {
+        return Ok(value);
+    }
+    let value = init()?;
+    Ok(cell.get_or_init(|| value))
+}
+
+pub(crate) fn neutral_tone_loader() -> Result<&'static NeutralToneLoader, PinyinError> {
+    get_or_try_init(&NEUTRAL_TONE_LOADER, NeutralToneLoader::try_new)
+}
+
+// 单字直接按码位查表，不再经过 Aho-Corasick
+fn char_table() -> &'static CharTable<'static> {
+    try_char_table().unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn try_char_table() -> Result<&'static CharTable<'static>, PinyinError> {
+    get_or_try_init(&CHAR_TABLE, || {
+        let loader = get_or_try_init(&CHARS_LOADER, CharsLoader::try_new)?;
+        Ok(CharTable::from_entries(loader.entries()))
+    })
+}
+
+fn matchers() -> Result<&'static Vec<Matcher<'static>>, PinyinError> {
+    get_or_try_init(&MATCHERS, || {
+        #[allow(unused_mut)]
+        let mut matchers = Vec::from([
+            Matcher::try_new(get_or_try_init(&WORDS_LOADER, WordsLoader::try_new)?)?,
+            Matcher::try_new(get_or_try_init(&SURNAMES_LOADER, SurnamesLoader::try_new)?)?,
+        ]);
+        // 扩展包排在最前，同一个词以扩展包的读音为准
+        #[cfg(feature = "slang")]
+        matchers.insert(
+            0,
+            Matcher::try_new(get_or_try_init(&SLANG_LOADER, SlangLoader::try_new)?)?,
+        );
+        Ok(matchers)
+    })
+}
+
+// 词组与姓氏
+fn match_words(word: &str) -> Vec<(String, String)> {
+    try_match_words(word).unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn try_match_words(word: &str) -> Result<Vec<(String, String)>, PinyinError> {
+    let matchers = matchers()?;
 
     #[cfg(test)]
     let start = std::time::Instant::now();
 
+    #[cfg(feature = "parallel")]
+    let matchers = matchers.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let matchers = matchers.iter();
+
     let mut results: Vec<_> = matchers
-        .par_iter()
         .flat_map(|matcher| matcher.match_word_pinyin(word, false))
         .map(|(k, v)| (k.to_string(), v.to_string()))
         .collect();
@@ -35,14 +241,90 @@ pub fn match_word_pinyin(word: &str) -> Vec<(String, String)> {
     #[cfg(test)]
     println!("match used: {}ms", start.elapsed().as_millis());
 
-    results
+    Ok(results)
 }
 
 pub fn convert(input: &str) -> Vec<String> {
+    try_segment(input)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .into_iter()
+        .map(|(word, pinyin)| pinyin.unwrap_or(word))
+        .collect()
+}
+
+// 以下是常用输出的快捷方式，多音字只取第一个读音，声调为符号
+// "你好世界" -> "nǐ hǎo shì jiè"
+pub fn pinyin(text: &str) -> String {
+    Converter::new(text).heteronym_limit(1).to_string()
+}
+
+// "带着希望去旅行" -> "dai-zhe-xi-wang-qu-lv-xing"
+pub fn permalink(text: &str) -> String {
+    Converter::new(text).to_permalink()
+}
+
+// "你好世界" -> "nhsj"
+pub fn abbr(text: &str) -> String {
+    Converter::new(text).to_abbr()
+}
+
+// 姓名开头的复姓或单姓按姓氏读音："单田芳" -> "shàn tián fāng"，"尉迟恭" -> "yù chí gōng"
+pub fn name(text: &str) -> String {
+    let surnames = SURNAMES_LOADER.get_or_init(SurnamesLoader::new);
+    let surname = [2, 1].iter().find_map(|&n| {
+        let (end, _) = text.char_indices().nth(n).unwrap_or((text.len(), ' '));
+        let surname = &text[..end];
+        match surname.chars().count() == n {
+            true => surnames.get(surname).map(|pinyin| (end, pinyin)),
+            false => None,
+        }
+    });
+
+    match surname {
+        Some((end, pinyin)) if end < text.len() => {
+            format!("{} {}", pinyin, self::pinyin(&text[end..]))
+        }
+        Some((_, pinyin)) => pinyin.to_string(),
+        None => self::pinyin(text),
+    }
+}
+
+// 通讯录的 A-Z 分组：按姓名读音的首字母（大写）分组，姓氏按姓氏读音，"单田芳" 归入 'S'
+// 不以汉字开头的条目归入 '#'，每组内保持输入的顺序
+pub fn group_by_initial<'a>(items: impl Iterator<Item = &'a str>) -> BTreeMap<char, Vec<&'a str>> {
+    let mut groups: BTreeMap<char, Vec<&'a str>> = BTreeMap::new();
+
+    for item in items {
+        let text = item.trim();
+        let initial = match text.chars().next() {
+            Some(c) if is_han(c) => name(text)
+                .split_whitespace()
+                .next()
+                .and_then(|s| Pinyin::try_from_marked(s).ok())
+                .and_then(|p| p.pinyin.chars().next())
+                .filter(char::is_ascii_alphabetic)
+                .map(|c| c.to_ascii_uppercase()),
+            _ => None,
+        };
+        groups.entry(initial.unwrap_or('#')).or_default().push(item);
+    }
+
+    groups
+}
+
+// "你好，世界！" -> "nǐ hǎo, shì jiè!"
+pub fn sentence(text: &str) -> String {
+    Converter::new(text).heteronym_limit(1).to_sentence()
+}
+
+// 按命中的词切分输入，未命中的字符单独成段且没有拼音，字典数据缺失或损坏时返回错误
+pub(crate) fn try_segment(input: &str) -> Result<Vec<(String, Option<String>)>, PinyinError> {
     // 先把整句话拿去匹配全部命中的词
     let input_len = input.chars().count();
-    let matched_words = match_word_pinyin(input);
+    let matched_words = try_match_words(input)?;
+    let table = try_char_table()?;
     let input_chars: Vec<char> = input.chars().collect();
+    let offsets: Vec<usize> = input.char_indices().map(|(offset, _)| offset).collect();
 
     let mut result = Vec::new();
     let mut i = 0;
@@ -54,7 +336,7 @@ pub fn convert(input: &str) -> Vec<String> {
             if i + word_len <= input_len
                 && &input_chars[i..i + word_len] == word.chars().collect::<Vec<_>>().as_slice()
             {
-                result.push(pinyin.to_string());
+                result.push((word.to_string(), Some(pinyin.to_string())));
                 i += word_len;
                 found = true;
                 break;
@@ -62,18 +344,94 @@ pub fn convert(input: &str) -> Vec<String> {
         }
 
         if !found {
-            result.push(input_chars[i].to_string());
-            i += 1;
+            i += segment_char(&input[offsets[i]..], table, &mut result);
+        }
+    }
+
+    Ok(result)
+}
+
+// 切分 input 开头的一个字，返回消耗的字符数
+fn segment_char(
+    input: &str,
+    table: &CharTable,
+    result: &mut Vec<(String, Option<String>)>,
+) -> usize {
+    let c = match input.chars().next() {
+        Some(c) => c,
+        None => return 0,
+    };
+    if let Some(pinyin) = table.get(c) {
+        result.push((c.to_string(), Some(pinyin.to_string())));
+        return 1;
+    }
+
+    // 网址、邮箱与数字整体输出
+    if c.is_ascii_alphanumeric() {
+        if let Some(len) = match_verbatim(input) {
+            result.push((input[..len].to_string(), None));
+            return input[..len].chars().count();
+        }
+    }
+
+    // 按字素簇整体输出，避免把 emoji 序列、组合字符拆开
+    let grapheme = input.graphemes(true).next().unwrap_or_default();
+    result.push((grapheme.to_string(), None));
+    grapheme.chars().count().max(1)
+}
+
+// 调用方已经分好词：["重庆", "火锅"] -> ["chóng qìng", "huǒ guō"]
+// 每个词整体查词典，查不到的词逐字转换，不会再被切分或与相邻的词合并
+pub fn convert_tokens(tokens: &[&str]) -> Vec<String> {
+    segment_tokens(tokens)
+        .into_iter()
+        .map(|(word, pinyin)| pinyin.unwrap_or(word))
+        .collect()
+}
+
+pub(crate) fn segment_tokens(tokens: &[&str]) -> Vec<(String, Option<String>)> {
+    let table = char_table();
+    let mut result = Vec::new();
+
+    for token in tokens.iter().filter(|t| !t.is_empty()) {
+        if token.chars().nth(1).is_some() {
+            if let Some((word, pinyin)) = match_words(token).into_iter().find(|(w, _)| w == token) {
+                result.push((word, Some(pinyin)));
+                continue;
+            }
+        }
+
+        let mut rest = *token;
+        while !rest.is_empty() {
+            let n = segment_char(rest, table, &mut result);
+            rest = match rest.char_indices().nth(n) {
+                Some((offset, _)) => &rest[offset..],
+                None => "",
+            };
         }
     }
 
     result
 }
 
+// 测试用的临时路径，带上进程号与序号，并行运行的测试之间互不影响
+#[cfg(test)]
+pub(crate) fn temp_path(name: &str) -> std::path::PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("pinyin_{}_{}_{}", std::process::id(), n, name))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{convert, loader::WordsLoader, matcher::Matcher};
+    use crate::{
+        abbr, convert, convert_tokens, group_by_initial, loader::WordsLoader, matcher::Matcher,
+        name, permalink, pinyin, sentence,
+    };
     use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_convert() {
@@ -112,6 +470,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shortcuts() {
+        assert_eq!(pinyin("你好世界"), "nǐ hǎo shì jiè");
+        assert_eq!(pinyin("吃"), "chī");
+        assert_eq!(permalink("带着希望去旅行"), "dai-zhe-xi-wang-qu-lv-xing");
+        assert_eq!(abbr("你好世界"), "nhsj");
+        assert_eq!(sentence("你好，世界！"), "nǐ hǎo, shì jiè!");
+        assert_eq!(name("单田芳"), "shàn tián fāng");
+        assert_eq!(name("尉迟恭"), "yù chí gōng");
+        assert_eq!(name("单"), "shàn");
+        assert_eq!(name("你好"), "nǐ hǎo");
+        assert_eq!(name(""), "");
+    }
+
+    #[test]
+    fn test_group_by_initial() {
+        let contacts = [
+            "张三",
+            "单田芳",
+            "曾国藩",
+            "李四",
+            "吕布",
+            "Bob",
+            "",
+            "阿毛",
+            "张伟",
+        ];
+        let groups = group_by_initial(contacts.iter().copied());
+        let want = BTreeMap::from([
+            ('#', vec!["Bob", ""]),
+            ('A', vec!["阿毛"]),
+            ('L', vec!["李四", "吕布"]),
+            ('S', vec!["单田芳"]),
+            ('Z', vec!["张三", "曾国藩", "张伟"]),
+        ]);
+        assert_eq!(groups, want);
+    }
+
+    #[test]
+    fn test_convert_tokens() {
+        assert_eq!(
+            convert_tokens(&["中国", "人民", "", "喜欢吃", "👨‍👩‍👧"]),
+            vec!["zhōng guó", "rén mín", "xǐ xī chì", "huān", "chī qī", "👨‍👩‍👧"]
+        );
+    }
+
+    #[test]
+    fn test_convert_verbatim() {
+        assert_eq!(
+            convert("访问https://a.cn/x，写信a@b.cn支付1,000元"),
+            vec![
+                "fǎng wèn",
+                "https://a.cn/x",
+                "，",
+                "xiě xìn",
+                "a@b.cn",
+                "zhī fù",
+                "1,000",
+                "yuán"
+            ]
+        );
+    }
+
+    #[cfg(feature = "slang")]
+    #[test]
+    fn test_convert_slang() {
+        assert_eq!(convert("干饭人种草"), vec!["gàn fàn rén", "zhòng cǎo"]);
+    }
+
+    #[test]
+    fn test_convert_graphemes() {
+        assert_eq!(
+            convert("你好👨‍👩‍👧🇨🇳e\u{301}"),
+            vec!["nǐ hǎo", "👨‍👩‍👧", "🇨🇳", "e\u{301}"]
+        );
+    }
+
     #[test]
     fn test_matcher() {
         let start = std::time::Instant::now();
@@ -1,10 +1,57 @@
-use rayon::{iter::*, slice::ParallelSlice};
+use crate::data;
+use crate::error::PinyinError;
+use crate::generator::parse_line;
+use crate::pinyin::parse_readings;
+use crate::store::DictStore;
+#[cfg(feature = "parallel")]
+use rayon::iter::*;
 use std::collections::HashMap;
+use std::path::Path;
 
 pub trait Loader {
     fn get_chunks(&self, size: usize) -> Vec<HashMap<&str, &str>>;
 }
 
+impl Loader for HashMap<String, String> {
+    fn get_chunks(&self, size: usize) -> Vec<HashMap<&str, &str>> {
+        stable_chunks(
+            self.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect(),
+            size,
+        )
+    }
+}
+
+type Chunks<'a> = Vec<HashMap<&'a str, &'a str>>;
+
+// 按词的 FNV-1a 哈希分块，每块包含哪些词不受 HashMap 遍历顺序影响，匹配结果在每次运行中保持一致
+// 开启 parallel 时并行分块，每个词所在的块只由哈希决定，合并的顺序不影响结果
+fn stable_chunks<'a>(entries: Vec<(&'a str, &'a str)>, size: usize) -> Chunks<'a> {
+    assert!(size > 0);
+    let insert = |mut chunks: Chunks<'a>, (word, pinyin): (&'a str, &'a str)| {
+        let hash = word.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        });
+        chunks[(hash % size as u64) as usize].insert(word, pinyin);
+        chunks
+    };
+
+    #[cfg(feature = "parallel")]
+    return entries
+        .into_par_iter()
+        .fold(|| vec![HashMap::new(); size], insert)
+        .reduce(
+            || vec![HashMap::new(); size],
+            |mut chunks, other| {
+                for (chunk, other) in chunks.iter_mut().zip(other) {
+                    chunk.extend(other);
+                }
+                chunks
+            },
+        );
+    #[cfg(not(feature = "parallel"))]
+    entries.into_iter().fold(vec![HashMap::new(); size], insert)
+}
+
 #[derive(Debug, Default)]
 pub struct WordsLoader {
     words: HashMap<String, String>,
@@ -12,142 +59,468 @@ pub struct WordsLoader {
 
 impl Loader for WordsLoader {
     fn get_chunks(&self, size: usize) -> Vec<HashMap<&str, &str>> {
-        assert!(size > 0);
-        self.words
-            .par_iter()
-            .collect::<Vec<_>>()
-            .par_chunks(self.words.len() / size)
-            .map(|chunk| {
-                chunk
-                    .par_iter()
-                    .map(|(k, v)| (k.as_str(), v.as_str()))
-                    .collect()
-            })
-            .collect()
+        stable_chunks(
+            self.words
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect(),
+            size,
+        )
     }
 }
 
 impl WordsLoader {
+    // 数据缺失或有格式不对的行时 panic，见 try_new
     pub fn new() -> Self {
-        let words_files = [
-            include_str!("../data/words_0.txt").lines(),
-            include_str!("../data/words_1.txt").lines(),
-            include_str!("../data/words_2.txt").lines(),
-            include_str!("../data/words_3.txt").lines(),
-            include_str!("../data/words_4.txt").lines(),
-            include_str!("../data/words_5.txt").lines(),
-            include_str!("../data/words_6.txt").lines(),
-            include_str!("../data/words_7.txt").lines(),
-            include_str!("../data/words_8.txt").lines(),
-            include_str!("../data/words_9.txt").lines(),
-        ];
-
-        let words = words_files
-            .into_par_iter()
-            .map(|lines| {
-                let mut list = vec![];
-                for line in lines {
-                    let parts: Vec<&str> = line.split(':').map(|s| s.trim()).collect();
-                    if parts.len() == 2 {
-                        let chinese = parts[0].to_string();
-                        let pinyin = parts[1].to_string();
-                        list.push((chinese, pinyin));
-                    }
-                }
-                list
-            })
-            .flatten()
+        Self::try_new().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new() -> Result<Self, PinyinError> {
+        let words = load_chunks("words")?
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
             .collect();
-        Self { words }
+        Ok(Self { words })
+    }
+}
+
+// 运行时从文件读取的自定义词库，每行 "词: 拼音"，也可以是单字数据的 "U+4E2D: zhōng" 写法
+// 空行与注释会被跳过，格式不对的行作为错误返回，多个文件中同一个词以先读到的为准
+#[derive(Debug, Default)]
+pub struct FileLoader {
+    words: HashMap<String, String>,
+}
+
+impl Loader for FileLoader {
+    fn get_chunks(&self, size: usize) -> Vec<HashMap<&str, &str>> {
+        stable_chunks(
+            self.words
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect(),
+            size,
+        )
+    }
+}
+
+// 作为用户词典交给 Converter：converter.user_dict(UserDict::new(FileLoader::new(path)?))
+impl DictStore for FileLoader {
+    fn get(&self, word: &str) -> Option<String> {
+        self.words.get(word).cloned()
+    }
+
+    fn max_word_len(&self) -> usize {
+        self.words.max_word_len()
+    }
+}
+
+impl FileLoader {
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, PinyinError> {
+        Self::from_paths([path])
+    }
+
+    pub fn from_paths<I, P>(paths: I) -> Result<Self, PinyinError>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut loader = Self::default();
+        for path in paths {
+            loader.add_str(&std::fs::read_to_string(path)?)?;
+        }
+        Ok(loader)
+    }
+
+    pub fn add_str(&mut self, contents: &str) -> Result<&mut Self, PinyinError> {
+        for (i, line) in contents.lines().enumerate() {
+            if is_blank_or_comment(line) {
+                continue;
+            }
+            let error = || PinyinError::DictionaryParse {
+                line: i + 1,
+                content: line.to_string(),
+            };
+            let (word, pinyin) = parse_line(line)
+                .filter(|(_, pinyin)| parse_readings(pinyin).is_ok())
+                .ok_or_else(error)?;
+            let word = match word.strip_prefix("U+") {
+                Some(hex) => u32::from_str_radix(hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(error)?
+                    .to_string(),
+                None => word,
+            };
+            self.words.entry(word).or_insert(pinyin);
+        }
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
     }
 }
 
+#[cfg(feature = "static-tables")]
+mod tables {
+    include!(concat!(env!("OUT_DIR"), "/chars.rs"));
+    include!(concat!(env!("OUT_DIR"), "/surnames.rs"));
+}
+
+// 开启 static-tables 时直接使用编译进来的、按词排序的数组，不在启动时再收集为 HashMap
+#[cfg(feature = "static-tables")]
+type StaticEntries = &'static [(&'static str, &'static str)];
+#[cfg(not(feature = "static-tables"))]
+type StaticEntries = HashMap<&'static str, &'static str>;
+
 #[derive(Debug, Default)]
 pub struct CharsLoader {
-    chars: HashMap<String, String>,
+    chars: StaticEntries,
 }
 
 impl Loader for CharsLoader {
     fn get_chunks(&self, size: usize) -> Vec<HashMap<&str, &str>> {
-        assert!(size > 0);
-        self.chars
-            .par_iter()
-            .collect::<Vec<_>>()
-            .par_chunks(self.chars.len() / size)
-            .map(|chunk| {
-                chunk
-                    .par_iter()
-                    .map(|(k, v)| (k.as_str(), v.as_str()))
-                    .collect()
-            })
-            .collect()
+        stable_chunks(self.entries().collect(), size)
     }
 }
 
 impl CharsLoader {
+    #[cfg(feature = "static-tables")]
     pub fn new() -> Self {
-        let chars_files = [
-            include_str!("../data/chars_0.txt").lines(),
-            include_str!("../data/chars_1.txt").lines(),
-            include_str!("../data/chars_2.txt").lines(),
-            include_str!("../data/chars_3.txt").lines(),
-            include_str!("../data/chars_4.txt").lines(),
-            include_str!("../data/chars_5.txt").lines(),
-            include_str!("../data/chars_6.txt").lines(),
-            include_str!("../data/chars_7.txt").lines(),
-            include_str!("../data/chars_8.txt").lines(),
-            include_str!("../data/chars_9.txt").lines(),
-        ];
-
-        let chars = chars_files
-            .into_par_iter()
-            .map(|lines| {
-                let mut list = vec![];
-                for line in lines {
-                    let parts: Vec<&str> = line.split(':').map(|s| s.trim()).collect();
-                    if parts.len() == 2 {
-                        let chinese = parts[0].to_string();
-                        let pinyin = parts[1].to_string();
-                        list.push((chinese, pinyin));
-                    }
-                }
-                list
-            })
-            .flatten()
-            .collect();
-        Self { chars }
+        Self {
+            chars: tables::CHARS,
+        }
+    }
+
+    #[cfg(feature = "static-tables")]
+    pub fn try_new() -> Result<Self, PinyinError> {
+        Ok(Self::new())
+    }
+
+    #[cfg(not(feature = "static-tables"))]
+    pub fn new() -> Self {
+        Self::try_new().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // 直接引用加载好的数据，不再为每个字分配字符串
+    #[cfg(not(feature = "static-tables"))]
+    pub fn try_new() -> Result<Self, PinyinError> {
+        Ok(Self {
+            chars: load_chunks("chars")?.into_iter().collect(),
+        })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+        self.chars.iter().map(|(k, v)| (*k, *v))
     }
 }
 
 #[derive(Debug, Default)]
 pub struct SurnamesLoader {
-    surnames: HashMap<String, String>,
+    surnames: StaticEntries,
 }
 
 impl Loader for SurnamesLoader {
     fn get_chunks(&self, _: usize) -> Vec<HashMap<&str, &str>> {
-        let map = self
-            .surnames
-            .iter()
-            .map(|(k, v)| (k.as_str(), v.as_str()))
-            .collect();
-        vec![map]
+        vec![self.surnames.iter().map(|(k, v)| (*k, *v)).collect()]
     }
 }
 
 impl SurnamesLoader {
+    #[cfg(feature = "static-tables")]
+    pub fn new() -> Self {
+        Self {
+            surnames: tables::SURNAMES,
+        }
+    }
+
+    #[cfg(feature = "static-tables")]
+    pub fn try_new() -> Result<Self, PinyinError> {
+        Ok(Self::new())
+    }
+
+    #[cfg(not(feature = "static-tables"))]
     pub fn new() -> Self {
-        let mut list = vec![];
-        for line in include_str!("../data/surnames.txt").lines() {
-            let parts: Vec<&str> = line.split(':').map(|s| s.trim()).collect();
-            if parts.len() == 2 {
-                let chinese = parts[0].to_string();
-                let pinyin = parts[1].to_string();
-                list.push((chinese, pinyin));
+        Self::try_new().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    #[cfg(not(feature = "static-tables"))]
+    pub fn try_new() -> Result<Self, PinyinError> {
+        Ok(Self {
+            surnames: parse_static(data::try_load("surnames.txt")?)?
+                .into_iter()
+                .collect(),
+        })
+    }
+
+    #[cfg(feature = "static-tables")]
+    pub fn get(&self, surname: &str) -> Option<&'static str> {
+        self.surnames
+            .binary_search_by_key(&surname, |(k, _)| k)
+            .ok()
+            .map(|i| self.surnames[i].1)
+    }
+
+    #[cfg(not(feature = "static-tables"))]
+    pub fn get(&self, surname: &str) -> Option<&'static str> {
+        self.surnames.get(surname).copied()
+    }
+}
+
+// 轻声词读音，词语中的轻声音节不标调
+#[derive(Debug, Default)]
+pub struct NeutralToneLoader {
+    words: HashMap<&'static str, &'static str>,
+}
+
+impl Loader for NeutralToneLoader {
+    fn get_chunks(&self, _: usize) -> Vec<HashMap<&str, &str>> {
+        vec![self.words.iter().map(|(k, v)| (*k, *v)).collect()]
+    }
+}
+
+impl NeutralToneLoader {
+    pub fn new() -> Self {
+        Self::try_new().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new() -> Result<Self, PinyinError> {
+        Ok(Self {
+            words: parse_static(data::try_load("neutral.txt")?)?
+                .into_iter()
+                .collect(),
+        })
+    }
+
+    pub fn get(&self, word: &str) -> Option<&'static str> {
+        self.words.get(word).copied()
+    }
+}
+
+// 网络用语与品牌译名扩展包，转换时优先于内置词组
+#[cfg(feature = "slang")]
+#[derive(Debug, Default)]
+pub struct SlangLoader {
+    words: HashMap<&'static str, &'static str>,
+}
+
+#[cfg(feature = "slang")]
+impl Loader for SlangLoader {
+    fn get_chunks(&self, _: usize) -> Vec<HashMap<&str, &str>> {
+        vec![self.words.iter().map(|(k, v)| (*k, *v)).collect()]
+    }
+}
+
+#[cfg(feature = "slang")]
+impl SlangLoader {
+    pub fn new() -> Self {
+        Self::try_new().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new() -> Result<Self, PinyinError> {
+        Ok(Self {
+            words: parse_static(data::try_load("slang.txt")?)?
+                .into_iter()
+                .collect(),
+        })
+    }
+}
+
+// 粤拼读音，多字词交给 Matcher 匹配，单字按码位查表
+#[cfg(feature = "jyutping")]
+#[derive(Debug, Default)]
+pub struct JyutpingLoader {
+    words: HashMap<&'static str, &'static str>,
+    chars: HashMap<char, &'static str>,
+}
+
+#[cfg(feature = "jyutping")]
+impl Loader for JyutpingLoader {
+    fn get_chunks(&self, _: usize) -> Vec<HashMap<&str, &str>> {
+        vec![self.words.iter().map(|(k, v)| (*k, *v)).collect()]
+    }
+}
+
+#[cfg(feature = "jyutping")]
+impl JyutpingLoader {
+    pub fn new() -> Self {
+        Self::try_new().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new() -> Result<Self, PinyinError> {
+        let mut loader = Self::default();
+        for (word, jyutping) in parse_entries(data::try_load("jyutping.txt")?, |_| true)? {
+            let mut chars = word.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => loader.chars.insert(c, jyutping),
+                _ => loader.words.insert(word, jyutping),
+            };
+        }
+        Ok(loader)
+    }
+
+    // 单字的全部读音，以空格分隔
+    pub fn get(&self, c: char) -> Option<&'static str> {
+        self.chars.get(&c).copied()
+    }
+}
+
+fn is_blank_or_comment(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+// 解析 data/ 中的拼音数据："中: zhōng zhòng"，跳过空行与注释，
+// 格式不对或读音不是合法带调拼音的行作为错误返回，加载后转换时不会再遇到坏数据
+fn parse_static(contents: &'static str) -> Result<Vec<(&'static str, &'static str)>, PinyinError> {
+    parse_entries(contents, |pinyin| parse_readings(pinyin).is_ok())
+}
+
+fn parse_entries(
+    contents: &'static str,
+    is_valid: fn(&str) -> bool,
+) -> Result<Vec<(&'static str, &'static str)>, PinyinError> {
+    let mut entries = vec![];
+    for (i, line) in contents.lines().enumerate() {
+        if is_blank_or_comment(line) {
+            continue;
+        }
+        let parts: Vec<&str> = line.split(':').map(|s| s.trim()).collect();
+        match parts[..] {
+            [word, pinyin] if !word.is_empty() && !pinyin.is_empty() && is_valid(pinyin) => {
+                entries.push((word, pinyin))
+            }
+            _ => {
+                return Err(PinyinError::DictionaryParse {
+                    line: i + 1,
+                    content: line.to_string(),
+                })
             }
         }
-        Self {
-            surnames: list.into_iter().collect(),
+    }
+    Ok(entries)
+}
+
+// 读取并解析 name_0.txt 到 name_9.txt
+fn load_chunks(name: &str) -> Result<Vec<(&'static str, &'static str)>, PinyinError> {
+    load_chunks_with(name, data::try_load)
+}
+
+// load 按文件名读取 "words_0.txt" 等分块
+pub(crate) fn load_chunks_with<F>(
+    name: &str,
+    load: F,
+) -> Result<Vec<(&'static str, &'static str)>, PinyinError>
+where
+    F: Fn(&str) -> Result<&'static str, PinyinError>,
+{
+    let files = (0..10)
+        .map(|i| load(&format!("{}_{}.txt", name, i)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    #[cfg(feature = "parallel")]
+    let files = files.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let files = files.into_iter();
+
+    let entries = files.map(parse_static).collect::<Result<Vec<_>, _>>()?;
+    Ok(entries.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_static, Loader};
+    use crate::error::PinyinError;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_file_loader() {
+        use super::FileLoader;
+        use crate::converter::Converter;
+        use crate::matcher::Matcher;
+        use crate::store::UserDict;
+
+        let path = crate::temp_path("file_loader.txt");
+        std::fs::write(&path, "# 医学\n阿司匹林: ā sī pǐ lín\n\nU+4E2D: zhòng\n").unwrap();
+
+        let loader = FileLoader::from_paths([&path, &path]).unwrap();
+        assert_eq!(loader.len(), 2);
+        let matcher = Matcher::new(&loader);
+        assert_eq!(matcher.convert("吃阿司匹林"), vec!["吃", "ā sī pǐ lín"]);
+        assert_eq!(matcher.convert("中"), vec!["zhòng"]);
+
+        // 用户词典排在内置词库之前
+        let dict = UserDict::new(FileLoader::new(&path).unwrap());
+        let mut converter = Converter::new("吃阿司匹林，中国");
+        converter.user_dict(dict).heteronym_limit(1);
+        assert_eq!(converter.to_string(), "chī ā sī pǐ lín ， zhòng guó");
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(FileLoader::new(&path).is_err());
+
+        let mut loader = FileLoader::default();
+        for (contents, line) in [
+            ("中: zhōng\n坏行\n", 2),
+            ("U+ZZZZ: zhōng", 1),
+            ("中: zhōngguó", 1),
+        ] {
+            assert!(matches!(
+                loader.add_str(contents),
+                Err(PinyinError::DictionaryParse { line: l, .. }) if l == line
+            ));
         }
     }
+
+    #[test]
+    fn test_parse_static() {
+        assert_eq!(
+            parse_static("# 注释\n\n中: zhōng zhòng\n").unwrap(),
+            vec![("中", "zhōng zhòng")]
+        );
+        for (contents, line) in [
+            ("中: zhōng\n中国", 2),
+            ("中:", 1),
+            ("中: a: b", 1),
+            ("中: zhōng\n国: guó2", 2),
+        ] {
+            assert!(matches!(
+                parse_static(contents),
+                Err(PinyinError::DictionaryParse { line: l, .. }) if l == line
+            ));
+        }
+    }
+
+    #[test]
+    fn test_stable_chunks() {
+        let words = ["丁", "万", "上", "中", "乐", "乔"];
+        let forward: HashMap<String, String> = words
+            .iter()
+            .map(|w| (w.to_string(), String::new()))
+            .collect();
+        let backward: HashMap<String, String> = words
+            .iter()
+            .rev()
+            .map(|w| (w.to_string(), String::new()))
+            .collect();
+
+        let chunks = forward.get_chunks(3);
+        let words: Vec<Vec<&str>> = chunks
+            .iter()
+            .map(|chunk| {
+                let mut words: Vec<_> = chunk.keys().copied().collect();
+                words.sort();
+                words
+            })
+            .collect();
+        assert_eq!(
+            words,
+            vec![vec!["丁", "万"], vec!["中", "乔"], vec!["上", "乐"]]
+        );
+        assert_eq!(chunks, backward.get_chunks(3));
+    }
 }
@@ -0,0 +1,160 @@
+use crate::converter::{Config, Converter};
+use crate::is_han;
+
+// 只转换 Markdown 正文中的汉字，其余字符原样保留：
+// 跳过开头的 front matter、代码块、行内代码、链接地址、引用式链接的定义与行内 HTML 标签
+// "见[文档](docs/中文.md)" -> "jiàn[wén dàng](docs/中文.md)"
+pub fn convert_markdown(markdown: &str, config: &Config) -> String {
+    let mut result = String::with_capacity(markdown.len() * 2);
+    let mut lines = markdown.split_inclusive('\n').peekable();
+
+    // --- 包围的 YAML 或 +++ 包围的 TOML
+    if let Some(delimiter) = lines
+        .peek()
+        .map(|line| line.trim_end())
+        .filter(|line| *line == "---" || *line == "+++")
+    {
+        let delimiter = delimiter.to_string();
+        result.extend(lines.next());
+        for line in lines.by_ref() {
+            result.push_str(line);
+            let line = line.trim_end();
+            if line == delimiter || (delimiter == "---" && line == "...") {
+                break;
+            }
+        }
+    }
+
+    // 代码块的围栏字符与长度
+    let mut fence: Option<(char, usize)> = None;
+    for line in lines {
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+        let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~');
+        let run = marker.map_or(0, |m| trimmed.chars().take_while(|c| *c == m).count());
+
+        match (fence, marker) {
+            (Some((m, len)), Some(c))
+                if c == m && run >= len && trimmed[run..].trim().is_empty() =>
+            {
+                fence = None;
+                result.push_str(line);
+            }
+            (Some(_), _) => result.push_str(line),
+            (None, Some(c)) if indent < 4 && run >= 3 => {
+                fence = Some((c, run));
+                result.push_str(line);
+            }
+            (None, _) if is_link_definition(trimmed) && indent < 4 => result.push_str(line),
+            (None, _) => convert_inline(line, config, &mut result),
+        }
+    }
+
+    result
+}
+
+// "[id]: https://example.com"
+fn is_link_definition(line: &str) -> bool {
+    line.starts_with('[') && line.find("]:").is_some_and(|i| !line[1..i].contains(']'))
+}
+
+fn convert_inline(line: &str, config: &Config, result: &mut String) {
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < line.len() {
+        let rest = &line[i..];
+        let verbatim = if rest.starts_with('`') {
+            // 与开头相同长度的反引号结束行内代码，找不到时按普通字符处理
+            let run = rest.chars().take_while(|c| *c == '`').count();
+            let ticks = &rest[..run];
+            rest[run..].find(ticks).map_or(run, |end| run + end + run)
+        } else if rest.starts_with("](") || rest.starts_with("][") {
+            let close = if rest.as_bytes()[1] == b'(' { ')' } else { ']' };
+            closing(&rest[2..], rest.as_bytes()[1] as char, close).map_or(0, |end| 2 + end)
+        } else if rest.starts_with('<')
+            && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!')
+        {
+            rest.find('>').map_or(0, |end| end + 1)
+        } else {
+            0
+        };
+
+        if verbatim == 0 {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        }
+
+        convert_text(&line[text_start..i], config, result);
+        result.push_str(&rest[..verbatim]);
+        i += verbatim;
+        text_start = i;
+    }
+
+    convert_text(&line[text_start..], config, result);
+}
+
+// 配对的结束括号之后的位置，允许括号嵌套
+fn closing(text: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        if c == close && depth == 0 {
+            return Some(i + 1);
+        }
+        match c {
+            _ if c == open => depth += 1,
+            _ if c == close => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+// 汉字换成拼音，相邻的词之间以及拼音与紧挨着的字母、数字之间加分隔符，其余字符不变
+fn convert_text(text: &str, config: &Config, result: &mut String) {
+    if !text.chars().any(is_han) {
+        result.push_str(text);
+        return;
+    }
+
+    let mut last_is_word = false;
+    for word in Converter::with_config(text, config.clone()).convert() {
+        let is_pinyin = word.is_matched() && word.word.chars().all(is_han);
+        let text = match is_pinyin {
+            true => word
+                .syllables()
+                .iter()
+                .map(|p| config.format_pinyin(p))
+                .collect::<Vec<_>>()
+                .join(&config.separator),
+            false => word.word.clone(),
+        };
+        let is_word = text.starts_with(char::is_alphanumeric);
+        if (is_pinyin || last_is_word) && is_word && result.ends_with(char::is_alphanumeric) {
+            result.push_str(&config.separator);
+        }
+        result.push_str(&text);
+        last_is_word = is_pinyin;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::convert_markdown;
+    use crate::converter::Config;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_convert_markdown() {
+        let config = Config::default();
+        let markdown = "---\ntitle: 中国\n---\n# 中国\n\n用`中国`写[文档](docs/中国.md \"中\")。\n\n```rust\nlet s = \"中国\";\n```\n\n[中国]: https://中国.cn\n";
+        assert_eq!(
+            convert_markdown(markdown, &config),
+            "---\ntitle: 中国\n---\n# zhōng guó\n\nyòng`中国`xiě[wén dàng](docs/中国.md \"中\")。\n\n```rust\nlet s = \"中国\";\n```\n\n[中国]: https://中国.cn\n"
+        );
+        assert_eq!(
+            convert_markdown("**中国**Rust <b title=\"中\">国</b>", &config),
+            "**zhōng guó**Rust <b title=\"中\">guó</b>"
+        );
+    }
+}
@@ -1,15 +1,85 @@
+use crate::error::PinyinError;
 use crate::loader::Loader;
+use crate::pinyin::PinyinWord;
 use daachorse::{CharwiseDoubleArrayAhoCorasick, CharwiseDoubleArrayAhoCorasickBuilder, MatchKind};
+#[cfg(feature = "parallel")]
 use rayon::iter::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::iter::Peekable;
 
+// 基于 Aho-Corasick 自动机的词典匹配器，借用 Loader 中的词与读音
 #[derive(Clone)]
 pub struct Matcher<'a> {
     handlers: Vec<CharwiseDoubleArrayAhoCorasick<&'a str>>,
 }
 
+// find_iter 找到的词，start、end 为字节偏移
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'a, 't> {
+    pub start: usize,
+    pub end: usize,
+    // &text[start..end]
+    pub word: &'t str,
+    // 字典中的读音，如 "zhōng guó"
+    pub pinyin: &'a str,
+}
+
+// Converter::segments 切分出的片段
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchSegment {
+    // 字典中的词或字，带读音
+    Matched(PinyinWord),
+    // 原样输出的字符，如标点、字母
+    NotMatched(char),
+}
+
+type Matches<'a, 't> = Peekable<Box<dyn Iterator<Item = daachorse::Match<&'a str>> + 't>>;
+
+// Matcher::find_iter 返回的迭代器
+pub struct FindIter<'a, 't> {
+    text: &'t str,
+    cursor: usize,
+    matches: Vec<Matches<'a, 't>>,
+}
+
+impl<'a, 't> Iterator for FindIter<'a, 't> {
+    type Item = Match<'a, 't>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut best: Option<daachorse::Match<&'a str>> = None;
+        for matches in self.matches.iter_mut() {
+            // 丢弃与已产出的匹配重叠的结果
+            while matches.next_if(|m| m.start() < self.cursor).is_some() {}
+
+            if let Some(m) = matches.peek() {
+                let better = match &best {
+                    Some(b) => (m.start(), b.end()) < (b.start(), m.end()),
+                    None => true,
+                };
+                if better {
+                    best = Some(*m);
+                }
+            }
+        }
+
+        let best = best?;
+        self.cursor = best.end();
+        Some(Match {
+            start: best.start(),
+            end: best.end(),
+            word: &self.text[best.start()..best.end()],
+            pinyin: best.value(),
+        })
+    }
+}
+
 impl<'a> Matcher<'a> {
+    // 词表中有空词等无法构建自动机的数据时 panic，见 try_new
     pub fn new<L: Loader>(loader: &'a L) -> Self {
+        Self::try_new(loader).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new<L: Loader>(loader: &'a L) -> Result<Self, PinyinError> {
         #[cfg(test)]
         let start = std::time::Instant::now();
 
@@ -20,39 +90,105 @@ impl<'a> Matcher<'a> {
         #[cfg(test)]
         let start = std::time::Instant::now();
 
+        #[cfg(feature = "parallel")]
+        let words = words.into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let words = words.into_iter();
+
         let handlers = words
-            .into_par_iter()
-            .map(|words| {
-                CharwiseDoubleArrayAhoCorasickBuilder::new()
-                    .match_kind(MatchKind::LeftmostLongest)
-                    .build_with_values(words)
-                    .unwrap()
-            })
-            .collect();
+            .filter(|words| !words.is_empty())
+            .map(build)
+            .collect::<Result<_, _>>()?;
 
         #[cfg(test)]
         println!("'handlers init' used: {}ms", start.elapsed().as_millis());
 
-        Matcher { handlers }
+        Ok(Matcher { handlers })
+    }
+
+    // 自定义词表，如产品名
+    pub fn from_map(map: &'a HashMap<String, String>) -> Self {
+        Self::new(map)
+    }
+
+    // 同一个词以先出现的读音为准，空词会被忽略
+    pub fn from_pairs<I>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        Self::try_from_pairs(pairs).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_pairs<I>(pairs: I) -> Result<Self, PinyinError>
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let mut words: Vec<(&'a str, &'a str)> = vec![];
+        let mut seen = HashSet::new();
+        for (word, pinyin) in pairs {
+            if !word.is_empty() && seen.insert(word) {
+                words.push((word, pinyin));
+            }
+        }
+
+        let handlers = if words.is_empty() {
+            vec![]
+        } else {
+            vec![build(words)?]
+        };
+
+        Ok(Matcher { handlers })
     }
 
+    // 从左到右惰性查找，同一位置取最长的词："重庆火锅" 先得到 "重庆"
+    // 与已产出的词重叠的匹配会被丢弃，不会回溯寻找其他切分
+    pub fn find_iter<'t>(&'t self, text: &'t str) -> FindIter<'a, 't> {
+        let matches = self
+            .handlers
+            .iter()
+            .map(|handler| {
+                let iter: Box<dyn Iterator<Item = daachorse::Match<&'a str>> + 't> =
+                    Box::new(handler.leftmost_find_iter(text));
+                iter.peekable()
+            })
+            .collect();
+
+        FindIter {
+            text,
+            cursor: 0,
+            matches,
+        }
+    }
+
+    // 找出 word 中所有不重复的 (词, 拼音)，desc_by_key 时按词倒序排列，
+    // 否则按各块自动机找到的顺序，每次运行都相同
     pub fn match_word_pinyin(&self, word: &'a str, desc_by_key: bool) -> Vec<(&'a str, &'a str)> {
-        let iter = self.handlers.iter().flat_map(|handler| {
-            handler
-                .leftmost_find_iter(word)
-                .map(|m| {
-                    let matched_word = &word[m.start()..m.end()];
-                    (matched_word, m.value())
-                })
-                .collect::<HashMap<&'a str, &'a str>>()
-        });
+        #[cfg(feature = "parallel")]
+        let handlers = self.handlers.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let handlers = self.handlers.iter();
+
+        // 各块并行匹配，collect 保持块的顺序，去重后的结果与串行时相同
+        let found: Vec<Vec<_>> = handlers
+            .map(|handler| {
+                handler
+                    .leftmost_find_iter(word)
+                    .map(|m| (&word[m.start()..m.end()], m.value()))
+                    .collect()
+            })
+            .collect();
+        let mut seen = HashSet::new();
+        let entries: Vec<_> = found
+            .into_iter()
+            .flatten()
+            .filter(|(matched_word, _)| seen.insert(*matched_word))
+            .collect();
         if desc_by_key {
-            return sort_by_key_length_desc(iter.collect());
+            return sort_by_key_length_desc(entries);
         }
-        iter.collect()
+        entries
     }
 
-    #[allow(dead_code)]
     pub fn convert(&self, input: &str) -> Vec<String> {
         // 先把整句话拿去匹配全部命中的词
         let input_len = input.chars().count();
@@ -88,8 +224,110 @@ impl<'a> Matcher<'a> {
     }
 }
 
-fn sort_by_key_length_desc<'a>(map: HashMap<&'a str, &'a str>) -> Vec<(&'a str, &'a str)> {
-    let mut entries: Vec<_> = map.into_iter().collect();
+fn build<'a, I>(words: I) -> Result<CharwiseDoubleArrayAhoCorasick<&'a str>, PinyinError>
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+    CharwiseDoubleArrayAhoCorasickBuilder::new()
+        .match_kind(MatchKind::LeftmostLongest)
+        .build_with_values(words)
+        .map_err(|e| PinyinError::InvalidData(e.to_string()))
+}
+
+fn sort_by_key_length_desc<'a>(mut entries: Vec<(&'a str, &'a str)>) -> Vec<(&'a str, &'a str)> {
     entries.sort_by(|(k1, _), (k2, _)| k2.cmp(k1));
     entries
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Match, Matcher};
+    use crate::error::PinyinError;
+    use crate::loader::SurnamesLoader;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_find_iter() {
+        let loader = SurnamesLoader::new();
+        let matcher = Matcher::new(&loader);
+
+        let matches: Vec<_> = matcher.find_iter("尉迟恭与单于丹").collect();
+        assert_eq!(
+            matches,
+            vec![
+                Match {
+                    start: 0,
+                    end: 6,
+                    word: "尉迟",
+                    pinyin: "yù chí"
+                },
+                Match {
+                    start: 12,
+                    end: 18,
+                    word: "单于",
+                    pinyin: "chán yú"
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_map() {
+        let map: HashMap<String, String> = [("华为", "huá wéi"), ("小米", "xiǎo mǐ")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let matcher = Matcher::from_map(&map);
+        assert_eq!(
+            matcher.convert("华为和小米"),
+            vec!["huá wéi", "和", "xiǎo mǐ"]
+        );
+
+        let empty = HashMap::new();
+        assert_eq!(Matcher::from_map(&empty).convert("小米"), vec!["小", "米"]);
+
+        let invalid: HashMap<String, String> = [(String::new(), "mǐ".to_string())].into();
+        assert!(matches!(
+            Matcher::try_new(&invalid),
+            Err(PinyinError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_pairs() {
+        let matcher = Matcher::from_pairs([("小米", "xiǎo mǐ"), ("小米", "xiao mi"), ("米", "mǐ")]);
+        let words: Vec<_> = matcher
+            .find_iter("小米米")
+            .map(|m| (m.word, m.pinyin))
+            .collect();
+        assert_eq!(words, vec![("小米", "xiǎo mǐ"), ("米", "mǐ")]);
+
+        assert_eq!(Matcher::from_pairs([]).find_iter("小米").count(), 0);
+
+        // 与 "重庆" 重叠的 "庆火" 被丢弃
+        let matcher =
+            Matcher::from_pairs([("重庆", "chóng qìng"), ("庆火", "qìng huǒ"), ("锅", "guō")]);
+        let words: Vec<_> = matcher.find_iter("重庆火锅").map(|m| m.word).collect();
+        assert_eq!(words, vec!["重庆", "锅"]);
+    }
+
+    #[test]
+    fn test_match_word_pinyin_order() {
+        let map: HashMap<String, String> = [("米", "mǐ"), ("小米", "xiǎo mǐ"), ("华为", "huá wéi")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let matcher = Matcher::new(&map);
+
+        // 按词的哈希分块，"小米"、"华为" 与 "米" 分在两块，顺序与 HashMap 的遍历顺序无关
+        assert_eq!(
+            matcher.match_word_pinyin("华为小米米", false),
+            vec![("华为", "huá wéi"), ("小米", "xiǎo mǐ"), ("米", "mǐ")]
+        );
+        assert_eq!(
+            matcher.match_word_pinyin("华为小米米", true),
+            vec![("米", "mǐ"), ("小米", "xiǎo mǐ"), ("华为", "huá wéi")]
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use crate::error::PinyinError;
+use crate::pinyin::Pinyin;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(feature = "trainer")]
+use crate::is_heteronym;
+
+// 多音字读音的判断依据：不看上下文、左边的字、右边的字
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Context {
+    Any,
+    Left(char),
+    Right(char),
+}
+
+impl Display for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Context::Any => write!(f, "*"),
+            Context::Left(c) => write!(f, "<{}", c),
+            Context::Right(c) => write!(f, ">{}", c),
+        }
+    }
+}
+
+impl FromStr for Context {
+    type Err = PinyinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let context = match (chars.next(), chars.next(), chars.next()) {
+            (Some('*'), None, _) => Context::Any,
+            (Some('<'), Some(c), None) => Context::Left(c),
+            (Some('>'), Some(c), None) => Context::Right(c),
+            _ => {
+                return Err(PinyinError::DictionaryParse {
+                    line: 1,
+                    content: s.to_string(),
+                })
+            }
+        };
+        Ok(context)
+    }
+}
+
+// 多音字消歧模型，由 ModelTrainer 从标注语料中统计得到
+// 文件每行一条：字<TAB>上下文<TAB>读音<TAB>次数，上下文为 "*"、"<左字" 或 ">右字"
+// 七	*	qi1	10
+// 七	<人	qi2	3
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HeteronymModel {
+    rules: HashMap<(char, Context), Vec<(Pinyin, u32)>>,
+}
+
+impl HeteronymModel {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PinyinError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.values().map(|r| r.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    // 根据左右相邻的字在候选读音中选出最可能的一个，模型中没有这个字时返回 None
+    pub fn choose(
+        &self,
+        hanzi: char,
+        left: Option<char>,
+        right: Option<char>,
+        candidates: &[Pinyin],
+    ) -> Option<usize> {
+        let contexts = [
+            (Some(Context::Any), 1.0),
+            (left.map(Context::Left), 2.0),
+            (right.map(Context::Right), 2.0),
+        ];
+        let rules: Vec<_> = contexts
+            .iter()
+            .filter_map(|(context, weight)| Some((self.rules.get(&(hanzi, (*context)?))?, weight)))
+            .collect();
+        if rules.is_empty() {
+            return None;
+        }
+
+        let score = |candidate: &Pinyin| -> f64 {
+            rules
+                .iter()
+                .map(|(readings, weight)| {
+                    let count = readings
+                        .iter()
+                        .find(|(p, _)| p == candidate)
+                        .map_or(0, |(_, n)| *n);
+                    *weight * (1.0 + count as f64).ln()
+                })
+                .sum()
+        };
+
+        // 同分时保留字典顺序靠前的读音
+        let mut best: Option<(usize, f64)> = None;
+        for (i, candidate) in candidates.iter().enumerate() {
+            let score = score(candidate);
+            if score > 0.0 && !matches!(best, Some((_, s)) if s >= score) {
+                best = Some((i, score));
+            }
+        }
+
+        best.map(|(i, _)| i)
+    }
+}
+
+impl FromStr for HeteronymModel {
+    type Err = PinyinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut model = HeteronymModel::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || PinyinError::DictionaryParse {
+                line: i + 1,
+                content: line.to_string(),
+            };
+            let parts: Vec<&str> = line.split('\t').collect();
+            let (hanzi, context, pinyin, count) = match parts.as_slice() {
+                [hanzi, context, pinyin, count] => (hanzi, context, pinyin, count),
+                _ => return Err(invalid()),
+            };
+            let mut chars = hanzi.chars();
+            let hanzi = match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => return Err(invalid()),
+            };
+            let context = context.parse().map_err(|_| invalid())?;
+            let pinyin = Pinyin::from_str(pinyin).map_err(|_| invalid())?;
+            let count = count.parse().map_err(|_| invalid())?;
+
+            model
+                .rules
+                .entry((hanzi, context))
+                .or_default()
+                .push((pinyin, count));
+        }
+
+        Ok(model)
+    }
+}
+
+// 按字、上下文排序输出，同一份模型每次写出的内容相同
+impl Display for HeteronymModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut keys: Vec<_> = self.rules.keys().collect();
+        keys.sort();
+        for key in keys {
+            for (pinyin, count) in self.rules[key].iter() {
+                writeln!(f, "{}\t{}\t{}\t{}", key.0, key.1, pinyin, count)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// 从逐字标注的语料训练多音字消歧模型，语料每行一句：
+// 七人: qi2 ren2
+// 七月: qī yuè
+#[cfg(feature = "trainer")]
+#[derive(Debug, Clone)]
+pub struct ModelTrainer {
+    counts: HashMap<(char, Context), HashMap<String, u32>>,
+    min_count: u32,
+}
+
+#[cfg(feature = "trainer")]
+impl Default for ModelTrainer {
+    fn default() -> Self {
+        Self {
+            counts: HashMap::new(),
+            min_count: 2,
+        }
+    }
+}
+
+#[cfg(feature = "trainer")]
+impl ModelTrainer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 出现次数少于 min_count 的上下文不写入模型
+    pub fn min_count(&mut self, min_count: u32) -> &mut Self {
+        self.min_count = min_count;
+        self
+    }
+
+    pub fn add_sentence(&mut self, text: &str, pinyin: &[Pinyin]) -> Result<(), PinyinError> {
+        let chars: Vec<char> = text.chars().collect();
+        if chars.len() != pinyin.len() {
+            let pinyin: Vec<_> = pinyin.iter().map(|p| p.to_string()).collect();
+            return Err(PinyinError::DictionaryParse {
+                line: 1,
+                content: format!("{}: {}", text, pinyin.join(" ")),
+            });
+        }
+
+        for (i, (hanzi, p)) in chars.iter().zip(pinyin).enumerate() {
+            if !is_heteronym(*hanzi) {
+                continue;
+            }
+
+            let mut contexts = vec![Context::Any];
+            if i > 0 {
+                contexts.push(Context::Left(chars[i - 1]));
+            }
+            if let Some(right) = chars.get(i + 1) {
+                contexts.push(Context::Right(*right));
+            }
+            for context in contexts {
+                *self
+                    .counts
+                    .entry((*hanzi, context))
+                    .or_default()
+                    .entry(p.to_string())
+                    .or_insert(0) += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    // 空行与 # 开头的行会被跳过，拼音可以带声调符号或数字
+    pub fn add_line(&mut self, line: &str) -> Result<(), PinyinError> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(());
+        }
+
+        let invalid = || PinyinError::DictionaryParse {
+            line: 1,
+            content: line.to_string(),
+        };
+        let (text, pinyin) = line.split_once(':').ok_or_else(invalid)?;
+        let pinyin: Vec<Pinyin> = pinyin
+            .split_whitespace()
+            .map(|s| match s.ends_with(|c: char| c.is_ascii_digit()) {
+                true => Pinyin::from_str(s),
+                false => Pinyin::try_from_marked(s),
+            })
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid())?;
+
+        self.add_sentence(text.trim(), &pinyin)
+            .map_err(|_| invalid())
+    }
+
+    // 返回读入的行数
+    pub fn train<R: std::io::BufRead>(&mut self, reader: R) -> Result<usize, PinyinError> {
+        let mut lines = 0;
+        for line in reader.lines() {
+            lines += 1;
+            // add_line 只看到一行，这里补上在整个输入中的行号
+            self.add_line(&line?).map_err(|e| match e {
+                PinyinError::DictionaryParse { content, .. } => PinyinError::DictionaryParse {
+                    line: lines,
+                    content,
+                },
+                e => e,
+            })?;
+        }
+        Ok(lines)
+    }
+
+    // 只保留与不看上下文时结论不同的上下文，模型因此很小
+    pub fn build(&self) -> HeteronymModel {
+        let best = |readings: &HashMap<String, u32>| {
+            readings
+                .iter()
+                .max_by(|(p1, n1), (p2, n2)| n1.cmp(n2).then(p2.cmp(p1)))
+                .map(|(p, _)| p.clone())
+        };
+
+        let mut model = HeteronymModel::default();
+        for ((hanzi, context), readings) in self.counts.iter() {
+            if *context != Context::Any {
+                let total: u32 = readings.values().sum();
+                let prior = self.counts.get(&(*hanzi, Context::Any)).and_then(best);
+                if total < self.min_count || best(readings) == prior {
+                    continue;
+                }
+            }
+
+            let mut readings: Vec<_> = readings
+                .iter()
+                .filter_map(|(p, n)| Some((Pinyin::from_str(p).ok()?, *n)))
+                .collect();
+            readings.sort_by(|(p1, n1), (p2, n2)| {
+                n2.cmp(n1)
+                    .then(p1.pinyin.cmp(&p2.pinyin))
+                    .then(p1.tone.cmp(&p2.tone))
+            });
+            model.rules.insert((*hanzi, *context), readings);
+        }
+
+        model
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Context, HeteronymModel};
+    use crate::error::PinyinError;
+    use crate::pinyin::Pinyin;
+    use crate::tone::Tone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_heteronym_model() {
+        let model: HeteronymModel = "# 七\n七\t*\tqi1\t10\n七\t<人\tqi2\t3\n".parse().unwrap();
+        assert_eq!(model.len(), 2);
+        assert_eq!(model.to_string(), "七\t*\tqi1\t10\n七\t<人\tqi2\t3\n");
+
+        let candidates = [
+            Pinyin::new("qi", Tone::First),
+            Pinyin::new("qi", Tone::Second),
+        ];
+        assert_eq!(model.choose('七', None, Some('月'), &candidates), Some(0));
+        assert_eq!(model.choose('七', Some('人'), None, &candidates), Some(1));
+        assert_eq!(model.choose('八', None, None, &candidates), None);
+
+        assert!(matches!(
+            "七\t*\tqi1\t1\n七\t?\tqi1\t1".parse::<HeteronymModel>(),
+            Err(PinyinError::DictionaryParse { line: 2, .. })
+        ));
+        assert!(matches!(
+            "?".parse::<Context>(),
+            Err(PinyinError::DictionaryParse { line: 1, .. })
+        ));
+    }
+
+    #[cfg(feature = "trainer")]
+    #[test]
+    fn test_model_trainer() {
+        use super::ModelTrainer;
+
+        let mut trainer = ModelTrainer::new();
+        let corpus = "七月: qī yuè\n七天: qi1 tian1\n# 注释\n\n七人: qí rén\n七人: qí rén\n";
+        assert_eq!(trainer.train(corpus.as_bytes()).unwrap(), 6);
+        assert!(matches!(
+            trainer.add_line("七人: qi2"),
+            Err(PinyinError::DictionaryParse { line: 1, .. })
+        ));
+        assert!(matches!(
+            ModelTrainer::new().train("七月: qī yuè\n七人: qi9 rén\n".as_bytes()),
+            Err(PinyinError::DictionaryParse { line: 2, .. })
+        ));
+
+        let model = trainer.build();
+        assert_eq!(
+            model.to_string(),
+            "七\t*\tqi1\t2\n七\t*\tqi2\t2\n七\t>人\tqi2\t2\n\
+             人\t*\tren2\t2\n天\t*\ttian1\t1\n月\t*\tyue4\t1\n"
+        );
+    }
+}
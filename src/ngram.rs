@@ -0,0 +1,193 @@
+use crate::error::PinyinError;
+use crate::pinyin::PinyinWord;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+// 同时保留的候选路径数
+const BEAM_WIDTH: usize = 16;
+// 模型中没有的音节的 log10 概率
+const UNKNOWN_LOGPROB: f32 = -7.0;
+
+// ARPA 格式的音节 n 元语言模型，音节写作带数字声调的 "qi2"、"de5"
+// 用于在多音字的多种读音组合中选出概率最高的一条路径
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct NgramModel {
+    order: usize,
+    // "qi2 ge4" -> (log10 概率, 回退权重)
+    ngrams: HashMap<String, (f32, f32)>,
+}
+
+impl NgramModel {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PinyinError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    // log10 P(token | history)，history 中超出模型阶数的部分会被忽略，缺失的 n 元按回退权重逐级回退
+    pub fn score(&self, history: &[&str], token: &str) -> f32 {
+        let start = history.len().saturating_sub(self.order.saturating_sub(1));
+        let mut history = &history[start..];
+        let mut backoff = 0.0;
+
+        loop {
+            let context = history.join(" ");
+            let ngram = match context.is_empty() {
+                true => token.to_string(),
+                false => format!("{} {}", context, token),
+            };
+            if let Some((logprob, _)) = self.ngrams.get(&ngram) {
+                return backoff + logprob;
+            }
+            if history.is_empty() {
+                return backoff + UNKNOWN_LOGPROB;
+            }
+
+            backoff += self.ngrams.get(&context).map_or(0.0, |(_, b)| *b);
+            history = &history[1..];
+        }
+    }
+
+    pub fn score_sequence(&self, tokens: &[&str]) -> f32 {
+        (0..tokens.len())
+            .map(|i| self.score(&tokens[..i], tokens[i]))
+            .sum()
+    }
+
+    // 对未被词语消歧的单字做束搜索，把最优路径上的读音移到候选的第一位
+    // 没有读音的字符（标点等）会截断上下文
+    pub fn choose_readings(&self, words: &mut [PinyinWord]) {
+        let mut start = 0;
+        for end in 0..=words.len() {
+            if end == words.len() || !words[end].is_matched() {
+                self.choose_run(&mut words[start..end]);
+                start = end + 1;
+            }
+        }
+    }
+
+    fn choose_run(&self, words: &mut [PinyinWord]) {
+        // (得分, 已选的音节, 每个多音字选中的候选下标)
+        let mut beam: Vec<(f32, Vec<String>, Vec<usize>)> = vec![(0.0, vec![], vec![])];
+
+        for word in words.iter() {
+            let candidates: Vec<usize> = if word.word.chars().count() == 1 {
+                (0..word.pinyin.len()).collect()
+            } else {
+                vec![0]
+            };
+            let mut next = vec![];
+            for (score, history, choices) in beam.iter() {
+                for i in candidates.iter() {
+                    let syllables: Vec<String> = match word.word.chars().count() {
+                        1 => vec![word.pinyin[*i].to_string()],
+                        _ => word.pinyin.iter().map(|p| p.to_string()).collect(),
+                    };
+                    let mut history = history.clone();
+                    let mut score = *score;
+                    for syllable in syllables {
+                        let context: Vec<&str> = history.iter().map(String::as_str).collect();
+                        score += self.score(&context, &syllable);
+                        history.push(syllable);
+                    }
+                    let mut choices = choices.clone();
+                    choices.push(*i);
+                    next.push((score, history, choices));
+                }
+            }
+
+            next.sort_by(|a, b| b.0.total_cmp(&a.0));
+            next.truncate(BEAM_WIDTH);
+            beam = next;
+        }
+
+        if let Some((_, _, choices)) = beam.first() {
+            for (word, best) in words.iter_mut().zip(choices) {
+                if *best > 0 {
+                    word.pinyin[..=*best].rotate_right(1);
+                }
+            }
+        }
+    }
+}
+
+// \data\
+// ngram 1=2
+//
+// \1-grams:
+// -1.0	qi1	-0.3
+// \2-grams:
+// -0.1	qi2 ge4
+// \end\
+impl FromStr for NgramModel {
+    type Err = PinyinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut model = NgramModel::default();
+        let mut order = 0;
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("ngram ") {
+                continue;
+            }
+            if line.starts_with('\\') {
+                order = line
+                    .strip_prefix('\\')
+                    .and_then(|l| l.strip_suffix("-grams:"))
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or(0);
+                model.order = model.order.max(order);
+                continue;
+            }
+            if order == 0 {
+                continue;
+            }
+
+            let invalid = || PinyinError::DictionaryParse {
+                line: i + 1,
+                content: line.to_string(),
+            };
+            let mut fields = line.split_whitespace();
+            let logprob: f32 = fields
+                .next()
+                .and_then(|p| p.parse().ok())
+                .ok_or_else(invalid)?;
+            let tokens: Vec<&str> = fields.by_ref().take(order).collect();
+            if tokens.len() != order {
+                return Err(invalid());
+            }
+            let backoff = match fields.next() {
+                Some(b) => b.parse().map_err(|_| invalid())?,
+                None => 0.0,
+            };
+
+            model.ngrams.insert(tokens.join(" "), (logprob, backoff));
+        }
+
+        Ok(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NgramModel;
+    use pretty_assertions::assert_eq;
+
+    const ARPA: &str = "\\data\\\nngram 1=3\nngram 2=1\n\n\\1-grams:\n-1.0\tqi1\t-0.5\n-1.5\tqi2\n-1.0\tge4\n\n\\2-grams:\n-0.1\tqi2 ge4\n\\end\\\n";
+
+    #[test]
+    fn test_ngram_model() {
+        let model: NgramModel = ARPA.parse().unwrap();
+        assert_eq!(model.order(), 2);
+        assert_eq!(model.score(&["qi2"], "ge4"), -0.1);
+        assert_eq!(model.score(&["qi1"], "ge4"), -1.5);
+        assert_eq!(model.score(&[], "xx"), -7.0);
+        assert_eq!(model.score_sequence(&["qi2", "ge4"]), -1.6);
+
+        assert!("\\1-grams:\nabc\tqi1".parse::<NgramModel>().is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use crate::pinyin::Pinyin;
+use crate::tone::Tone;
+
+// 中文数字，如 "壹万贰仟" -> 12000, [yi1, wan4, er4, qian1]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Numeral {
+    pub value: u64,
+    pub pinyin: Vec<Pinyin>,
+}
+
+enum Token {
+    Digit(u64),
+    // 十、百、千
+    Unit(u64),
+    // 万、亿
+    Section(u64),
+}
+
+// 数字语境下的固定读音，避免 "陆" 读成 lù、"參" 读成 cān
+fn lookup(c: char) -> Option<(Token, &'static str, Tone)> {
+    let item = match c {
+        '〇' | '零' => (Token::Digit(0), "ling", Tone::Second),
+        '一' | '壹' => (Token::Digit(1), "yi", Tone::First),
+        '二' | '贰' | '貳' => (Token::Digit(2), "er", Tone::Fourth),
+        '两' | '兩' => (Token::Digit(2), "liang", Tone::Third),
+        '三' | '叁' | '參' => (Token::Digit(3), "san", Tone::First),
+        '四' | '肆' => (Token::Digit(4), "si", Tone::Fourth),
+        '五' | '伍' => (Token::Digit(5), "wu", Tone::Third),
+        '六' | '陆' | '陸' => (Token::Digit(6), "liu", Tone::Fourth),
+        '七' | '柒' => (Token::Digit(7), "qi", Tone::First),
+        '八' | '捌' => (Token::Digit(8), "ba", Tone::First),
+        '九' | '玖' => (Token::Digit(9), "jiu", Tone::Third),
+        '十' | '拾' => (Token::Unit(10), "shi", Tone::Second),
+        '百' | '佰' => (Token::Unit(100), "bai", Tone::Third),
+        '千' | '仟' => (Token::Unit(1_000), "qian", Tone::First),
+        '万' | '萬' => (Token::Section(10_000), "wan", Tone::Fourth),
+        '亿' | '億' => (Token::Section(100_000_000), "yi", Tone::Fourth),
+        _ => return None,
+    };
+
+    Some(item)
+}
+
+pub fn is_numeral(c: char) -> bool {
+    lookup(c).is_some()
+}
+
+// 〇到九，不含十、百、千等单位
+pub(crate) fn is_digit(c: char) -> bool {
+    matches!(lookup(c), Some((Token::Digit(_), _, _)))
+}
+
+// 读取一串中文数字的数值与读音，支持 "一千零二十"、"贰佰" 以及 "二〇二四" 这种逐位写法
+// 单位必须从大到小排列，"十十"、"五五十"、"一万二万" 这类写法返回 None
+pub fn read_numeral(text: &str) -> Option<Numeral> {
+    let mut tokens = vec![];
+    let mut pinyin = vec![];
+    for c in text.chars() {
+        let (token, syllable, tone) = lookup(c)?;
+        tokens.push(token);
+        pinyin.push(Pinyin::new(syllable, tone));
+    }
+
+    if tokens.is_empty() {
+        return None;
+    }
+
+    let value = if tokens.iter().all(|t| matches!(t, Token::Digit(_))) {
+        tokens.iter().try_fold(0u64, |value, token| match token {
+            Token::Digit(d) => value.checked_mul(10)?.checked_add(*d),
+            _ => None,
+        })?
+    } else {
+        let mut total = 0u64;
+        let mut section = 0u64;
+        let mut number = 0u64;
+        // 当前段中上一个十、百、千，以及上一个万、亿
+        let mut last_unit = None;
+        let mut last_section = None;
+
+        for (i, token) in tokens.iter().enumerate() {
+            match *token {
+                Token::Digit(d) => {
+                    // 数位之间只能隔着单位，"零" 除外："一百零五"
+                    if i > 0 && matches!(tokens[i - 1], Token::Digit(prev) if prev != 0) {
+                        return None;
+                    }
+                    number = d;
+                }
+                Token::Unit(unit) => {
+                    if last_unit.is_some_and(|last| unit >= last) {
+                        return None;
+                    }
+                    // "十五" 中的 "十" 前面省略了 "一"
+                    if number == 0 && unit == 10 && (i == 0 || section == 0) {
+                        number = 1;
+                    }
+                    section = section.checked_add(number.checked_mul(unit)?)?;
+                    number = 0;
+                    last_unit = Some(unit);
+                }
+                Token::Section(unit) => {
+                    if last_section == Some(unit) {
+                        return None;
+                    }
+                    last_section = Some(unit);
+                    last_unit = None;
+                    section = section.checked_add(number)?;
+                    total = if unit == 10_000 {
+                        total.checked_add(section.checked_mul(unit)?)?
+                    } else {
+                        total.checked_add(section)?.checked_mul(unit)?
+                    };
+                    section = 0;
+                    number = 0;
+                }
+            }
+        }
+
+        total.checked_add(section)?.checked_add(number)?
+    };
+
+    Some(Numeral { value, pinyin })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_numeral;
+
+    #[test]
+    fn test_read_numeral() {
+        let cases = [
+            ("零", 0),
+            ("十", 10),
+            ("十五", 15),
+            ("二十", 20),
+            ("一百零五", 105),
+            ("一千零二十", 1020),
+            ("壹万贰仟叁佰肆拾伍", 12345),
+            ("陆佰万", 6_000_000),
+            ("一亿二千万", 120_000_000),
+            ("三亿零五万", 300_050_000),
+            ("二〇二四", 2024),
+            ("两千", 2000),
+        ];
+        for (text, value) in cases {
+            assert_eq!(read_numeral(text).unwrap().value, value, "{}", text);
+        }
+
+        let numeral = read_numeral("陆佰").unwrap();
+        let pinyin: Vec<_> = numeral.pinyin.iter().map(|p| p.to_string()).collect();
+        assert_eq!(pinyin, vec!["liu4", "bai3"]);
+
+        assert_eq!(read_numeral(""), None);
+        assert_eq!(read_numeral("一百块"), None);
+        for text in ["十十", "五五十", "一百百", "一十百", "一万二万", "三千五三"]
+        {
+            assert_eq!(read_numeral(text), None, "{}", text);
+        }
+    }
+}
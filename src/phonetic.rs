@@ -0,0 +1,213 @@
+use crate::converter::Converter;
+use crate::fuzzy::Fuzzy;
+use crate::pinyin::{Pinyin, ToneStyle, YuFormat};
+use crate::tone::Tone;
+
+// 按 Fuzzy::phonetic 的规则把不带声调的音节折叠为模糊音等价类中的代表：
+// zh/ch/sh -> z/c/s，n -> l，后鼻音 -ng -> -n，n、l 后的 ü -> u
+// "zhang" -> "zan", "ning" -> "lin", "lv" -> "lu", "yang" -> "ian"
+pub fn fold_syllable(syllable: &str) -> String {
+    Fuzzy::phonetic().fold(syllable)
+}
+
+// 按读音生成的模糊键，用于数据去重、记录关联时的分块，读音相近的文本得到相同的键
+// "张三" 与 "臧山" -> "zansan"
+// 多音字取第一个读音，文本中的字母与数字转为小写保留，其余字符忽略
+// 等同于 phonetic_key_with(text, &Fuzzy::phonetic())，比 Fuzzy::default() 多出 ian/iang、uan/uang 与 ü/u
+pub fn phonetic_key(text: &str) -> String {
+    phonetic_key_with(text, &Fuzzy::phonetic())
+}
+
+// 按给定的模糊音规则折叠音节生成键，规则之外的差异（如 ü 与 u）保留
+// phonetic_key_with("飞", &Fuzzy::new(&[FuzzyRule::FH])) == phonetic_key_with("黑", ..)
+pub fn phonetic_key_with(text: &str, fuzzy: &Fuzzy) -> String {
+    let mut key = String::with_capacity(text.len());
+    for word in Converter::new(text).convert() {
+        if !word.is_matched() {
+            key.extend(
+                word.word
+                    .chars()
+                    .filter(|c| c.is_ascii_alphanumeric())
+                    .map(|c| c.to_ascii_lowercase()),
+            );
+            continue;
+        }
+
+        for p in word.syllables() {
+            key.push_str(&fuzzy.fold(&p.format_with(ToneStyle::None, YuFormat::V)));
+        }
+    }
+
+    key
+}
+
+// 两段文本读音的相似度，0.0..=1.0，按音节的编辑距离计算，不区分声调
+// "张伟" 与 "章炜" -> 1.0
+pub fn similarity(a: &str, b: &str) -> f32 {
+    similarity_with(a, b, false)
+}
+
+// 按音节的编辑距离计算相似度：相同的音节不计代价，只有声调不同或模糊音相等（zh = z、n = l 等）计 0.5
+// 多音字取第一个读音，字母与数字按整段小写比较，其余字符忽略
+pub fn similarity_with(a: &str, b: &str, tone_sensitive: bool) -> f32 {
+    let (a, b) = (syllables(a), syllables(b));
+    let len = a.len().max(b.len());
+    if len == 0 {
+        return 1.0;
+    }
+
+    let fuzzy = Fuzzy::default();
+    let cost = |x: &Pinyin, y: &Pinyin| -> f32 {
+        match (x.pinyin == y.pinyin, x.tone == y.tone || !tone_sensitive) {
+            (true, true) => 0.0,
+            (true, false) => 0.5,
+            _ if fuzzy.equal(&x.pinyin, &y.pinyin) => 0.5,
+            _ => 1.0,
+        }
+    };
+
+    let mut prev: Vec<f32> = (0..=b.len()).map(|j| j as f32).collect();
+    for (i, x) in a.iter().enumerate() {
+        let mut current = vec![i as f32 + 1.0];
+        for (j, y) in b.iter().enumerate() {
+            let value = (prev[j] + cost(x, y))
+                .min(prev[j + 1] + 1.0)
+                .min(current[j] + 1.0);
+            current.push(value);
+        }
+        prev = current;
+    }
+
+    1.0 - prev[b.len()] / len as f32
+}
+
+fn syllables(text: &str) -> Vec<Pinyin> {
+    let mut result = vec![];
+    for word in Converter::new(text).convert() {
+        if word.is_matched() {
+            result.extend(word.syllables().iter().map(|p| Pinyin {
+                pinyin: p.format_with(ToneStyle::None, YuFormat::V),
+                tone: p.tone,
+            }));
+            continue;
+        }
+
+        let token: String = word
+            .word
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        if !token.is_empty() {
+            result.push(Pinyin {
+                pinyin: token,
+                tone: Tone::Neutral,
+            });
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fold_syllable, phonetic_key, phonetic_key_with, similarity, similarity_with};
+    use crate::fuzzy::{Fuzzy, FuzzyRule};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_fold_syllable() {
+        let cases = [
+            ("zhang", "zan"),
+            ("chen", "cen"),
+            ("sheng", "sen"),
+            ("ning", "lin"),
+            ("lv", "lu"),
+            ("ng", "ng"),
+            ("n", "n"),
+            ("xiong", "xiong"),
+            ("zhong", "zong"),
+            ("nü", "lu"),
+            ("yang", "ian"),
+            ("weng", "un"),
+        ];
+        for (syllable, folded) in cases {
+            assert_eq!(fold_syllable(syllable), folded, "{}", syllable);
+        }
+    }
+
+    #[test]
+    fn test_phonetic_key() {
+        assert_eq!(phonetic_key("张三"), "zansan");
+        assert_eq!(phonetic_key("张三"), phonetic_key("臧山"));
+        assert_eq!(phonetic_key("刘"), phonetic_key("牛"));
+        assert_eq!(phonetic_key("陈 Alex 2"), "cenalex2");
+        assert_ne!(phonetic_key("林"), phonetic_key("陈"));
+
+        // 与 Fuzzy::phonetic() 的规则一致，比默认规则多折叠 ian/iang、uan/uang 与 n、l 后的 ü/u
+        for text in ["中国", "女", "先", "黄", "翁", "Alex 2"] {
+            assert_eq!(
+                phonetic_key(text),
+                phonetic_key_with(text, &Fuzzy::phonetic()),
+                "{}",
+                text
+            );
+        }
+        assert_eq!(phonetic_key("中"), "zong");
+        assert_eq!(phonetic_key("女"), phonetic_key("路"));
+        assert_eq!(phonetic_key("先"), phonetic_key("香"));
+        let fuzzy = Fuzzy::default();
+        assert_eq!(phonetic_key("中"), phonetic_key_with("中", &fuzzy));
+        assert_ne!(
+            phonetic_key_with("女", &fuzzy),
+            phonetic_key_with("路", &fuzzy)
+        );
+        assert_ne!(
+            phonetic_key_with("先", &fuzzy),
+            phonetic_key_with("香", &fuzzy)
+        );
+    }
+
+    #[test]
+    fn test_phonetic_key_with() {
+        let fuzzy = Fuzzy::default();
+        assert_eq!(phonetic_key_with("张三", &fuzzy), "zansan");
+        assert_eq!(
+            phonetic_key_with("刘", &fuzzy),
+            phonetic_key_with("牛", &fuzzy)
+        );
+        // 默认规则不包括 ian = iang
+        assert_ne!(
+            phonetic_key_with("先", &fuzzy),
+            phonetic_key_with("香", &fuzzy)
+        );
+
+        let fuzzy = Fuzzy::new(&[FuzzyRule::FH, FuzzyRule::IanIang]);
+        assert_eq!(
+            phonetic_key_with("飞", &fuzzy),
+            phonetic_key_with("黑", &fuzzy)
+        );
+        assert_eq!(
+            phonetic_key_with("先", &fuzzy),
+            phonetic_key_with("香", &fuzzy)
+        );
+        assert_eq!(phonetic_key_with("Wang 2", &fuzzy), "wang2");
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("张伟", "章炜"), 1.0);
+        assert_eq!(similarity("张伟", "张伟"), 1.0);
+        assert_eq!(similarity("", ""), 1.0);
+        assert_eq!(similarity("张伟", ""), 0.0);
+        assert_eq!(similarity("王小明", "王晓"), 1.0 - 1.0 / 3.0);
+        // 模糊音计半个
+        assert_eq!(similarity("张三", "臧三"), 0.75);
+        assert_eq!(similarity("刘德华", "张学友"), 0.0);
+        assert_eq!(similarity("李 Ming", "李ming"), 1.0);
+
+        assert_eq!(similarity_with("张伟", "张威", true), 0.75);
+        assert_eq!(similarity_with("张伟", "张威", false), 1.0);
+        assert_eq!(similarity_with("汤", "糖", true), 0.5);
+        assert_eq!(similarity_with("汤", "糖", false), 1.0);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::converter::Converter;
+use crate::is_han;
+use crate::tone::Tone;
+use std::fmt::Display;
+
+// 按普通话声调划分的平仄：一、二声为平，三、四声为仄
+// 古入声字今读平声的情况需要韵书数据，这里不做区分；轻声与没有读音的字无法判断
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PingZe {
+    Ping,
+    Ze,
+    Unknown,
+}
+
+impl PingZe {
+    pub fn from_tone(tone: Tone) -> Self {
+        match tone {
+            Tone::First | Tone::Second => PingZe::Ping,
+            Tone::Third | Tone::Fourth => PingZe::Ze,
+            Tone::Neutral => PingZe::Unknown,
+        }
+    }
+}
+
+impl Display for PingZe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            PingZe::Ping => "平",
+            PingZe::Ze => "仄",
+            PingZe::Unknown => "？",
+        };
+        f.write_str(s)
+    }
+}
+
+// 五言、七言近体诗的四种基本句式
+pub const REGULATED_TEMPLATES: [&str; 8] = [
+    "仄仄平平仄",
+    "平平仄仄平",
+    "平平平仄仄",
+    "仄仄仄平平",
+    "平平仄仄平平仄",
+    "仄仄平平仄仄平",
+    "仄仄平平平仄仄",
+    "平平仄仄仄平平",
+];
+
+// 逐字标注平仄，标点等非汉字字符被跳过，多音字按分词后的首选读音判断
+// "床前明月光" -> [('床', 平), ('前', 平), ('明', 平), ('月', 仄), ('光', 平)]
+pub fn ping_ze(line: &str) -> Vec<(char, PingZe)> {
+    Converter::new(line)
+        .to_pairs()
+        .into_iter()
+        .filter(|(c, _)| is_han(*c))
+        .map(|(c, p)| (c, p.map_or(PingZe::Unknown, |p| PingZe::from_tone(p.tone))))
+        .collect()
+}
+
+// "床前明月光" -> "平平平仄平"
+pub fn ping_ze_pattern(line: &str) -> String {
+    ping_ze(line).iter().map(|(_, pz)| pz.to_string()).collect()
+}
+
+// 找出诗句符合的近体诗句式，按 "一三五不论" 放宽单数位置（五言的第一、三字，七言的第一、三、五字）
+// 不符合任何句式时返回空
+pub fn match_regulated(line: &str) -> Vec<&'static str> {
+    let pattern: Vec<PingZe> = ping_ze(line).into_iter().map(|(_, pz)| pz).collect();
+
+    REGULATED_TEMPLATES
+        .iter()
+        .filter(|template| {
+            let template: Vec<char> = template.chars().collect();
+            template.len() == pattern.len()
+                && template
+                    .iter()
+                    .zip(pattern.iter())
+                    .enumerate()
+                    .all(|(i, (t, pz))| {
+                        let free = i % 2 == 0 && i + 1 < template.len();
+                        free || (*t == '平' && *pz == PingZe::Ping)
+                            || (*t == '仄' && *pz == PingZe::Ze)
+                    })
+        })
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{match_regulated, ping_ze, ping_ze_pattern, PingZe};
+    use crate::tone::Tone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ping_ze() {
+        assert_eq!(
+            ping_ze("白日，依山")
+                .iter()
+                .map(|(c, _)| *c)
+                .collect::<String>(),
+            "白日依山"
+        );
+        assert_eq!(PingZe::from_tone(Tone::Neutral), PingZe::Unknown);
+        assert_eq!(ping_ze_pattern("黄河入海流"), "平平仄仄平");
+        assert_eq!(match_regulated("黄河入海流"), vec!["平平仄仄平"]);
+        assert_eq!(match_regulated("欲穷千里目"), vec!["平平平仄仄"]);
+        assert_eq!(match_regulated("你好"), Vec::<&str>::new());
+    }
+}
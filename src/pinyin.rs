@@ -1,43 +1,263 @@
-use crate::error::PingyinError;
+use crate::error::PinyinError;
+use crate::tone::Tone;
+use crate::zhuyin::to_zhuyin;
 use std::{cmp::PartialEq, fmt::Display, str::FromStr};
+use unicode_normalization::UnicodeNormalization;
 
-#[allow(dead_code)]
-#[derive(Debug, PartialEq)]
-enum ToneStyle {
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ToneStyle {
+    // zhong4
     Number,
+    // zhòng
+    #[default]
     Mark,
+    // zhong
     None,
+    // zho\u{300}ng，声调符号写作组合附加符号（NFD），供不支持预组合字符的字体与终端使用
+    MarkCombining,
+    // zhong⁴，语言学文献常用的上标数字
+    Superscript,
+    // zhong51，五度标记法的调值，轻声记作 0
+    Contour,
+    // zhong˥˩，赵元任声调字母，轻声不标
+    ToneLetter,
 }
 
-#[derive(Debug)]
-struct Pinyin {
+// 自定义声调的呈现方式，如五度标记 ˥˧˩、盲文或教学用记号
+pub trait ToneRenderer {
+    // pinyin 为已规范 ü 拼写、不带声调的音节
+    fn render(&self, pinyin: &str, tone: Tone) -> String;
+}
+
+impl ToneRenderer for ToneStyle {
+    fn render(&self, pinyin: &str, tone: Tone) -> String {
+        match self {
+            ToneStyle::Number => format!("{}{}", pinyin, tone),
+            ToneStyle::Mark => format_tone(pinyin, tone),
+            ToneStyle::None => pinyin.to_string(),
+            ToneStyle::MarkCombining => format_tone(pinyin, tone).nfd().collect(),
+            ToneStyle::Superscript => format!("{}{}", pinyin, superscript(tone)),
+            ToneStyle::Contour => format!("{}{}", pinyin, contour(tone)),
+            ToneStyle::ToneLetter => format!("{}{}", pinyin, tone_letter(tone)),
+        }
+    }
+}
+
+// 只输出音节的首字母："zhong" -> "z"，用于生成简码、首字母检索
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Abbr;
+
+impl ToneRenderer for Abbr {
+    fn render(&self, pinyin: &str, _: Tone) -> String {
+        pinyin.chars().take(1).collect()
+    }
+}
+
+// 手机九宫格按键："hao" -> "426"，ü 与 v 同在 8 键上
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct T9;
+
+impl T9 {
+    pub(crate) fn key(c: char) -> Option<char> {
+        let key = match c.to_ascii_lowercase() {
+            'a'..='c' => '2',
+            'd'..='f' => '3',
+            'g'..='i' => '4',
+            'j'..='l' => '5',
+            'm'..='o' => '6',
+            'p'..='s' => '7',
+            't'..='v' | 'ü' => '8',
+            'w'..='z' => '9',
+            c if c.is_ascii_digit() => c,
+            _ => return None,
+        };
+        Some(key)
+    }
+}
+
+impl ToneRenderer for T9 {
+    fn render(&self, pinyin: &str, _: Tone) -> String {
+        pinyin.chars().filter_map(T9::key).collect()
+    }
+}
+
+// ToneStyle::Number 中轻声的写法，各下游系统的约定不同
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NeutralTone {
+    // ma5
+    #[default]
+    Five,
+    // ma0
+    Zero,
+    // ma
+    Omit,
+}
+
+// 按 Number 输出，轻声按约定的写法
+impl ToneRenderer for NeutralTone {
+    fn render(&self, pinyin: &str, tone: Tone) -> String {
+        match (tone, self) {
+            (Tone::Neutral, NeutralTone::Zero) => format!("{}0", pinyin),
+            (Tone::Neutral, NeutralTone::Omit) => pinyin.to_string(),
+            _ => format!("{}{}", pinyin, tone),
+        }
+    }
+}
+
+impl<F: Fn(&str, Tone) -> String> ToneRenderer for F {
+    fn render(&self, pinyin: &str, tone: Tone) -> String {
+        self(pinyin, tone)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum YuFormat {
+    // nǚ lüè
+    #[default]
+    Umlaut,
+    // nv3 lvè，声调标在 ü 上时仍写作 ǚ
+    V,
+    // nǔ luè
+    U,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawPinyin")
+)]
+pub struct Pinyin {
     pub pinyin: String,
-    pub tone: u8,
+    // 原先为 u8，现为 Tone，需要数字时用 tone_number()，由数字构造时经 Tone::try_from 校验
+    pub tone: Tone,
+}
+
+// 反序列化时先读入原始字段，再经 Pinyin::try_new 校验
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawPinyin {
+    pinyin: String,
+    tone: Tone,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawPinyin> for Pinyin {
+    type Error = PinyinError;
+
+    fn try_from(raw: RawPinyin) -> Result<Self, Self::Error> {
+        Self::try_new(&raw.pinyin, raw.tone)
+    }
 }
 
 impl Pinyin {
-    #[allow(dead_code)]
-    pub fn new(pinyin: &str, tone: u8) -> Self {
-        assert!((1..=5).contains(&tone));
+    pub fn new(pinyin: &str, tone: Tone) -> Self {
+        match Self::try_new(pinyin, tone) {
+            Ok(pinyin) => pinyin,
+            Err(e) => panic!("{}", e),
+        }
+    }
 
-        Self {
+    // 音节不能为空且只能由字母组成；数字形式的声调先经 Tone::try_from 转换
+    pub fn try_new(pinyin: &str, tone: Tone) -> Result<Self, PinyinError> {
+        if pinyin.is_empty() {
+            return Err(PinyinError::EmptyInput);
+        }
+        if !pinyin.chars().all(char::is_alphabetic) {
+            return Err(PinyinError::InvalidSyllable(pinyin.to_string()));
+        }
+        Ok(Self {
             pinyin: pinyin.to_string(),
             tone,
-        }
+        })
+    }
+
+    // 数字形式的声调，1..=5
+    pub fn tone_number(&self) -> u8 {
+        self.tone.number()
+    }
+
+    // Pinyin::new("zhong", Tone::Fourth) -> "ㄓㄨㄥˋ"
+    pub fn to_zhuyin(&self) -> Option<String> {
+        to_zhuyin(&self.pinyin, self.tone)
     }
 
-    #[allow(dead_code)]
     pub fn is_toneless(&self) -> bool {
-        self.tone == 5
+        self.tone.is_neutral()
+    }
+
+    // 五度标记法的调值，轻声没有固定调值，记作 "0"
+    pub fn contour(&self) -> &'static str {
+        contour(self.tone)
     }
 
-    #[allow(dead_code)]
     pub fn format(&self, style: ToneStyle) -> String {
-        match style {
-            ToneStyle::Number => self.to_string(),
-            ToneStyle::Mark => format_tone(&self.pinyin, self.tone),
-            ToneStyle::None => self.pinyin.clone(),
+        self.format_with(style, YuFormat::default())
+    }
+
+    pub fn format_with(&self, style: ToneStyle, yu_format: YuFormat) -> String {
+        self.render(&style, yu_format)
+    }
+
+    pub fn render<R: ToneRenderer + ?Sized>(&self, renderer: &R, yu_format: YuFormat) -> String {
+        let rendered = renderer.render(&normalize_yu(&self.pinyin), self.tone);
+        format_yu(&rendered, yu_format)
+    }
+
+    // "zhōng" -> Pinyin { pinyin: "zhong", tone: Tone::First }，不是合法的带调拼音时 panic
+    pub fn from_marked(s: &str) -> Self {
+        Self::try_from_marked(s).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // 只能由字母、ü、ê 组成，最多一个声调符号，没有声调符号时为轻声
+    pub fn try_from_marked(s: &str) -> Result<Self, PinyinError> {
+        let invalid = || PinyinError::InvalidSyllable(s.to_string());
+        let mut tone = None;
+        let mut pinyin = String::with_capacity(s.len());
+        // 组合附加符号（NFD）写法先合成为预组合字符
+        for c in s.nfc() {
+            let t = match c {
+                // ê̄、m̀ 等没有预组合字符，声调符号仍是组合附加符号
+                '\u{304}' => 1,
+                '\u{301}' => 2,
+                '\u{30C}' => 3,
+                '\u{300}' => 4,
+                // GB 18030 私用区的 ḿ
+                '\u{E7C7}' => {
+                    pinyin.push('m');
+                    2
+                }
+                _ => match unmark_vowel(c.to_lowercase().next().unwrap_or(c)) {
+                    Some((vowel, t)) if c.is_uppercase() => {
+                        pinyin.extend(vowel.to_uppercase());
+                        t
+                    }
+                    Some((vowel, t)) => {
+                        pinyin.push(vowel);
+                        t
+                    }
+                    None if c.is_ascii_alphabetic() || matches!(c, 'ü' | 'Ü' | 'ê' | 'Ê') => {
+                        pinyin.push(c);
+                        continue;
+                    }
+                    None => return Err(invalid()),
+                },
+            };
+            if pinyin.is_empty() || tone.replace(t).is_some() {
+                return Err(invalid());
+            }
         }
+        if pinyin.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            pinyin,
+            tone: tone.map_or(Ok(Tone::Neutral), Tone::try_from)?,
+        })
     }
 }
 
@@ -48,29 +268,46 @@ impl Display for Pinyin {
 }
 
 impl FromStr for Pinyin {
-    type Err = PingyinError;
+    type Err = PinyinError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut tone = 5;
-        let mut end = s.len();
-        let c = match s.chars().last() {
-            Some(c) => c,
-            None => {
-                return Err(PingyinError::ParseStrError(s.to_string()));
+        if s.is_empty() {
+            return Err(PinyinError::EmptyInput);
+        }
+        // 末尾没有数字时为轻声
+        match s.strip_suffix(|c: char| c.is_ascii_digit()) {
+            Some(pinyin) => {
+                Self::try_new(pinyin, Tone::try_from(s.as_bytes()[s.len() - 1] - b'0')?)
             }
-        };
-        if c.is_numeric() {
-            tone = c.to_digit(10).unwrap() as u8;
-            end -= 1;
+            None => Self::try_new(s, Tone::Neutral),
         }
+        .map_err(|e| match e {
+            PinyinError::EmptyInput => PinyinError::InvalidSyllable(s.to_string()),
+            e => e,
+        })
+    }
+}
+
+impl TryFrom<(&str, u8)> for Pinyin {
+    type Error = PinyinError;
 
-        let pinyin: String = s.chars().take(end).collect();
-        Ok(Self { pinyin, tone })
+    fn try_from((pinyin, tone): (&str, u8)) -> Result<Self, Self::Error> {
+        Self::try_new(pinyin, Tone::try_from(tone)?)
     }
 }
 
-#[derive(Debug)]
-struct PinyinWord {
+// 字典中的一条读音："zhōng zhòng" 或 "zhuó,zuó"
+pub(crate) fn parse_readings(readings: &str) -> Result<Vec<Pinyin>, PinyinError> {
+    readings
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .map(Pinyin::try_from_marked)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PinyinWord {
     // "重庆"
     pub word: String,
     // [["chong", 2], ["qing", 4]]
@@ -78,13 +315,38 @@ struct PinyinWord {
 }
 
 impl PinyinWord {
-    #[allow(dead_code)]
     pub fn new(word: &str, pinyin: Vec<Pinyin>) -> Self {
         Self {
             word: word.to_string(),
             pinyin,
         }
     }
+
+    // 未命中字典的字符没有拼音
+    pub fn is_matched(&self) -> bool {
+        !self.pinyin.is_empty()
+    }
+
+    // 逐字的音节：单字只取第一个（最常用的）读音，词语取全部音节
+    pub fn syllables(&self) -> &[Pinyin] {
+        if self.word.chars().count() == 1 {
+            &self.pinyin[..self.pinyin.len().min(1)]
+        } else {
+            &self.pinyin
+        }
+    }
+
+    pub fn format(&self, style: ToneStyle, separator: &str) -> String {
+        if !self.is_matched() {
+            return self.word.clone();
+        }
+
+        self.pinyin
+            .iter()
+            .map(|p| p.format(style))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
 }
 
 impl Display for PinyinWord {
@@ -101,52 +363,217 @@ impl Display for PinyinWord {
 }
 
 impl FromStr for PinyinWord {
-    type Err = PingyinError;
+    type Err = PinyinError;
 
     // "重:zhong4 chong2" -> PinyinWord { word: "重", pinyin: [["zhong", 4], ["chong", 2]] }
+    // 按单行字典数据解析，出错时行号为 1
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(':');
-        let word = parts
-            .next()
-            .ok_or_else(|| PingyinError::ParseStrError(s.to_string()))?
-            .to_string();
-        let mut pinyin = vec![];
-        for p in parts
-            .next()
-            .ok_or_else(|| PingyinError::ParseStrError(s.to_string()))?
+        let invalid = || PinyinError::DictionaryParse {
+            line: 1,
+            content: s.to_string(),
+        };
+        let (word, pinyin) = s.split_once(':').ok_or_else(invalid)?;
+        let pinyin = pinyin
             .split(' ')
-        {
-            pinyin.push(Pinyin::from_str(p)?);
+            .map(Pinyin::from_str)
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid())?;
+
+        Ok(Self {
+            word: word.to_string(),
+            pinyin,
+        })
+    }
+}
+
+// 选择音节中标注声调的元音
+pub trait MarkPlacement {
+    // 返回要标注的字符下标，None 表示不标注
+    fn select(&self, chars: &[char]) -> Option<usize>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StandardPlacement;
+
+// 《汉语拼音方案》的标调规则：有 a 标 a，没有 a 找 o、e（含 ê），
+// 都没有时 i、u、ü 只有一个就标在它上面，iu、ui 标在后一个上
+impl MarkPlacement for StandardPlacement {
+    fn select(&self, chars: &[char]) -> Option<usize> {
+        let chars: Vec<char> = chars.iter().map(|c| to_lower(*c)).collect();
+        let find = |vowel: char| chars.iter().position(|c| *c == vowel);
+
+        find('a')
+            .or_else(|| find('o'))
+            .or_else(|| find('e'))
+            .or_else(|| find('ê'))
+            .or_else(|| chars.iter().rposition(|c| matches!(c, 'i' | 'u' | 'ü')))
+            // 没有元音的 m、n、ng、hm、hng 标在鼻音上："ńg"
+            .or_else(|| chars.iter().position(|c| matches!(c, 'm' | 'n')))
+    }
+}
+
+impl<F: Fn(&[char]) -> Option<usize>> MarkPlacement for F {
+    fn select(&self, chars: &[char]) -> Option<usize> {
+        self(chars)
+    }
+}
+
+// 使用自定义标调位置的声调符号风格，可以传给 Converter::render
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MarkWith<P: MarkPlacement>(pub P);
+
+impl<P: MarkPlacement> ToneRenderer for MarkWith<P> {
+    fn render(&self, pinyin: &str, tone: Tone) -> String {
+        format_tone_with(pinyin, tone, &self.0)
+    }
+}
+
+pub(crate) fn contour(tone: Tone) -> &'static str {
+    match tone {
+        Tone::First => "55",
+        Tone::Second => "35",
+        Tone::Third => "214",
+        Tone::Fourth => "51",
+        Tone::Neutral => "0",
+    }
+}
+
+pub(crate) fn tone_letter(tone: Tone) -> &'static str {
+    match tone {
+        Tone::First => "˥",
+        Tone::Second => "˧˥",
+        Tone::Third => "˨˩˦",
+        Tone::Fourth => "˥˩",
+        Tone::Neutral => "",
+    }
+}
+
+// 1 -> "¹"，5 -> "⁵"
+pub(crate) fn superscript(tone: Tone) -> &'static str {
+    match tone {
+        Tone::First => "¹",
+        Tone::Second => "²",
+        Tone::Third => "³",
+        Tone::Fourth => "⁴",
+        Tone::Neutral => "⁵",
+    }
+}
+
+pub(crate) fn format_tone(pinyin: &str, tone: Tone) -> String {
+    format_tone_with(pinyin, tone, &StandardPlacement)
+}
+
+fn format_tone_with<P: MarkPlacement + ?Sized>(pinyin: &str, tone: Tone, placement: &P) -> String {
+    let chars: Vec<char> = pinyin.chars().collect();
+    let is_vowel = |c: char| "aeiouüê".contains(to_lower(c));
+    // 只有没有元音的音节才能标在 m、n 上
+    let nasal = !chars.iter().any(|c| is_vowel(*c));
+    let selected = placement.select(&chars).filter(|idx| {
+        chars
+            .get(*idx)
+            .is_some_and(|c| is_vowel(*c) || (nasal && "mn".contains(to_lower(*c))))
+    });
+
+    let mut result = String::with_capacity(pinyin.len() + 2);
+    for (idx, c) in chars.into_iter().enumerate() {
+        match Some(idx) == selected {
+            true => push_marked(&mut result, c, tone),
+            false => result.push(c),
         }
+    }
 
-        Ok(Self { word, pinyin })
+    result
+}
+
+// 大写元音标调后仍为大写："Ou" -> "Ōu"；ê 只有二声、四声有预组合字符，其余用组合附加符号
+// m、n 有预组合字符的用预组合字符："ń"、"ḿ"，其余为组合附加符号："m̄"
+fn push_marked(result: &mut String, vowel: char, tone: Tone) {
+    let lower = to_lower(vowel);
+    if matches!(lower, 'm' | 'n') {
+        let mark = match tone {
+            Tone::First => '\u{304}',
+            Tone::Second => '\u{301}',
+            Tone::Third => '\u{30C}',
+            Tone::Fourth => '\u{300}',
+            Tone::Neutral => return result.push(vowel),
+        };
+        result.extend([vowel, mark].into_iter().nfc());
+    } else if lower == 'ê' {
+        // 大写的 Ê 同样保留大小写："Ế"、"Ê\u{304}"
+        let upper = lower != vowel;
+        match (tone, upper) {
+            (Tone::First, _) => result.extend([vowel, '\u{304}']),
+            (Tone::Second, false) => result.push('ế'),
+            (Tone::Second, true) => result.push('Ế'),
+            (Tone::Third, _) => result.extend([vowel, '\u{30C}']),
+            (Tone::Fourth, false) => result.push('ề'),
+            (Tone::Fourth, true) => result.push('Ề'),
+            (Tone::Neutral, _) => result.push(vowel),
+        }
+    } else if lower != vowel {
+        result.extend(mark_vowel(lower, tone).to_uppercase());
+    } else {
+        result.push(mark_vowel(vowel, tone));
     }
 }
 
-fn format_tone(pinyin: &str, tone: u8) -> String {
-    // find the vowel to mark
-    // if the vowel is 'i' or 'u' or 'ü', find the next vowel
-    let mut chars: Vec<char> = pinyin.chars().collect();
-    let mut last_vowel_idx = 0;
+fn to_lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
 
-    for (idx, c) in chars.iter().enumerate() {
-        if "aeiouü".contains(*c) {
-            last_vowel_idx = idx;
-            if *c != 'i' || *c != 'u' || *c != 'ü' {
-                break;
-            }
+// 按拼写拆分声母与韵母，y、w 按声母处理，"yuan" -> ("y", "uan")
+pub(crate) fn split_initial(syllable: &str) -> (&str, &str) {
+    for initial in ["zh", "ch", "sh"] {
+        if let Some(fin) = syllable.strip_prefix(initial) {
+            return (initial, fin);
+        }
+    }
+
+    match syllable.chars().next() {
+        Some(
+            'b' | 'p' | 'm' | 'f' | 'd' | 't' | 'n' | 'l' | 'g' | 'k' | 'h' | 'j' | 'q' | 'x' | 'r'
+            | 'z' | 'c' | 's' | 'y' | 'w',
+        ) => syllable.split_at(1),
+        _ => ("", syllable),
+    }
+}
+
+// ü 只在 n、l 后保留两点；j、q、x 与零声母 y 后写作 u
+// "nv" -> "nü", "jü" -> "ju", "yü" -> "yu"
+fn normalize_yu(pinyin: &str) -> String {
+    let pinyin = pinyin.replace('v', "ü");
+    let mut chars = pinyin.chars();
+    match (chars.next(), chars.next()) {
+        (Some(initial @ ('j' | 'q' | 'x' | 'y')), Some('ü')) => {
+            format!("{}u{}", initial, chars.as_str())
         }
+        _ => pinyin,
     }
+}
 
-    let vowel = chars[last_vowel_idx];
-    chars[last_vowel_idx] = mark_vowel(vowel, tone);
-    chars.into_iter().collect()
+fn format_yu(pinyin: &str, yu_format: YuFormat) -> String {
+    match yu_format {
+        YuFormat::Umlaut => pinyin.to_string(),
+        YuFormat::V => pinyin.replace('ü', "v"),
+        YuFormat::U => pinyin
+            .chars()
+            .map(|c| match c {
+                'ü' => 'u',
+                'ǖ' => 'ū',
+                'ǘ' => 'ú',
+                'ǚ' => 'ǔ',
+                'ǜ' => 'ù',
+                _ => c,
+            })
+            .collect(),
+    }
 }
 
-fn mark_vowel(vowel: char, tone: u8) -> char {
-    if tone == 0 || tone == 5 {
+fn mark_vowel(vowel: char, tone: Tone) -> char {
+    if tone.is_neutral() {
         return vowel;
     }
+    let tone = tone.number();
 
     let tone_marks = [
         'ā', 'á', 'ǎ', 'à', 'ē', 'é', 'ě', 'è', 'ī', 'í', 'ǐ', 'ì', 'ō', 'ó', 'ǒ', 'ò', 'ū', 'ú',
@@ -165,45 +592,122 @@ fn mark_vowel(vowel: char, tone: u8) -> char {
     tone_marks[index - 1]
 }
 
+fn unmark_vowel(c: char) -> Option<(char, u8)> {
+    let (vowel, tone) = match c {
+        'ā' => ('a', 1),
+        'á' => ('a', 2),
+        'ǎ' => ('a', 3),
+        'à' => ('a', 4),
+        'ē' => ('e', 1),
+        'é' => ('e', 2),
+        'ě' => ('e', 3),
+        'è' => ('e', 4),
+        'ī' => ('i', 1),
+        'í' => ('i', 2),
+        'ǐ' => ('i', 3),
+        'ì' => ('i', 4),
+        'ō' => ('o', 1),
+        'ó' => ('o', 2),
+        'ǒ' => ('o', 3),
+        'ò' => ('o', 4),
+        'ū' => ('u', 1),
+        'ú' => ('u', 2),
+        'ǔ' => ('u', 3),
+        'ù' => ('u', 4),
+        'ǖ' => ('ü', 1),
+        'ǘ' => ('ü', 2),
+        'ǚ' => ('ü', 3),
+        'ǜ' => ('ü', 4),
+        // 嗯、呒、欸 等特殊读音
+        'ń' => ('n', 2),
+        'ň' => ('n', 3),
+        'ǹ' => ('n', 4),
+        'ḿ' => ('m', 2),
+        'ế' => ('ê', 2),
+        'ề' => ('ê', 4),
+        _ => return None,
+    };
+
+    Some((vowel, tone))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{mark_vowel, Pinyin, PinyinWord, ToneStyle};
+    use super::{
+        format_tone, mark_vowel, parse_readings, unmark_vowel, MarkWith, NeutralTone, Pinyin,
+        PinyinWord, ToneRenderer, ToneStyle, YuFormat,
+    };
+    use crate::error::PinyinError;
+    use crate::tone::Tone;
     use std::str::FromStr;
 
     #[test]
     fn test_pinyin_new() {
-        let pinyin = Pinyin::new("zhong", 4);
+        let pinyin = Pinyin::new("zhong", Tone::Fourth);
         assert_eq!(pinyin.pinyin, "zhong");
-        assert_eq!(pinyin.tone, 4);
+        assert_eq!(pinyin.tone, Tone::Fourth);
 
-        let pinyin = Pinyin::new("a", 5);
+        let pinyin = Pinyin::new("a", Tone::Neutral);
         assert_eq!(pinyin.pinyin, "a");
     }
 
     #[test]
     #[should_panic]
-    fn test_pinyin_new_panic_with_invalid_tone() {
-        let _pinyin = Pinyin::new("zhong", 6);
+    fn test_pinyin_new_panic_with_invalid_syllable() {
+        let _pinyin = Pinyin::new("zh ong", Tone::First);
     }
 
     #[test]
-    #[should_panic]
-    fn test_pinyin_new_panic_with_zero_tone() {
-        let _pinyin = Pinyin::new("zhong", 0);
+    fn test_pinyin_try_new() {
+        assert_eq!(
+            Pinyin::try_new("lü", Tone::Fourth).unwrap(),
+            Pinyin::new("lü", Tone::Fourth)
+        );
+        assert_eq!(
+            Pinyin::try_from(("zhong", 1)).unwrap(),
+            Pinyin::new("zhong", Tone::First)
+        );
+        assert!(matches!(
+            Pinyin::try_from(("zhong", 0)),
+            Err(PinyinError::InvalidTone { got: 0 })
+        ));
+        assert!(matches!(
+            Pinyin::try_from(("zhong", 6)),
+            Err(PinyinError::InvalidTone { got: 6 })
+        ));
+        assert!(matches!(
+            Pinyin::try_new("", Tone::First),
+            Err(PinyinError::EmptyInput)
+        ));
+        assert!(matches!(
+            Pinyin::try_new("zh ong", Tone::First),
+            Err(PinyinError::InvalidSyllable(s)) if s == "zh ong"
+        ));
+        assert!(Pinyin::try_from(("zhong1", 1)).is_err());
+    }
+
+    #[test]
+    fn test_pinyin_to_zhuyin() {
+        assert_eq!(
+            Pinyin::new("zhong", Tone::Fourth).to_zhuyin().unwrap(),
+            "ㄓㄨㄥˋ"
+        );
+        assert_eq!(Pinyin::from_marked("nǚ").to_zhuyin().unwrap(), "ㄋㄩˇ");
+        assert_eq!(Pinyin::new("ng", Tone::Second).to_zhuyin(), None);
     }
 
     #[test]
     fn test_pinyin_is_toneless() {
-        let pinyin = Pinyin::new("zhong", 4);
+        let pinyin = Pinyin::new("zhong", Tone::Fourth);
         assert!(!pinyin.is_toneless());
 
-        let pinyin = Pinyin::new("zhong", 5);
+        let pinyin = Pinyin::new("zhong", Tone::Neutral);
         assert!(pinyin.is_toneless());
     }
 
     #[test]
     fn test_pinyin_to_string() {
-        let pinyin = Pinyin::new("zhong", 4);
+        let pinyin = Pinyin::new("zhong", Tone::Fourth);
         assert_eq!(pinyin.to_string(), "zhong4");
     }
 
@@ -211,16 +715,31 @@ mod tests {
     fn test_pinyin_from_string() {
         let pinyin = Pinyin::from_str("zhong4").unwrap();
         assert_eq!(pinyin.pinyin, "zhong");
-        assert_eq!(pinyin.tone, 4);
+        assert_eq!(pinyin.tone_number(), 4);
 
         let pinyin = Pinyin::from_str("zhong").unwrap();
         assert_eq!(pinyin.pinyin, "zhong");
-        assert_eq!(pinyin.tone, 5);
+        assert_eq!(pinyin.tone, Tone::Neutral);
+
+        assert!(matches!(Pinyin::from_str(""), Err(PinyinError::EmptyInput)));
+        assert!(matches!(
+            Pinyin::from_str("zhong0"),
+            Err(PinyinError::InvalidTone { got: 0 })
+        ));
+        assert!(Pinyin::from_str("zhong7").is_err());
+        assert!(matches!(
+            Pinyin::from_str("4"),
+            Err(PinyinError::InvalidSyllable(s)) if s == "4"
+        ));
+        assert!(Pinyin::from_str("zhong\u{0663}").is_err());
     }
 
     #[test]
     fn test_pinyin_word_new() {
-        let pinyin = vec![Pinyin::new("zhong", 4), Pinyin::new("chong", 2)];
+        let pinyin = vec![
+            Pinyin::new("zhong", Tone::Fourth),
+            Pinyin::new("chong", Tone::Second),
+        ];
         let pinyin_word = PinyinWord::new("重", pinyin);
         assert_eq!(pinyin_word.word, "重");
         assert_eq!(pinyin_word.pinyin.len(), 2);
@@ -228,7 +747,10 @@ mod tests {
 
     #[test]
     fn test_pinyin_word_to_string() {
-        let pinyin = vec![Pinyin::new("zhong", 4), Pinyin::new("chong", 2)];
+        let pinyin = vec![
+            Pinyin::new("zhong", Tone::Fourth),
+            Pinyin::new("chong", Tone::Second),
+        ];
         let pinyin_word = PinyinWord::new("重", pinyin);
         assert_eq!(pinyin_word.to_string(), "重:zhong4 chong2");
     }
@@ -249,79 +771,295 @@ mod tests {
         assert_eq!(pinyin_word.word, "重庆口味");
         assert_eq!(pinyin_word.pinyin.len(), 4);
         assert_eq!(pinyin_word.to_string(), "重庆口味:chong2 qing4 kou3 wei4");
-    }
 
-    #[test]
-    fn test_mark_vowel() {
-        assert_eq!(mark_vowel('a', 1), 'ā');
-        assert_eq!(mark_vowel('a', 2), 'á');
-        assert_eq!(mark_vowel('a', 3), 'ǎ');
-        assert_eq!(mark_vowel('a', 4), 'à');
-        assert_eq!(mark_vowel('a', 5), 'a');
-        assert_eq!(mark_vowel('e', 1), 'ē');
-        assert_eq!(mark_vowel('e', 2), 'é');
-        assert_eq!(mark_vowel('e', 3), 'ě');
-        assert_eq!(mark_vowel('e', 4), 'è');
-        assert_eq!(mark_vowel('e', 5), 'e');
-        assert_eq!(mark_vowel('i', 1), 'ī');
-        assert_eq!(mark_vowel('i', 2), 'í');
-        assert_eq!(mark_vowel('i', 3), 'ǐ');
-        assert_eq!(mark_vowel('i', 4), 'ì');
-        assert_eq!(mark_vowel('i', 5), 'i');
-        assert_eq!(mark_vowel('o', 1), 'ō');
-        assert_eq!(mark_vowel('o', 2), 'ó');
-        assert_eq!(mark_vowel('o', 3), 'ǒ');
-        assert_eq!(mark_vowel('o', 4), 'ò');
-        assert_eq!(mark_vowel('o', 5), 'o');
-        assert_eq!(mark_vowel('u', 1), 'ū');
-        assert_eq!(mark_vowel('u', 2), 'ú');
-        assert_eq!(mark_vowel('u', 3), 'ǔ');
-        assert_eq!(mark_vowel('u', 4), 'ù');
-        assert_eq!(mark_vowel('u', 5), 'u');
-        assert_eq!(mark_vowel('ü', 1), 'ǖ');
-        assert_eq!(mark_vowel('ü', 2), 'ǘ');
-        assert_eq!(mark_vowel('ü', 3), 'ǚ');
-        assert_eq!(mark_vowel('ü', 4), 'ǜ');
-        assert_eq!(mark_vowel('ü', 5), 'ü');
+        for s in ["重", "重:zhong9", "重:"] {
+            assert!(matches!(
+                PinyinWord::from_str(s),
+                Err(PinyinError::DictionaryParse { line: 1, content }) if content == s
+            ));
+        }
     }
 
     #[test]
-    #[should_panic]
-    fn test_mark_vowel_panic_with_invalid_vowel() {
-        mark_vowel('b', 1);
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let word = PinyinWord::from_str("重庆:chong2 qing4").unwrap();
+        let json = serde_json::to_string(&word).unwrap();
+        assert_eq!(
+            json,
+            r#"{"word":"重庆","pinyin":[{"pinyin":"chong","tone":2},{"pinyin":"qing","tone":4}]}"#
+        );
+        assert_eq!(serde_json::from_str::<PinyinWord>(&json).unwrap(), word);
+        for json in [
+            r#"{"pinyin":"","tone":1}"#,
+            r#"{"pinyin":"zh ong","tone":1}"#,
+            r#"{"pinyin":"zhong","tone":6}"#,
+        ] {
+            assert!(serde_json::from_str::<Pinyin>(json).is_err(), "{}", json);
+        }
+
+        assert_eq!(
+            serde_json::to_string(&(ToneStyle::Number, YuFormat::V)).unwrap(),
+            r#"["Number","V"]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ToneStyle>(r#""MarkCombining""#).unwrap(),
+            ToneStyle::MarkCombining
+        );
     }
 
-    // TODO
     #[test]
-    #[ignore]
-    #[should_panic]
-    fn test_mark_vowel_panic_with_invalid_tone() {
-        mark_vowel('a', 6);
+    fn test_mark_vowel() {
+        assert_eq!(mark_vowel('a', Tone::First), 'ā');
+        assert_eq!(mark_vowel('a', Tone::Second), 'á');
+        assert_eq!(mark_vowel('a', Tone::Third), 'ǎ');
+        assert_eq!(mark_vowel('a', Tone::Fourth), 'à');
+        assert_eq!(mark_vowel('a', Tone::Neutral), 'a');
+        assert_eq!(mark_vowel('e', Tone::First), 'ē');
+        assert_eq!(mark_vowel('e', Tone::Second), 'é');
+        assert_eq!(mark_vowel('e', Tone::Third), 'ě');
+        assert_eq!(mark_vowel('e', Tone::Fourth), 'è');
+        assert_eq!(mark_vowel('e', Tone::Neutral), 'e');
+        assert_eq!(mark_vowel('i', Tone::First), 'ī');
+        assert_eq!(mark_vowel('i', Tone::Second), 'í');
+        assert_eq!(mark_vowel('i', Tone::Third), 'ǐ');
+        assert_eq!(mark_vowel('i', Tone::Fourth), 'ì');
+        assert_eq!(mark_vowel('i', Tone::Neutral), 'i');
+        assert_eq!(mark_vowel('o', Tone::First), 'ō');
+        assert_eq!(mark_vowel('o', Tone::Second), 'ó');
+        assert_eq!(mark_vowel('o', Tone::Third), 'ǒ');
+        assert_eq!(mark_vowel('o', Tone::Fourth), 'ò');
+        assert_eq!(mark_vowel('o', Tone::Neutral), 'o');
+        assert_eq!(mark_vowel('u', Tone::First), 'ū');
+        assert_eq!(mark_vowel('u', Tone::Second), 'ú');
+        assert_eq!(mark_vowel('u', Tone::Third), 'ǔ');
+        assert_eq!(mark_vowel('u', Tone::Fourth), 'ù');
+        assert_eq!(mark_vowel('u', Tone::Neutral), 'u');
+        assert_eq!(mark_vowel('ü', Tone::First), 'ǖ');
+        assert_eq!(mark_vowel('ü', Tone::Second), 'ǘ');
+        assert_eq!(mark_vowel('ü', Tone::Third), 'ǚ');
+        assert_eq!(mark_vowel('ü', Tone::Fourth), 'ǜ');
+        assert_eq!(mark_vowel('ü', Tone::Neutral), 'ü');
     }
 
-    // TODO
     #[test]
-    #[ignore]
     #[should_panic]
-    fn test_mark_vowel_panic_with_zero_tone() {
-        mark_vowel('a', 0);
+    fn test_mark_vowel_panic_with_invalid_vowel() {
+        mark_vowel('b', Tone::First);
     }
 
     #[test]
     fn test_mark_vowel_with_toneless() {
-        assert_eq!(mark_vowel('a', 5), 'a');
+        assert_eq!(mark_vowel('a', Tone::Neutral), 'a');
     }
 
     #[test]
     fn test_pinyin_format() {
-        let pinyin = Pinyin::new("zhong", 4);
+        let pinyin = Pinyin::new("zhong", Tone::Fourth);
         assert_eq!(pinyin.format(ToneStyle::Number), "zhong4");
         assert_eq!(pinyin.format(ToneStyle::Mark), "zhòng");
         assert_eq!(pinyin.format(ToneStyle::None), "zhong");
+        assert_eq!(pinyin.format(ToneStyle::MarkCombining), "zho\u{300}ng");
+        assert_eq!(pinyin.format(ToneStyle::Superscript), "zhong⁴");
+        assert_eq!(pinyin.format(ToneStyle::Contour), "zhong51");
+        assert_eq!(pinyin.format(ToneStyle::ToneLetter), "zhong˥˩");
+        assert_eq!(
+            Pinyin::new("ni", Tone::Third).format(ToneStyle::Contour),
+            "ni214"
+        );
+        assert_eq!(
+            Pinyin::new("ni", Tone::Third).format(ToneStyle::ToneLetter),
+            "ni˨˩˦"
+        );
+
+        let pinyin = Pinyin::new("lü", Tone::Third);
+        assert_eq!(pinyin.format(ToneStyle::MarkCombining), "lu\u{308}\u{30C}");
+        assert_eq!(Pinyin::from_marked("lu\u{308}\u{30C}"), pinyin);
 
-        let pinyin = Pinyin::new("a", 5);
+        let pinyin = Pinyin::new("a", Tone::Neutral);
         assert_eq!(pinyin.format(ToneStyle::Number), "a5");
+        assert_eq!(pinyin.format(ToneStyle::Superscript), "a⁵");
+        assert_eq!(pinyin.format(ToneStyle::Contour), "a0");
+        assert_eq!(pinyin.render(&NeutralTone::Five, YuFormat::V), "a5");
+        assert_eq!(pinyin.render(&NeutralTone::Zero, YuFormat::V), "a0");
+        assert_eq!(pinyin.render(&NeutralTone::Omit, YuFormat::V), "a");
+        assert_eq!(
+            Pinyin::new("lü", Tone::Fourth).render(&NeutralTone::Omit, YuFormat::V),
+            "lv4"
+        );
+        assert_eq!(pinyin.format(ToneStyle::ToneLetter), "a");
         assert_eq!(pinyin.format(ToneStyle::Mark), "a");
         assert_eq!(pinyin.format(ToneStyle::None), "a");
     }
+
+    #[test]
+    fn test_pinyin_try_from_marked() {
+        assert_eq!(
+            Pinyin::try_from_marked("m\u{300}").unwrap(),
+            Pinyin::new("m", Tone::Fourth)
+        );
+        assert_eq!(
+            Pinyin::try_from_marked("ê\u{304}").unwrap(),
+            Pinyin::new("ê", Tone::First)
+        );
+        assert_eq!(
+            Pinyin::try_from_marked("\u{E7C7}").unwrap(),
+            Pinyin::new("m", Tone::Second)
+        );
+        assert_eq!(
+            Pinyin::try_from_marked("Zhōng").unwrap(),
+            Pinyin::new("Zhong", Tone::First)
+        );
+        assert_eq!(
+            Pinyin::try_from_marked("ZHŌNG").unwrap(),
+            Pinyin::new("ZHONG", Tone::First)
+        );
+        for s in ["", "zhōngguó", "zhong1", "zh-ong", "\u{304}a"] {
+            assert!(
+                matches!(
+                    Pinyin::try_from_marked(s),
+                    Err(PinyinError::InvalidSyllable(_))
+                ),
+                "{}",
+                s
+            );
+        }
+        assert_eq!(
+            parse_readings("zhuó,zuó chī").unwrap(),
+            vec![
+                Pinyin::new("zhuo", Tone::Second),
+                Pinyin::new("zuo", Tone::Second),
+                Pinyin::new("chi", Tone::First)
+            ]
+        );
+        assert!(parse_readings("zhōng 1").is_err());
+    }
+
+    #[test]
+    fn test_pinyin_from_marked() {
+        assert_eq!(
+            Pinyin::from_marked("zhōng"),
+            Pinyin::new("zhong", Tone::First)
+        );
+        assert_eq!(Pinyin::from_marked("lǜ"), Pinyin::new("lü", Tone::Fourth));
+        assert_eq!(Pinyin::from_marked("de"), Pinyin::new("de", Tone::Neutral));
+        assert_eq!(Pinyin::from_marked("ǹ"), Pinyin::new("n", Tone::Fourth));
+        assert_eq!(unmark_vowel('b'), None);
+    }
+
+    #[test]
+    fn test_pinyin_word_format() {
+        let word = PinyinWord::new(
+            "中国",
+            vec![
+                Pinyin::new("zhong", Tone::First),
+                Pinyin::new("guo", Tone::Second),
+            ],
+        );
+        assert_eq!(word.format(ToneStyle::Mark, " "), "zhōng guó");
+        assert_eq!(word.format(ToneStyle::Number, "-"), "zhong1-guo2");
+
+        let word = PinyinWord::new("，", vec![]);
+        assert!(!word.is_matched());
+        assert_eq!(word.format(ToneStyle::Mark, " "), "，");
+    }
+
+    #[test]
+    fn test_pinyin_format_with_yu() {
+        let cases = [
+            // 女
+            (("nü", 3), YuFormat::Umlaut, ["nü3", "nǚ", "nü"]),
+            (("nü", 3), YuFormat::V, ["nv3", "nǚ", "nv"]),
+            (("nü", 3), YuFormat::U, ["nu3", "nǔ", "nu"]),
+            (("nv", 3), YuFormat::Umlaut, ["nü3", "nǚ", "nü"]),
+            // 略
+            (("lüe", 4), YuFormat::V, ["lve4", "lvè", "lve"]),
+            // 鱼
+            (("yu", 2), YuFormat::Umlaut, ["yu2", "yú", "yu"]),
+            (("yu", 2), YuFormat::V, ["yu2", "yú", "yu"]),
+            (("yü", 2), YuFormat::Umlaut, ["yu2", "yú", "yu"]),
+            // 约
+            (("yue", 1), YuFormat::V, ["yue1", "yuē", "yue"]),
+            // 居
+            (("jv", 1), YuFormat::Umlaut, ["ju1", "jū", "ju"]),
+            (("xue", 2), YuFormat::V, ["xue2", "xué", "xue"]),
+        ];
+
+        for ((pinyin, tone), yu_format, [number, mark, none]) in cases {
+            let pinyin = Pinyin::try_from((pinyin, tone)).unwrap();
+            assert_eq!(pinyin.format_with(ToneStyle::Number, yu_format), number);
+            assert_eq!(pinyin.format_with(ToneStyle::Mark, yu_format), mark);
+            assert_eq!(pinyin.format_with(ToneStyle::None, yu_format), none);
+        }
+    }
+
+    #[test]
+    fn test_pinyin_render() {
+        struct ToneLetters;
+
+        impl ToneRenderer for ToneLetters {
+            fn render(&self, pinyin: &str, tone: Tone) -> String {
+                let letters = ["˥", "˧˥", "˨˩˦", "˥˩", ""];
+                format!("{}{}", pinyin, letters[tone as usize - 1])
+            }
+        }
+
+        let pinyin = Pinyin::new("nü", Tone::Third);
+        assert_eq!(pinyin.render(&ToneLetters, YuFormat::Umlaut), "nü˨˩˦");
+        assert_eq!(pinyin.render(&ToneLetters, YuFormat::V), "nv˨˩˦");
+        assert_eq!(pinyin.render(&ToneStyle::Mark, YuFormat::Umlaut), "nǚ");
+        assert_eq!(
+            pinyin.render(&|p: &str, t: Tone| format!("{}({})", p, t), YuFormat::U),
+            "nu(3)"
+        );
+    }
+
+    #[test]
+    fn test_mark_placement_table() {
+        // 每个韵母及其零声母写法，四个声调轮换
+        let table = "ā bó kě dài bèi māo dǒu bàn fēn táng zhěng zhōng ér \
+            bī jiá tiě xiào liū jiǎn pīn liáng xǐng qiōng \
+            gū huá guǒ shuài guī guǎn chūn zhuáng \
+            nǚ lüè lǘ juān xuě qún \
+            yī yá yě yào yōu yán yǐn yàng yīng yǒng \
+            wū wá wǒ wài wēi wán wěn wàng wēng \
+            yū yuè yuán yǔn ế ề \
+            m\u{304} ḿ m\u{30C} m\u{300} n\u{304} ń ň ǹ \
+            n\u{304}g ńg ňg ǹg hm\u{304} hḿ hm\u{30C} hm\u{300}";
+        for marked in table.split_whitespace() {
+            let pinyin = Pinyin::from_marked(marked);
+            assert_eq!(pinyin.format(ToneStyle::Mark), marked, "{:?}", pinyin);
+        }
+    }
+
+    #[test]
+    fn test_mark_placement() {
+        assert_eq!(format_tone("zhong", Tone::First), "zhōng");
+        assert_eq!(format_tone("guo", Tone::Second), "guó");
+        assert_eq!(format_tone("n", Tone::Fourth), "ǹ");
+        assert_eq!(format_tone("ng", Tone::Third), "ňg");
+        assert_eq!(format_tone("M", Tone::Second), "Ḿ");
+        assert_eq!(format_tone("n", Tone::Neutral), "n");
+        assert_eq!(Pinyin::from_marked("ǹ").format(ToneStyle::Mark), "ǹ");
+
+        let first_vowel =
+            MarkWith(|chars: &[char]| chars.iter().position(|c| "aeiouü".contains(*c)));
+        assert_eq!(first_vowel.render("guo", Tone::Second), "gúo");
+        assert_eq!(first_vowel.render("hao", Tone::Third), "hǎo");
+
+        assert_eq!(format_tone("Ou", Tone::First), "Ōu");
+        assert_eq!(format_tone("LÜE", Tone::Fourth), "LÜÈ");
+        assert_eq!(format_tone("ê", Tone::Second), "ế");
+        assert_eq!(format_tone("ê", Tone::First), "ê\u{304}");
+        assert_eq!(format_tone("Ê", Tone::Second), "Ế");
+        assert_eq!(format_tone("Ê", Tone::Third), "Ê\u{30C}");
+        assert_eq!(format_tone("Ê", Tone::Fourth), "Ề");
+        assert_eq!(Pinyin::new("Ê", Tone::Second).format(ToneStyle::Mark), "Ế");
+
+        let nowhere = MarkWith(|_: &[char]| Some(42));
+        assert_eq!(
+            Pinyin::new("hao", Tone::Third).render(&nowhere, YuFormat::Umlaut),
+            "hao"
+        );
+    }
 }
@@ -0,0 +1,363 @@
+use crate::pinyin::{contour, superscript, tone_letter, ToneStyle};
+use crate::tone::Tone;
+
+// 拼音以外的拼写与转写方案，由拼音音节按规则转换
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Romanization {
+    // 威妥玛拼音："ch'ung²-ch'ing⁴"，送气音用撇号表示
+    WadeGiles,
+    // 俄语的巴拉第汉字转写（西里尔字母）："чжун го"，通常不标声调
+    Palladius,
+}
+
+// 威妥玛中与拼音拼写不同的整体音节
+const WADE_GILES_SYLLABLES: [(&str, &str); 20] = [
+    ("zhi", "chih"),
+    ("chi", "ch'ih"),
+    ("shi", "shih"),
+    ("ri", "jih"),
+    ("zi", "tzu"),
+    ("ci", "tz'u"),
+    ("si", "ssu"),
+    ("er", "erh"),
+    ("e", "o"),
+    ("yi", "i"),
+    ("ye", "yeh"),
+    ("you", "yu"),
+    ("yan", "yen"),
+    ("yong", "yung"),
+    ("yu", "yü"),
+    ("yue", "yüeh"),
+    ("yuan", "yüan"),
+    ("yun", "yün"),
+    ("wen", "wên"),
+    ("weng", "wêng"),
+];
+
+// 拼音声母与威妥玛声母
+const WADE_GILES_INITIALS: [(&str, &str); 21] = [
+    ("zh", "ch"),
+    ("ch", "ch'"),
+    ("sh", "sh"),
+    ("b", "p"),
+    ("p", "p'"),
+    ("m", "m"),
+    ("f", "f"),
+    ("d", "t"),
+    ("t", "t'"),
+    ("n", "n"),
+    ("l", "l"),
+    ("g", "k"),
+    ("k", "k'"),
+    ("h", "h"),
+    ("j", "ch"),
+    ("q", "ch'"),
+    ("x", "hs"),
+    ("r", "j"),
+    ("z", "ts"),
+    ("c", "ts'"),
+    ("s", "s"),
+];
+
+// 巴拉第转写中的整体音节：零声母音节与 zhi、zi 等
+const PALLADIUS_SYLLABLES: [(&str, &str); 32] = [
+    ("zhi", "чжи"),
+    ("chi", "чи"),
+    ("shi", "ши"),
+    ("ri", "жи"),
+    ("zi", "цзы"),
+    ("ci", "цы"),
+    ("si", "сы"),
+    ("yi", "и"),
+    ("ya", "я"),
+    ("yo", "ё"),
+    ("yao", "яо"),
+    ("ye", "е"),
+    ("you", "ю"),
+    ("yan", "янь"),
+    ("yin", "инь"),
+    ("yang", "ян"),
+    ("ying", "ин"),
+    ("yong", "юн"),
+    ("yu", "юй"),
+    ("yue", "юэ"),
+    ("yuan", "юань"),
+    ("yun", "юнь"),
+    ("wu", "у"),
+    ("wa", "ва"),
+    ("wo", "во"),
+    ("wai", "вай"),
+    ("wei", "вэй"),
+    ("wan", "вань"),
+    ("wen", "вэнь"),
+    ("wang", "ван"),
+    ("weng", "вэн"),
+    ("er", "эр"),
+];
+
+const PALLADIUS_INITIALS: [(&str, &str); 21] = [
+    ("zh", "чж"),
+    ("ch", "ч"),
+    ("sh", "ш"),
+    ("b", "б"),
+    ("p", "п"),
+    ("m", "м"),
+    ("f", "ф"),
+    ("d", "д"),
+    ("t", "т"),
+    ("n", "н"),
+    ("l", "л"),
+    ("g", "г"),
+    ("k", "к"),
+    ("h", "х"),
+    ("j", "цз"),
+    ("q", "ц"),
+    ("x", "с"),
+    ("r", "ж"),
+    ("z", "цз"),
+    ("c", "ц"),
+    ("s", "с"),
+];
+
+// j、q、x 后的 u 写作 ü；ui 为 uei 的缩写，只有 h 后保留 "хуэй"，其余声母后写作 "уй"
+const PALLADIUS_FINALS: [(&str, &str); 35] = [
+    ("a", "а"),
+    ("o", "о"),
+    ("e", "э"),
+    ("ê", "э"),
+    ("ai", "ай"),
+    ("ei", "эй"),
+    ("ao", "ао"),
+    ("ou", "оу"),
+    ("an", "ань"),
+    ("en", "энь"),
+    ("ang", "ан"),
+    ("eng", "эн"),
+    ("ong", "ун"),
+    ("i", "и"),
+    ("ia", "я"),
+    ("ie", "е"),
+    ("iao", "яо"),
+    ("iu", "ю"),
+    ("ian", "янь"),
+    ("in", "инь"),
+    ("iang", "ян"),
+    ("ing", "ин"),
+    ("iong", "юн"),
+    ("u", "у"),
+    ("ua", "уа"),
+    ("uo", "о"),
+    ("uai", "уай"),
+    ("ui", "уэй"),
+    ("uan", "уань"),
+    ("un", "унь"),
+    ("uang", "уан"),
+    ("ü", "юй"),
+    ("üe", "юэ"),
+    ("üan", "юань"),
+    ("ün", "юнь"),
+];
+
+impl Romanization {
+    // 不带声调的音节："chong" -> "ch'ung"，pinyin 中的 ü 可以写作 v
+    pub fn syllable(&self, pinyin: &str) -> String {
+        match self {
+            Romanization::WadeGiles => wade_giles(pinyin),
+            Romanization::Palladius => palladius(pinyin),
+        }
+    }
+
+    // 声调按 style 输出：Mark 为上标数字 "ch'ung²"，Number 为数字 "ch'ung2"，None 不标
+    // 轻声在 Mark 中不标，Superscript 记作 "⁵"
+    pub fn render(&self, pinyin: &str, tone: Tone, style: ToneStyle) -> String {
+        let syllable = self.syllable(pinyin);
+        match style {
+            ToneStyle::None => syllable,
+            ToneStyle::Number => format!("{}{}", syllable, tone),
+            ToneStyle::Contour => format!("{}{}", syllable, contour(tone)),
+            ToneStyle::ToneLetter => format!("{}{}", syllable, tone_letter(tone)),
+            ToneStyle::Mark | ToneStyle::MarkCombining if tone.is_neutral() => syllable,
+            ToneStyle::Mark | ToneStyle::MarkCombining | ToneStyle::Superscript => {
+                format!("{}{}", syllable, superscript(tone))
+            }
+        }
+    }
+}
+
+fn wade_giles(pinyin: &str) -> String {
+    let pinyin = pinyin.to_lowercase().replace('v', "ü");
+    if let Some((_, syllable)) = WADE_GILES_SYLLABLES.iter().find(|(p, _)| *p == pinyin) {
+        return syllable.to_string();
+    }
+    // 其余 y、w 开头的音节拼写相同
+    if pinyin.starts_with(['y', 'w']) {
+        return pinyin;
+    }
+
+    let (initial, wade_initial) = WADE_GILES_INITIALS
+        .iter()
+        .find(|(p, _)| pinyin.starts_with(p))
+        .copied()
+        .unwrap_or(("", ""));
+    let fin = &pinyin[initial.len()..];
+    // j、q、x 后的 u 读作 ü
+    let fin = match (initial, fin.strip_prefix('u')) {
+        ("j" | "q" | "x", Some(rest)) => format!("ü{}", rest),
+        _ => fin.to_string(),
+    };
+    let velar = matches!(initial, "g" | "k" | "h");
+
+    let fin = match fin.as_str() {
+        "ong" => "ung",
+        "iong" => "iung",
+        "ie" => "ieh",
+        "üe" => "üeh",
+        "ian" => "ien",
+        "e" if velar => "o",
+        "e" => "ê",
+        "en" => "ên",
+        "eng" => "êng",
+        "uo" if !(velar || initial == "sh") => "o",
+        "ui" if matches!(initial, "g" | "k") => "uei",
+        fin => fin,
+    };
+
+    format!("{}{}", wade_initial, fin)
+}
+
+// 没有对应写法的音节（如叹词 ng、hm）原样返回
+fn palladius(pinyin: &str) -> String {
+    let pinyin = pinyin.to_lowercase().replace('v', "ü");
+    if let Some((_, syllable)) = PALLADIUS_SYLLABLES.iter().find(|(p, _)| *p == pinyin) {
+        return syllable.to_string();
+    }
+
+    let (initial, cyrillic) = PALLADIUS_INITIALS
+        .iter()
+        .find(|(p, _)| pinyin.starts_with(p))
+        .copied()
+        .unwrap_or(("", ""));
+    let fin = &pinyin[initial.len()..];
+    let fin = match (initial, fin.strip_prefix('u')) {
+        ("j" | "q" | "x", Some(rest)) => format!("ü{}", rest),
+        _ => fin.to_string(),
+    };
+
+    match PALLADIUS_FINALS.iter().find(|(p, _)| *p == fin) {
+        Some((_, _)) if fin == "ui" && initial != "h" => format!("{}уй", cyrillic),
+        Some((_, fin)) => format!("{}{}", cyrillic, fin),
+        None => pinyin,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Romanization;
+    use crate::pinyin::ToneStyle;
+    use crate::tone::Tone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_wade_giles() {
+        let cases = [
+            ("chong", "ch'ung"),
+            ("qing", "ch'ing"),
+            ("xi", "hsi"),
+            ("zhi", "chih"),
+            ("si", "ssu"),
+            ("ci", "tz'u"),
+            ("zhong", "chung"),
+            ("guo", "kuo"),
+            ("duo", "to"),
+            ("shuo", "shuo"),
+            ("ruo", "jo"),
+            ("gui", "kuei"),
+            ("dui", "tui"),
+            ("he", "ho"),
+            ("de", "tê"),
+            ("heng", "hêng"),
+            ("xue", "hsüeh"),
+            ("lüe", "lüeh"),
+            ("lv", "lü"),
+            ("jun", "chün"),
+            ("quan", "ch'üan"),
+            ("xiong", "hsiung"),
+            ("tian", "t'ien"),
+            ("xie", "hsieh"),
+            ("ba", "pa"),
+            ("pao", "p'ao"),
+            ("cao", "ts'ao"),
+            ("yi", "i"),
+            ("yuan", "yüan"),
+            ("yang", "yang"),
+            ("wo", "wo"),
+            ("er", "erh"),
+            ("an", "an"),
+            ("en", "ên"),
+        ];
+        for (pinyin, want) in cases {
+            assert_eq!(Romanization::WadeGiles.syllable(pinyin), want, "{}", pinyin);
+        }
+
+        let wade_giles = Romanization::WadeGiles;
+        assert_eq!(
+            wade_giles.render("chong", Tone::Second, ToneStyle::Mark),
+            "ch'ung²"
+        );
+        assert_eq!(
+            wade_giles.render("qing", Tone::Fourth, ToneStyle::Number),
+            "ch'ing4"
+        );
+        assert_eq!(
+            wade_giles.render("de", Tone::Neutral, ToneStyle::Mark),
+            "tê"
+        );
+        assert_eq!(wade_giles.render("xi", Tone::First, ToneStyle::None), "hsi");
+    }
+
+    #[test]
+    fn test_palladius() {
+        let cases = [
+            ("zhong", "чжун"),
+            ("guo", "го"),
+            ("bei", "бэй"),
+            ("jing", "цзин"),
+            ("shang", "шан"),
+            ("hai", "хай"),
+            ("mao", "мао"),
+            ("ze", "цзэ"),
+            ("dong", "дун"),
+            ("xie", "се"),
+            ("liu", "лю"),
+            ("tian", "тянь"),
+            ("lü", "люй"),
+            ("xu", "сюй"),
+            ("xue", "сюэ"),
+            ("quan", "цюань"),
+            ("jun", "цзюнь"),
+            ("xiong", "сюн"),
+            ("zi", "цзы"),
+            ("si", "сы"),
+            ("shi", "ши"),
+            ("ri", "жи"),
+            ("yi", "и"),
+            ("yu", "юй"),
+            ("wei", "вэй"),
+            ("hui", "хуэй"),
+            ("gui", "гуй"),
+            ("kui", "куй"),
+            ("dui", "дуй"),
+            ("shui", "шуй"),
+            ("an", "ань"),
+            ("er", "эр"),
+            ("ng", "ng"),
+        ];
+        for (pinyin, want) in cases {
+            assert_eq!(Romanization::Palladius.syllable(pinyin), want, "{}", pinyin);
+        }
+        assert_eq!(
+            Romanization::Palladius.render("hua", Tone::Second, ToneStyle::None),
+            "хуа"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::numeral::is_digit;
+use crate::pinyin::{Pinyin, PinyinWord};
+use crate::tone::Tone;
+
+// 每个字的读音在 words 中的位置：(词语下标, 音节下标, 汉字)，没有读音的字为 None，变调不跨过它们
+fn slots(words: &[PinyinWord]) -> Vec<Option<(usize, usize, char)>> {
+    let mut slots = vec![];
+    for (i, word) in words.iter().enumerate() {
+        let count = word.word.chars().count();
+        if word.pinyin.is_empty() || (count > 1 && count != word.pinyin.len()) {
+            slots.extend(word.word.chars().map(|_| None));
+        } else {
+            slots.extend(word.word.chars().enumerate().map(|(j, c)| Some((i, j, c))));
+        }
+    }
+    slots
+}
+
+// 单字改写第一个候选读音，其余候选保留在后面
+fn set_tone(words: &mut [PinyinWord], (i, j, _): (usize, usize, char), tone: Tone) {
+    let word = &mut words[i];
+    if word.word.chars().count() > 1 {
+        word.pinyin[j].tone = tone;
+        return;
+    }
+    let pinyin = Pinyin {
+        pinyin: word.pinyin[0].pinyin.clone(),
+        tone,
+    };
+    word.pinyin.retain(|p| *p != pinyin);
+    word.pinyin.insert(0, pinyin);
+}
+
+// "一"、"不" 的变调：
+// 一：在四声（以及轻声）前读 yí，在一、二、三声前读 yì，序数、逐位读的数字与句末读 yī，"看一看" 读轻声
+// 不：在四声前读 bú，其余读 bù，"好不好" 读轻声
+pub(crate) fn apply_yi_bu(words: &mut [PinyinWord]) {
+    let slots = slots(words);
+    let reading = |words: &[PinyinWord], slot: Option<(usize, usize, char)>| {
+        slot.map(|(i, j, c)| (c, words[i].pinyin[j].clone()))
+    };
+
+    for k in 0..slots.len() {
+        let slot = match slots[k] {
+            Some(slot) => slot,
+            None => continue,
+        };
+        let (c, current) = match reading(words, Some(slot)) {
+            Some(reading) => reading,
+            None => continue,
+        };
+        let prev = k.checked_sub(1).and_then(|k| reading(words, slots[k]));
+        let next = slots.get(k + 1).and_then(|s| reading(words, *s));
+        let reduplicated = matches!((&prev, &next), (Some((a, _)), Some((b, _))) if a == b);
+
+        let tone = match (c, current.pinyin.as_str()) {
+            ('一', "yi") => match (&prev, &next) {
+                (Some(('第', _)), _) => Tone::First,
+                (Some((p, _)), _) if is_digit(*p) || *p == '十' => Tone::First,
+                (_, Some((n, _))) if is_digit(*n) => Tone::First,
+                _ if reduplicated => Tone::Neutral,
+                (_, Some((_, n))) if n.tone == Tone::Fourth || n.tone.is_neutral() => Tone::Second,
+                (_, Some(_)) => Tone::Fourth,
+                (_, None) => Tone::First,
+            },
+            ('不', "bu") => match &next {
+                _ if reduplicated => Tone::Neutral,
+                Some((_, n)) if n.tone == Tone::Fourth => Tone::Second,
+                _ => Tone::Fourth,
+            },
+            _ => continue,
+        };
+        if tone != current.tone {
+            set_tone(words, slot, tone);
+        }
+    }
+}
+
+// 上声连读变调：相连的上声，前一个读作阳平，"你好" -> "ní hǎo"
+// 连续三个以上时按词语分组，词内先变；单字后面接多字词时单字保持上声，"很美好" -> "hěn méi hǎo"
+pub(crate) fn apply_third_tone(words: &mut [PinyinWord]) {
+    let slots = slots(words);
+    let is_third = |words: &[PinyinWord], slot: Option<(usize, usize, char)>| {
+        slot.is_some_and(|(i, j, _)| words[i].pinyin[j].tone == Tone::Third)
+    };
+    // 同一个词在连续上声中的音节数
+    let run_len = |slots: &[Option<(usize, usize, char)>], word: usize| {
+        slots
+            .iter()
+            .filter(|s| matches!(s, Some((i, _, _)) if *i == word))
+            .count()
+    };
+
+    let mut start = 0;
+    while start < slots.len() {
+        let len = slots[start..]
+            .iter()
+            .take_while(|s| is_third(words, **s))
+            .count();
+        let run: Vec<(usize, usize, char)> = slots[start..start + len]
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+
+        let mut changes = vec![];
+        for pair in run.windows(2) {
+            let (current, next) = (pair[0], pair[1]);
+            let keep = current.0 != next.0
+                && run_len(&slots[start..start + len], current.0) == 1
+                && run_len(&slots[start..start + len], next.0) > 1;
+            if !keep {
+                changes.push(current);
+            }
+        }
+        for slot in changes {
+            set_tone(words, slot, Tone::Second);
+        }
+
+        start += len.max(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_third_tone, apply_yi_bu};
+    use crate::{Converter, ToneStyle};
+    use pretty_assertions::assert_eq;
+
+    fn sandhi(input: &str) -> String {
+        let mut words = Converter::new(input).convert();
+        apply_yi_bu(&mut words);
+        apply_third_tone(&mut words);
+        words
+            .iter()
+            .flat_map(|w| w.syllables())
+            .map(|p| p.format(ToneStyle::Mark))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_apply_yi_bu() {
+        assert_eq!(sandhi("一样"), "yí yàng");
+        assert_eq!(sandhi("一天"), "yì tiān");
+        assert_eq!(sandhi("一年"), "yì nián");
+        assert_eq!(sandhi("一起"), "yì qǐ");
+        assert_eq!(sandhi("看一看"), "kàn yi kàn");
+        assert_eq!(sandhi("第一"), "dì yī");
+        assert_eq!(sandhi("统一"), "tǒng yī");
+        assert_eq!(sandhi("一二三"), "yī èr sān");
+        assert_eq!(sandhi("十一"), "shí yī");
+        assert_eq!(sandhi("一万"), "yí wàn");
+        assert_eq!(sandhi("不对"), "bú duì");
+        assert_eq!(sandhi("不是"), "bú shì");
+        assert_eq!(sandhi("不行"), "bù xíng");
+        assert_eq!(sandhi("好不好"), "hǎo bu hǎo");
+    }
+
+    #[test]
+    fn test_apply_third_tone() {
+        assert_eq!(sandhi("你好"), "ní hǎo");
+        assert_eq!(sandhi("水果"), "shuí guǒ");
+        assert_eq!(sandhi("我很好"), "wó hén hǎo");
+        assert_eq!(sandhi("展览馆"), "zhán lán guǎn");
+        assert_eq!(sandhi("很美好"), "hěn méi hǎo");
+        assert_eq!(sandhi("你好，老鼠"), "ní hǎo láo shǔ");
+        assert_eq!(sandhi("好人"), "hǎo rén");
+    }
+}
@@ -0,0 +1,239 @@
+use crate::pinyin::{split_initial, ToneRenderer};
+use crate::tone::Tone;
+
+// 双拼方案，每个音节编码为声母键 + 韵母键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShuangpinScheme {
+    // 小鹤双拼
+    Xiaohe,
+    // 自然码
+    Ziranma,
+    // 微软双拼
+    Microsoft,
+}
+
+impl ShuangpinScheme {
+    // 编码一个不带声调的音节，"zhuang" -> "vl"（小鹤）
+    // 无法编码的音节（如叹词 "hm"、"ng"）返回 None
+    pub fn encode(&self, syllable: &str) -> Option<String> {
+        let syllable = syllable.to_lowercase().replace('v', "ü");
+        let (initial, fin) = split_initial(&syllable);
+        if fin.is_empty() {
+            return None;
+        }
+
+        if initial.is_empty() {
+            return self.encode_zero_initial(fin);
+        }
+
+        let initial = match initial {
+            "zh" => 'v',
+            "ch" => 'i',
+            "sh" => 'u',
+            _ => initial.chars().next()?,
+        };
+        let fin = if fin.chars().count() == 1 {
+            match fin {
+                "a" | "o" | "e" | "i" | "u" => fin.chars().next()?,
+                // 只有微软双拼把 ü 放在 y 键上
+                "ü" if *self == Self::Microsoft => 'y',
+                "ü" => 'v',
+                _ => return None,
+            }
+        } else {
+            self.final_key(fin)?
+        };
+
+        Some(format!("{}{}", initial, fin))
+    }
+
+    // 零声母音节："ang" -> "ah"（小鹤、自然码），"oh"（微软）
+    fn encode_zero_initial(&self, fin: &str) -> Option<String> {
+        if *self == Self::Microsoft {
+            let key = match fin {
+                "a" | "e" | "o" => fin.chars().next()?,
+                _ => self.final_key(fin)?,
+            };
+            return Some(format!("o{}", key));
+        }
+
+        // ê 没有对应的键位
+        if !fin.is_ascii() {
+            return None;
+        }
+        let code = match fin.len() {
+            1 => fin.repeat(2),
+            2 => fin.to_string(),
+            _ => format!("{}{}", &fin[..1], self.final_key(fin)?),
+        };
+        Some(code)
+    }
+
+    fn final_key(&self, fin: &str) -> Option<char> {
+        let key = match self {
+            Self::Xiaohe => match fin {
+                "iu" => 'q',
+                "ei" => 'w',
+                "uan" | "üan" => 'r',
+                "ue" | "üe" => 't',
+                "un" | "ün" => 'y',
+                "uo" => 'o',
+                "ie" => 'p',
+                "ong" | "iong" => 's',
+                "ai" => 'd',
+                "en" => 'f',
+                "eng" => 'g',
+                "ang" => 'h',
+                "an" => 'j',
+                "uai" | "ing" => 'k',
+                "iang" | "uang" => 'l',
+                "ou" => 'z',
+                "ua" | "ia" => 'x',
+                "ao" => 'c',
+                "ui" => 'v',
+                "in" => 'b',
+                "iao" => 'n',
+                "ian" => 'm',
+                "er" => 'r',
+                _ => return None,
+            },
+            Self::Ziranma => match fin {
+                "iu" => 'q',
+                "ua" | "ia" => 'w',
+                "uan" | "üan" => 'r',
+                "ue" | "üe" => 't',
+                "ing" | "uai" => 'y',
+                "uo" => 'o',
+                "un" | "ün" => 'p',
+                "ong" | "iong" => 's',
+                "iang" | "uang" => 'd',
+                "en" => 'f',
+                "eng" => 'g',
+                "ang" => 'h',
+                "an" => 'j',
+                "ao" => 'k',
+                "ai" => 'l',
+                "ei" => 'z',
+                "ie" => 'x',
+                "iao" => 'c',
+                "ui" => 'v',
+                "ou" => 'b',
+                "in" => 'n',
+                "ian" => 'm',
+                "er" => 'r',
+                _ => return None,
+            },
+            Self::Microsoft => match fin {
+                "iu" => 'q',
+                "ia" | "ua" => 'w',
+                "uan" | "üan" => 'r',
+                "ue" => 't',
+                "uai" => 'y',
+                "uo" => 'o',
+                "un" | "ün" => 'p',
+                "ong" | "iong" => 's',
+                "iang" | "uang" => 'd',
+                "en" => 'f',
+                "eng" => 'g',
+                "ang" => 'h',
+                "an" => 'j',
+                "ao" => 'k',
+                "ai" => 'l',
+                "ing" => ';',
+                "ei" => 'z',
+                "ie" => 'x',
+                "iao" => 'c',
+                "ui" | "üe" => 'v',
+                "ou" => 'b',
+                "in" => 'n',
+                "ian" => 'm',
+                "er" => 'r',
+                _ => return None,
+            },
+        };
+
+        Some(key)
+    }
+}
+
+// 无法编码的音节原样输出
+impl ToneRenderer for ShuangpinScheme {
+    fn render(&self, pinyin: &str, _: Tone) -> String {
+        self.encode(pinyin).unwrap_or_else(|| pinyin.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShuangpinScheme;
+    use crate::syllable::SYLLABLES;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_encode() {
+        let cases = [
+            ("zhuang", ["vl", "vd", "vd"]),
+            ("shuang", ["ul", "ud", "ud"]),
+            ("xiong", ["xs", "xs", "xs"]),
+            ("ying", ["yk", "yy", "y;"]),
+            ("lü", ["lv", "lv", "ly"]),
+            ("lve", ["lt", "lt", "lv"]),
+            ("jue", ["jt", "jt", "jt"]),
+            ("yuan", ["yr", "yr", "yr"]),
+            ("a", ["aa", "aa", "oa"]),
+            ("ai", ["ai", "ai", "ol"]),
+            ("ang", ["ah", "ah", "oh"]),
+            ("er", ["er", "er", "or"]),
+            ("ma", ["ma", "ma", "ma"]),
+        ];
+        let schemes = [
+            ShuangpinScheme::Xiaohe,
+            ShuangpinScheme::Ziranma,
+            ShuangpinScheme::Microsoft,
+        ];
+        for (syllable, codes) in cases {
+            for (scheme, code) in schemes.iter().zip(codes) {
+                assert_eq!(
+                    scheme.encode(syllable).as_deref(),
+                    Some(code),
+                    "{:?} {}",
+                    scheme,
+                    syllable
+                );
+            }
+        }
+
+        assert_eq!(ShuangpinScheme::Xiaohe.encode("hm"), None);
+        assert_eq!(ShuangpinScheme::Xiaohe.encode("ng"), None);
+        assert_eq!(ShuangpinScheme::Xiaohe.encode("n"), None);
+        assert_eq!(ShuangpinScheme::Xiaohe.encode("ê"), None);
+        assert_eq!(ShuangpinScheme::Microsoft.encode("ê"), None);
+    }
+
+    #[test]
+    fn test_encode_all_syllables() {
+        let schemes = [
+            ShuangpinScheme::Xiaohe,
+            ShuangpinScheme::Ziranma,
+            ShuangpinScheme::Microsoft,
+        ];
+        // 叹词与 ê 没有双拼编码
+        let syllables = SYLLABLES
+            .iter()
+            .filter(|s| !["hm", "hng", "m", "n", "ng", "ê"].contains(s));
+
+        for syllable in syllables {
+            for scheme in schemes {
+                let code = scheme.encode(syllable);
+                assert_eq!(
+                    code.as_ref().map(|c| c.chars().count()),
+                    Some(2),
+                    "{:?} {} {:?}",
+                    scheme,
+                    syllable,
+                    code
+                );
+            }
+        }
+    }
+}
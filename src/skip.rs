@@ -0,0 +1,100 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+type SkipFn = dyn Fn(&str) -> Option<usize> + Send + Sync;
+
+// 在匹配之前识别需要原样输出的片段，如模板占位符、emoji 短代码、商品编号
+#[derive(Clone)]
+pub enum SkipPattern {
+    // 以 open 开头、到 close 结束（含）的片段："{{" + "}}" 匹配 "{{name}}"
+    Delimited(String, String),
+    // 以前缀开头，后面连续的 ASCII 字母、数字、'-'、'_'：“SKU-” 匹配 "SKU-12A"
+    Prefix(String),
+    // 返回从输入开头起需要跳过的字节数，None 或 0 表示不跳过
+    Custom(Arc<SkipFn>),
+}
+
+impl SkipPattern {
+    pub fn delimited(open: &str, close: &str) -> Self {
+        SkipPattern::Delimited(open.to_string(), close.to_string())
+    }
+
+    pub fn prefix(prefix: &str) -> Self {
+        SkipPattern::Prefix(prefix.to_string())
+    }
+
+    pub fn custom<F: Fn(&str) -> Option<usize> + Send + Sync + 'static>(f: F) -> Self {
+        SkipPattern::Custom(Arc::new(f))
+    }
+
+    // input 开头命中时返回片段的字节长度
+    pub fn match_at(&self, input: &str) -> Option<usize> {
+        let len = match self {
+            SkipPattern::Delimited(open, close) => {
+                let rest = input.strip_prefix(open.as_str())?;
+                open.len() + rest.find(close.as_str())? + close.len()
+            }
+            SkipPattern::Prefix(prefix) => {
+                let rest = input.strip_prefix(prefix.as_str())?;
+                prefix.len()
+                    + rest
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                        .unwrap_or(rest.len())
+            }
+            SkipPattern::Custom(f) => f(input)?,
+        };
+
+        // 自定义函数返回的长度可能落在字符中间
+        (len > 0 && input.is_char_boundary(len.min(input.len()))).then_some(len.min(input.len()))
+    }
+}
+
+impl Debug for SkipPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipPattern::Delimited(open, close) => {
+                f.debug_tuple("Delimited").field(open).field(close).finish()
+            }
+            SkipPattern::Prefix(prefix) => f.debug_tuple("Prefix").field(prefix).finish(),
+            SkipPattern::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+// 自定义函数按是否为同一个函数比较
+impl PartialEq for SkipPattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SkipPattern::Delimited(o1, c1), SkipPattern::Delimited(o2, c2)) => {
+                o1 == o2 && c1 == c2
+            }
+            (SkipPattern::Prefix(p1), SkipPattern::Prefix(p2)) => p1 == p2,
+            (SkipPattern::Custom(f1), SkipPattern::Custom(f2)) => Arc::ptr_eq(f1, f2),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SkipPattern;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_skip_pattern() {
+        let delimited = SkipPattern::delimited("{{", "}}");
+        assert_eq!(delimited.match_at("{{name}}你好"), Some(8));
+        assert_eq!(delimited.match_at("{{name"), None);
+        assert_eq!(delimited.match_at("你{{name}}"), None);
+
+        let prefix = SkipPattern::prefix("SKU-");
+        assert_eq!(prefix.match_at("SKU-12A_b号"), Some(9));
+        assert_eq!(prefix.match_at("SK"), None);
+
+        let custom = SkipPattern::custom(|s| s.starts_with('#').then_some(100));
+        assert_eq!(custom.match_at("#标签"), Some(7));
+        assert_eq!(SkipPattern::custom(|_| Some(1)).match_at("中"), None);
+        assert_eq!(custom.clone(), custom);
+        assert_eq!(format!("{:?}", delimited), "Delimited(\"{{\", \"}}\")");
+    }
+}
@@ -0,0 +1,209 @@
+use crate::converter::{Converter, LetterCase};
+use crate::translit::{Kana, Latin, Transliterate};
+use std::fmt::Display;
+use std::ops::Deref;
+
+// 可配置的 slug 生成器，默认以 "-" 连接、小写、转写带附加符号的拉丁字母与假名：
+// Slug::new().separator("_").max_length(16).slugify("带着希望去旅行") -> "dai_zhe_xi_wang"
+// 只保留 ASCII 字母与数字，分隔符不会重复出现在开头、结尾或相邻位置
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slug {
+    separator: String,
+    max_length: Option<usize>,
+    case: LetterCase,
+    transliterate: bool,
+}
+
+impl Default for Slug {
+    fn default() -> Self {
+        Self {
+            separator: "-".to_string(),
+            max_length: None,
+            case: LetterCase::Lower,
+            transliterate: true,
+        }
+    }
+}
+
+impl Slug {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    // 最多保留的字符数，只在音节或单词之间截断；第一个音节或单词就超长时才截断它本身
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    // LetterCase::Preserve 保留输入中字母的大小写
+    pub fn case(mut self, case: LetterCase) -> Self {
+        self.case = case;
+        self
+    }
+
+    // 把 "Café"、"すし" 转写为 "cafe"、"sushi"，关闭后这些字符中的非 ASCII 部分直接丢弃
+    pub fn transliterate(mut self, enabled: bool) -> Self {
+        self.transliterate = enabled;
+        self
+    }
+
+    pub fn slugify(&self, text: &str) -> String {
+        Converter::new(text).to_slug(self)
+    }
+
+    pub(crate) fn transliterator(&self) -> &'static dyn Transliterate {
+        match self.transliterate {
+            true => &(Latin, Kana),
+            false => &|_| None,
+        }
+    }
+
+    pub(crate) fn join(&self, tokens: Vec<String>) -> String {
+        let separator_len = self.separator.chars().count();
+        let mut slug = String::new();
+        let mut len = 0;
+
+        for token in tokens.into_iter().filter(|t| !t.is_empty()) {
+            let token = match self.case {
+                LetterCase::Preserve => token,
+                LetterCase::Lower => token.to_ascii_lowercase(),
+                LetterCase::Upper => token.to_ascii_uppercase(),
+            };
+            let extra = if slug.is_empty() { 0 } else { separator_len };
+            match self.max_length {
+                Some(max) if slug.is_empty() && token.len() > max => {
+                    slug.push_str(&token[..max]);
+                    break;
+                }
+                Some(max) if len + extra + token.len() > max => break,
+                _ => {}
+            }
+            if !slug.is_empty() {
+                slug.push_str(&self.separator);
+            }
+            slug.push_str(&token);
+            len += extra + token.len();
+        }
+
+        slug
+    }
+}
+
+// 序列化时输出中文内容的 slug，反序列化时原样读入，适合直接放在 API 响应结构体里：
+// #[derive(Serialize)] struct Post { title: PinyinSlug<String> }
+// {"title": "dai-zhe-xi-wang-qu-lv-xing"}
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct PinyinSlug<T>(pub T);
+
+impl<T: AsRef<str>> PinyinSlug<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+
+    pub fn slug(&self) -> String {
+        Converter::new(self.0.as_ref()).to_permalink()
+    }
+}
+
+impl<T> Deref for PinyinSlug<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> From<T> for PinyinSlug<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: AsRef<str>> Display for PinyinSlug<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.slug())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: AsRef<str>> serde::Serialize for PinyinSlug<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.slug())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for PinyinSlug<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PinyinSlug, Slug};
+    use crate::converter::LetterCase;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(
+            Slug::new().slugify("带着希望去旅行"),
+            "dai-zhe-xi-wang-qu-lv-xing"
+        );
+        assert_eq!(
+            Slug::new()
+                .separator("_")
+                .max_length(16)
+                .slugify("带着希望去旅行"),
+            "dai_zhe_xi_wang"
+        );
+        assert_eq!(Slug::new().max_length(2).slugify("旅行"), "lv");
+        assert_eq!(Slug::new().max_length(1).slugify("旅行"), "l");
+        assert_eq!(
+            Slug::new().slugify("  --咖啡 Café -- 2024!! "),
+            "ka-fei-cafe-2024"
+        );
+        assert_eq!(
+            Slug::new().transliterate(false).slugify("咖啡 Café"),
+            "ka-fei-caf"
+        );
+        assert_eq!(
+            Slug::new().case(LetterCase::Upper).slugify("用 Rust 写"),
+            "YONG-RUST-XIE"
+        );
+        assert_eq!(
+            Slug::new().case(LetterCase::Preserve).slugify("用 Rust 写"),
+            "yong-Rust-xie"
+        );
+        assert_eq!(Slug::new().separator("").slugify("你好"), "nihao");
+        assert_eq!(Slug::new().slugify("，。"), "");
+    }
+
+    #[test]
+    fn test_pinyin_slug() {
+        let slug = PinyinSlug::new("带着希望去旅行".to_string());
+        assert_eq!(slug.to_string(), "dai-zhe-xi-wang-qu-lv-xing");
+        assert_eq!(slug.as_str(), "带着希望去旅行");
+
+        #[cfg(feature = "serde")]
+        {
+            assert_eq!(
+                serde_json::to_string(&slug).unwrap(),
+                "\"dai-zhe-xi-wang-qu-lv-xing\""
+            );
+            let slug: PinyinSlug<String> = serde_json::from_str("\"旅行\"").unwrap();
+            assert_eq!(slug.into_inner(), "旅行");
+        }
+    }
+}
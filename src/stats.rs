@@ -0,0 +1,95 @@
+use crate::converter::Converter;
+use crate::pinyin::{split_initial, PinyinWord, ToneStyle, YuFormat};
+use crate::tone::Tone;
+use std::collections::HashMap;
+
+// 语料的音节、声母、韵母与声调分布，多音字按第一个读音统计
+// 声母按拼写拆分，y、w 计为声母，零声母计为 ""
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CorpusStats {
+    syllables: HashMap<String, usize>,
+    initials: HashMap<String, usize>,
+    finals: HashMap<String, usize>,
+    // 下标 0..=4 对应第一声到轻声
+    tones: [usize; 5],
+    total: usize,
+}
+
+impl CorpusStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_text(&mut self, text: &str) {
+        self.add(&Converter::new(text).convert());
+    }
+
+    pub fn add(&mut self, words: &[PinyinWord]) {
+        for p in words.iter().flat_map(|word| word.syllables()) {
+            let syllable = p.format_with(ToneStyle::None, YuFormat::Umlaut);
+            let (initial, fin) = split_initial(&syllable);
+            *self.initials.entry(initial.to_string()).or_insert(0) += 1;
+            *self.finals.entry(fin.to_string()).or_insert(0) += 1;
+            *self.syllables.entry(syllable).or_insert(0) += 1;
+            self.tones[usize::from(p.tone_number() - 1)] += 1;
+            self.total += 1;
+        }
+    }
+
+    // 统计到的音节总数
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    // 不带声调的音节，按出现次数从多到少排列
+    pub fn syllables(&self) -> Vec<(&str, usize)> {
+        ranked(&self.syllables)
+    }
+
+    pub fn initials(&self) -> Vec<(&str, usize)> {
+        ranked(&self.initials)
+    }
+
+    pub fn finals(&self) -> Vec<(&str, usize)> {
+        ranked(&self.finals)
+    }
+
+    pub fn tone_count(&self, tone: Tone) -> usize {
+        self.tones[usize::from(tone.number() - 1)]
+    }
+
+    pub fn tone_ratio(&self, tone: Tone) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.tone_count(tone) as f64 / self.total as f64
+    }
+}
+
+fn ranked(counts: &HashMap<String, usize>) -> Vec<(&str, usize)> {
+    let mut entries: Vec<_> = counts.iter().map(|(k, n)| (k.as_str(), *n)).collect();
+    entries.sort_by(|(k1, n1), (k2, n2)| n2.cmp(n1).then(k1.cmp(k2)));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CorpusStats;
+    use crate::tone::Tone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_corpus_stats() {
+        let mut stats = CorpusStats::new();
+        stats.add_text("中国人");
+        stats.add_text("重庆，人民");
+
+        assert_eq!(stats.total(), 7);
+        assert_eq!(stats.syllables()[0], ("ren", 2));
+        assert_eq!(&stats.initials()[..2], &[("r", 2), ("ch", 1)]);
+        assert_eq!(stats.finals()[0], ("en", 2));
+        assert_eq!(stats.tone_count(Tone::Second), 5);
+        assert_eq!(stats.tone_count(Tone::Neutral), 0);
+        assert!((stats.tone_ratio(Tone::First) - 1.0 / 7.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+// 字典存储后端，可以是内存中的 HashMap，也可以是 sqlite、sled 等嵌入式 KV 存储
+pub trait DictStore {
+    // 精确查找一个词的拼音，如 "重庆" -> "chóng qìng"
+    fn get(&self, word: &str) -> Option<String>;
+
+    // 存储中最长词的字数，决定匹配时的查找窗口
+    fn max_word_len(&self) -> usize;
+}
+
+impl DictStore for HashMap<String, String> {
+    fn get(&self, word: &str) -> Option<String> {
+        HashMap::get(self, word).cloned()
+    }
+
+    // 每次调用都会遍历所有键，StoreMatcher 与 UserDict 只在创建时调用一次
+    fn max_word_len(&self) -> usize {
+        self.keys().map(|k| k.chars().count()).max().unwrap_or(0)
+    }
+}
+
+impl<S: DictStore + ?Sized> DictStore for &S {
+    fn get(&self, word: &str) -> Option<String> {
+        (**self).get(word)
+    }
+
+    fn max_word_len(&self) -> usize {
+        (**self).max_word_len()
+    }
+}
+
+impl<S: DictStore + ?Sized> DictStore for Box<S> {
+    fn get(&self, word: &str) -> Option<String> {
+        (**self).get(word)
+    }
+
+    fn max_word_len(&self) -> usize {
+        (**self).max_word_len()
+    }
+}
+
+// 不预先构建自动机，而是在匹配时按需查询存储，适合放不进内存的大词库
+pub struct StoreMatcher<S: DictStore> {
+    store: S,
+    max_word_len: usize,
+}
+
+impl<S: DictStore> StoreMatcher<S> {
+    pub fn new(store: S) -> Self {
+        let max_word_len = store.max_word_len();
+        Self {
+            store,
+            max_word_len,
+        }
+    }
+
+    // 正向最大匹配，返回互不重叠的 (词, 拼音)
+    pub fn match_word_pinyin(&self, input: &str) -> Vec<(String, String)> {
+        let mut results = vec![];
+        let mut rest = input;
+
+        while let Some(c) = rest.chars().next() {
+            match self.longest_match(rest) {
+                Some((word, pinyin)) => {
+                    rest = &rest[word.len()..];
+                    results.push((word.to_string(), pinyin));
+                }
+                None => rest = &rest[c.len_utf8()..],
+            }
+        }
+
+        results
+    }
+
+    pub fn convert(&self, input: &str) -> Vec<String> {
+        let mut result = vec![];
+        let mut rest = input;
+
+        while let Some(c) = rest.chars().next() {
+            match self.longest_match(rest) {
+                Some((word, pinyin)) => {
+                    rest = &rest[word.len()..];
+                    result.push(pinyin);
+                }
+                None => {
+                    rest = &rest[c.len_utf8()..];
+                    result.push(c.to_string());
+                }
+            }
+        }
+
+        result
+    }
+
+    // text 开头能匹配到的最长的词，按 max_word_len 限定查询次数，查询时不再为每个窗口分配字符串
+    pub(crate) fn longest_match<'t>(&self, text: &'t str) -> Option<(&'t str, String)> {
+        let ends: Vec<usize> = text
+            .char_indices()
+            .map(|(i, c)| i + c.len_utf8())
+            .take(self.max_word_len)
+            .collect();
+        ends.into_iter().rev().find_map(|end| {
+            let word = &text[..end];
+            self.store.get(word).map(|pinyin| (word, pinyin))
+        })
+    }
+}
+
+// 供 Converter 使用的用户词典，转换时先于内置词库匹配，命中的词以用户词典的读音为准
+// 最长词长在创建时计算一次，克隆出的词典共享同一份存储
+#[derive(Clone)]
+pub struct UserDict(Arc<StoreMatcher<Box<dyn DictStore + Send + Sync>>>);
+
+impl UserDict {
+    pub fn new<S: DictStore + Send + Sync + 'static>(store: S) -> Self {
+        Self(Arc::new(StoreMatcher::new(Box::new(store))))
+    }
+
+    pub(crate) fn longest_match<'t>(&self, text: &'t str) -> Option<(&'t str, String)> {
+        self.0.longest_match(text)
+    }
+}
+
+impl Debug for UserDict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("UserDict(..)")
+    }
+}
+
+// 按是否为同一份词典比较
+impl PartialEq for UserDict {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DictStore, StoreMatcher};
+    use std::collections::HashMap;
+
+    fn store() -> HashMap<String, String> {
+        [("重庆", "chóng qìng"), ("重", "zhòng"), ("火锅", "huǒ guō")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_hashmap_store() {
+        let store = store();
+        assert_eq!(
+            DictStore::get(&store, "重庆"),
+            Some("chóng qìng".to_string())
+        );
+        assert_eq!(DictStore::get(&store, "重庆火锅"), None);
+        assert_eq!(store.max_word_len(), 2);
+    }
+
+    #[test]
+    fn test_store_matcher() {
+        let store = store();
+        let matcher = StoreMatcher::new(&store);
+        assert_eq!(
+            matcher.match_word_pinyin("重庆火锅很重"),
+            vec![
+                ("重庆".to_string(), "chóng qìng".to_string()),
+                ("火锅".to_string(), "huǒ guō".to_string()),
+                ("重".to_string(), "zhòng".to_string()),
+            ]
+        );
+        assert_eq!(
+            matcher.convert("重庆火锅很重"),
+            vec!["chóng qìng", "huǒ guō", "很", "zhòng"]
+        );
+    }
+}
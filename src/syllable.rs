@@ -0,0 +1,363 @@
+use crate::error::PinyinError;
+use crate::pinyin::{split_initial, Pinyin};
+use crate::tone::Tone;
+use std::str::FromStr;
+
+// 按音系拆分的音节：声母、韵母（含介音）与声调
+// 与拼写不同，y、w 不算声母，"yuan" -> ("", "üan")，"ju" -> ("j", "ü")
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Syllable {
+    initial: String,
+    fin: String,
+    tone: Tone,
+}
+
+// y、w 开头的零声母音节还原为韵母的本来写法
+const ZERO_INITIAL_FINALS: [(&str, &str); 23] = [
+    ("yi", "i"),
+    ("ya", "ia"),
+    ("ye", "ie"),
+    ("yao", "iao"),
+    ("you", "iu"),
+    ("yan", "ian"),
+    ("yin", "in"),
+    ("yang", "iang"),
+    ("ying", "ing"),
+    ("yong", "iong"),
+    ("yu", "ü"),
+    ("yue", "üe"),
+    ("yuan", "üan"),
+    ("yun", "ün"),
+    ("wu", "u"),
+    ("wa", "ua"),
+    ("wo", "uo"),
+    ("wai", "uai"),
+    ("wei", "ui"),
+    ("wan", "uan"),
+    ("wen", "un"),
+    ("wang", "uang"),
+    ("weng", "ueng"),
+];
+
+// 普通话音节表（不带声调），含叹词 hm、ng 与口语音节 biang、den 等，按字节序排列
+pub(crate) const SYLLABLES: [&str; 425] = [
+    "a", "ai", "an", "ang", "ao", "ba", "bai", "ban", "bang", "bao", "bei", "ben", "beng", "bi",
+    "bian", "biang", "biao", "bie", "bin", "bing", "bo", "bu", "ca", "cai", "can", "cang", "cao",
+    "ce", "cei", "cen", "ceng", "cha", "chai", "chan", "chang", "chao", "che", "chen", "cheng",
+    "chi", "chong", "chou", "chu", "chua", "chuai", "chuan", "chuang", "chui", "chun", "chuo",
+    "ci", "cong", "cou", "cu", "cuan", "cui", "cun", "cuo", "da", "dai", "dan", "dang", "dao",
+    "de", "dei", "den", "deng", "di", "dia", "dian", "diao", "die", "din", "ding", "diu", "dong",
+    "dou", "du", "duan", "dui", "dun", "duo", "e", "ei", "en", "eng", "er", "fa", "fan", "fang",
+    "fei", "fen", "feng", "fiao", "fo", "fou", "fu", "ga", "gai", "gan", "gang", "gao", "ge",
+    "gei", "gen", "geng", "gong", "gou", "gu", "gua", "guai", "guan", "guang", "gui", "gun", "guo",
+    "ha", "hai", "han", "hang", "hao", "he", "hei", "hen", "heng", "hm", "hng", "hong", "hou",
+    "hu", "hua", "huai", "huan", "huang", "hui", "hun", "huo", "ji", "jia", "jian", "jiang",
+    "jiao", "jie", "jin", "jing", "jiong", "jiu", "ju", "juan", "jue", "jun", "ka", "kai", "kan",
+    "kang", "kao", "ke", "kei", "ken", "keng", "kong", "kou", "ku", "kua", "kuai", "kuan", "kuang",
+    "kui", "kun", "kuo", "la", "lai", "lan", "lang", "lao", "le", "lei", "len", "leng", "li",
+    "lia", "lian", "liang", "liao", "lie", "lin", "ling", "liu", "lo", "long", "lou", "lu", "luan",
+    "lun", "luo", "lü", "lüe", "m", "ma", "mai", "man", "mang", "mao", "me", "mei", "men", "meng",
+    "mi", "mian", "miao", "mie", "min", "ming", "miu", "mo", "mou", "mu", "n", "na", "nai", "nan",
+    "nang", "nao", "ne", "nei", "nen", "neng", "ng", "ni", "nia", "nian", "niang", "niao", "nie",
+    "nin", "ning", "niu", "nong", "nou", "nu", "nuan", "nun", "nuo", "nü", "nüe", "o", "ou", "pa",
+    "pai", "pan", "pang", "pao", "pei", "pen", "peng", "pi", "pian", "piao", "pie", "pin", "ping",
+    "po", "pou", "pu", "qi", "qia", "qian", "qiang", "qiao", "qie", "qin", "qing", "qiong", "qiu",
+    "qu", "quan", "que", "qun", "ran", "rang", "rao", "re", "ren", "reng", "ri", "rong", "rou",
+    "ru", "rua", "ruan", "rui", "run", "ruo", "sa", "sai", "san", "sang", "sao", "se", "sen",
+    "seng", "sha", "shai", "shan", "shang", "shao", "she", "shei", "shen", "sheng", "shi", "shou",
+    "shu", "shua", "shuai", "shuan", "shuang", "shui", "shun", "shuo", "si", "song", "sou", "su",
+    "suan", "sui", "sun", "suo", "ta", "tai", "tan", "tang", "tao", "te", "tei", "teng", "ti",
+    "tian", "tiao", "tie", "ting", "tong", "tou", "tu", "tuan", "tui", "tun", "tuo", "wa", "wai",
+    "wan", "wang", "wei", "wen", "weng", "wo", "wong", "wu", "xi", "xia", "xian", "xiang", "xiao",
+    "xie", "xin", "xing", "xiong", "xiu", "xu", "xuan", "xue", "xun", "ya", "yan", "yang", "yao",
+    "ye", "yi", "yin", "ying", "yo", "yong", "you", "yu", "yuan", "yue", "yun", "za", "zai", "zan",
+    "zang", "zao", "ze", "zei", "zen", "zeng", "zha", "zhai", "zhan", "zhang", "zhao", "zhe",
+    "zhei", "zhen", "zheng", "zhi", "zhong", "zhou", "zhu", "zhua", "zhuai", "zhuan", "zhuang",
+    "zhui", "zhun", "zhuo", "zi", "zong", "zou", "zu", "zuan", "zui", "zun", "zuo", "ê",
+];
+
+// "zhong"、"zhong4"、"zhòng" 都可以，ü 可以写作 v；不带声调时按轻声
+pub fn validate_syllable(s: &str) -> Result<Syllable, PinyinError> {
+    let invalid = || PinyinError::InvalidSyllable(s.to_string());
+    let pinyin = match s.ends_with(|c: char| c.is_ascii_digit()) {
+        true => Pinyin::from_str(s).map_err(|_| invalid())?,
+        false => Pinyin::try_from_marked(s).map_err(|_| invalid())?,
+    };
+    let spelling = pinyin.pinyin.to_lowercase().replace('v', "ü");
+    if SYLLABLES.binary_search(&spelling.as_str()).is_err() {
+        return Err(invalid());
+    }
+
+    Ok(Syllable::from(&pinyin))
+}
+
+pub fn is_valid_syllable(s: &str) -> bool {
+    validate_syllable(s).is_ok()
+}
+
+// split_pinyin 最多返回的切分数，长串的切分数随长度指数增长
+const MAX_SPLITS: usize = 32;
+
+// 把不带声调的拼音串切分为音节，返回可能的切分，音节少的在前，最多 MAX_SPLITS 种
+// "xian" -> [["xian"], ["xi", "an"]]，撇号与空白为固定的分界："xi'an" -> [["xi", "an"]]
+// 含有无法组成音节的部分时返回空
+pub fn split_pinyin(input: &str) -> Vec<Vec<String>> {
+    split_limited(input, MAX_SPLITS)
+}
+
+// 音节最少的一种切分，音节数相同时前面的音节长的优先："xianggang" -> ["xiang", "gang"]
+// 含有无法组成音节的部分时返回 None
+pub fn split_pinyin_best(input: &str) -> Option<Vec<String>> {
+    split_limited(input, 1).into_iter().next()
+}
+
+fn split_limited(input: &str, limit: usize) -> Vec<Vec<String>> {
+    let mut result: Vec<Vec<String>> = vec![vec![]];
+    for part in input
+        .split(['\'', '’', ' '])
+        .filter(|part| !part.is_empty())
+    {
+        let splits = split_part(part, limit);
+        result = result
+            .iter()
+            .flat_map(|prefix| {
+                splits
+                    .iter()
+                    .map(move |split| [&prefix[..], split].concat())
+            })
+            .collect();
+        result.sort_by_key(Vec::len);
+        result.truncate(limit);
+    }
+    result.retain(|split| !split.is_empty());
+
+    result
+}
+
+// 从后往前求出每个位置之后音节最少的 limit 种切分，音节数相同时长的音节优先
+// 每种切分记作 (音节数, 首个音节的结束位置, 在结束位置的切分中的序号)
+fn split_part(part: &str, limit: usize) -> Vec<Vec<String>> {
+    let bounds: Vec<usize> = part
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([part.len()])
+        .collect();
+    let last = bounds.len() - 1;
+    let mut splits: Vec<Vec<(usize, usize, usize)>> = vec![vec![]; bounds.len()];
+    splits[last].push((0, last, 0));
+
+    for start in (0..last).rev() {
+        let mut candidates = vec![];
+        for end in (start + 1..=last.min(start + 6)).rev() {
+            if !is_splittable(&part[bounds[start]..bounds[end]]) {
+                continue;
+            }
+            candidates.extend(
+                splits[end]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (count, _, _))| (count + 1, end, i)),
+            );
+        }
+        candidates.sort_by_key(|(count, _, _)| *count);
+        candidates.truncate(limit);
+        splits[start] = candidates;
+    }
+
+    splits[0]
+        .iter()
+        .map(|&first| {
+            let mut syllables = vec![];
+            let (mut start, mut split) = (0, first);
+            while start < last {
+                let (_, end, i) = split;
+                syllables.push(part[bounds[start]..bounds[end]].to_string());
+                start = end;
+                split = splits[end][i];
+            }
+            syllables
+        })
+        .collect()
+}
+
+// 叹词 m、n、ng、hm、hng、ê 不参与切分，避免 "xiang" 切出 "xia" + "ng"
+fn is_splittable(syllable: &str) -> bool {
+    let syllable = syllable.to_lowercase().replace('v', "ü");
+    !matches!(syllable.as_str(), "m" | "n" | "ng" | "hm" | "hng" | "ê")
+        && SYLLABLES.binary_search(&syllable.as_str()).is_ok()
+}
+
+impl Syllable {
+    // 声母，零声母为 ""
+    pub fn initial(&self) -> &str {
+        &self.initial
+    }
+
+    // 韵母，包括介音："zhuang" -> "uang"
+    pub fn final_(&self) -> &str {
+        &self.fin
+    }
+
+    // 介音 i、u、ü："xiang" -> Some('i')，"xing" -> None
+    pub fn medial(&self) -> Option<char> {
+        let mut chars = self.fin.chars();
+        match (chars.next(), chars.next()) {
+            _ if matches!(self.fin.as_str(), "in" | "ing" | "ün") => None,
+            (Some(c @ ('i' | 'u' | 'ü')), Some(_)) => Some(c),
+            _ => None,
+        }
+    }
+
+    pub fn tone(&self) -> Tone {
+        self.tone
+    }
+}
+
+// 按音系拆分小写的拼写，ü 不能写作 v："yuan" -> ("", "üan")，"ju" -> ("j", "ü")
+// 鼻音 m、n、ng 自成音节，整体算作韵母
+pub(crate) fn split_phonological(spelling: &str) -> (&str, String) {
+    if matches!(spelling, "m" | "n" | "ng") {
+        return ("", spelling.to_string());
+    }
+    if let Some((_, fin)) = ZERO_INITIAL_FINALS.iter().find(|(s, _)| *s == spelling) {
+        return ("", fin.to_string());
+    }
+
+    let (initial, fin) = split_initial(spelling);
+    match (initial, fin.strip_prefix('u')) {
+        // j、q、x 后的 u 是省写了两点的 ü
+        ("j" | "q" | "x", Some(rest)) => (initial, format!("ü{}", rest)),
+        _ => (initial, fin.to_string()),
+    }
+}
+
+impl From<&Pinyin> for Syllable {
+    fn from(pinyin: &Pinyin) -> Self {
+        let spelling = pinyin.pinyin.to_lowercase().replace('v', "ü");
+        let (initial, fin) = split_phonological(&spelling);
+
+        Self {
+            initial: initial.to_string(),
+            fin,
+            tone: pinyin.tone,
+        }
+    }
+}
+
+impl From<Pinyin> for Syllable {
+    fn from(pinyin: Pinyin) -> Self {
+        Self::from(&pinyin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_valid_syllable, split_pinyin, split_pinyin_best, validate_syllable, Syllable, SYLLABLES,
+    };
+    use crate::error::PinyinError;
+    use crate::pinyin::Pinyin;
+    use crate::tone::Tone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_syllable() {
+        let cases = [
+            ("zhuang", 4, "zh", "uang", Some('u')),
+            ("xiang", 1, "x", "iang", Some('i')),
+            ("xing", 2, "x", "ing", None),
+            ("ju", 3, "j", "ü", None),
+            ("quan", 2, "q", "üan", Some('ü')),
+            ("lv", 4, "l", "ü", None),
+            ("nüe", 4, "n", "üe", Some('ü')),
+            ("yuan", 2, "", "üan", Some('ü')),
+            ("you", 3, "", "iu", Some('i')),
+            ("wei", 4, "", "ui", Some('u')),
+            ("wu", 3, "", "u", None),
+            ("ai", 4, "", "ai", None),
+            ("er", 2, "", "er", None),
+            ("gui", 4, "g", "ui", Some('u')),
+            ("lun", 2, "l", "un", Some('u')),
+            ("zhi", 1, "zh", "i", None),
+            ("ma", 5, "m", "a", None),
+            ("ng", 5, "", "ng", None),
+        ];
+        for (pinyin, tone, initial, fin, medial) in cases {
+            let syllable = Syllable::from(Pinyin::try_from((pinyin, tone)).unwrap());
+            assert_eq!(syllable.initial(), initial, "{}", pinyin);
+            assert_eq!(syllable.final_(), fin, "{}", pinyin);
+            assert_eq!(syllable.medial(), medial, "{}", pinyin);
+            assert_eq!(syllable.tone().number(), tone);
+        }
+    }
+
+    #[test]
+    fn test_validate_syllable() {
+        assert!(SYLLABLES.windows(2).all(|w| w[0] < w[1]));
+
+        for s in [
+            "zhong", "Zhong", "zhong1", "zhòng", "lv", "lüe", "nve", "er", "ng", "hm", "ê",
+        ] {
+            assert!(is_valid_syllable(s), "{}", s);
+        }
+        for s in [
+            "",
+            "zhon",
+            "jü5",
+            "lue",
+            "gi",
+            "bue",
+            "zhong6",
+            "xianggang",
+            "中",
+        ] {
+            assert!(!is_valid_syllable(s), "{}", s);
+        }
+
+        let syllable = validate_syllable("quan2").unwrap();
+        assert_eq!(syllable.initial(), "q");
+        assert_eq!(syllable.final_(), "üan");
+        assert_eq!(syllable.tone(), Tone::Second);
+        assert_eq!(validate_syllable("ma").unwrap().tone(), Tone::Neutral);
+        assert!(matches!(
+            validate_syllable("biu"),
+            Err(PinyinError::InvalidSyllable(s)) if s == "biu"
+        ));
+    }
+
+    #[test]
+    fn test_split_pinyin() {
+        assert_eq!(split_pinyin("xian"), vec![vec!["xian"], vec!["xi", "an"]]);
+        assert_eq!(
+            split_pinyin("xianggang"),
+            vec![vec!["xiang", "gang"], vec!["xi", "ang", "gang"]]
+        );
+        assert_eq!(split_pinyin("xi'an"), vec![vec!["xi", "an"]]);
+        assert_eq!(split_pinyin("Bei Jing"), vec![vec!["Bei", "Jing"]]);
+        assert_eq!(split_pinyin("nvren"), vec![vec!["nv", "ren"]]);
+        assert_eq!(split_pinyin("zhongguo")[0], vec!["zhong", "guo"]);
+        assert!(split_pinyin("").is_empty());
+        assert!(split_pinyin("zhongg").is_empty());
+        assert!(split_pinyin("ni3hao").is_empty());
+
+        // 切分数有上限，长串也能很快返回，音节最少的切分在最前
+        let long = "xian".repeat(200);
+        let splits = split_pinyin(&long);
+        assert_eq!(splits.len(), 32);
+        assert_eq!(splits[0], vec!["xian"; 200]);
+        assert!(splits.windows(2).all(|w| w[0].len() <= w[1].len()));
+    }
+
+    #[test]
+    fn test_split_pinyin_best() {
+        assert_eq!(split_pinyin_best("xian").unwrap(), vec!["xian"]);
+        assert_eq!(
+            split_pinyin_best("xianggang").unwrap(),
+            vec!["xiang", "gang"]
+        );
+        assert_eq!(split_pinyin_best("xi'an").unwrap(), vec!["xi", "an"]);
+        assert_eq!(split_pinyin_best(&"xian".repeat(1000)).unwrap().len(), 1000);
+        assert_eq!(split_pinyin_best("zhongg"), None);
+        assert_eq!(split_pinyin_best(""), None);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::loader::Loader;
+
+// 每页 256 个码位，0x110000 个码位共 4352 页
+const PAGE_BITS: u32 = 8;
+const PAGE_SIZE: usize = 1 << PAGE_BITS;
+const PAGES: usize = 0x110000 >> PAGE_BITS;
+
+type Page<'a> = Box<[Option<&'a str>; PAGE_SIZE]>;
+
+// 按码位索引的单字读音表，两级数组，只为有数据的页分配内存，查询不需要哈希
+pub struct CharTable<'a> {
+    pages: Vec<Option<Page<'a>>>,
+    len: usize,
+}
+
+impl<'a> CharTable<'a> {
+    // 只收录单个字符的条目
+    pub fn new<L: Loader + ?Sized>(loader: &'a L) -> Self {
+        Self::from_entries(loader.get_chunks(1).into_iter().flatten())
+    }
+
+    // 直接从 (字, 读音) 建表，不经过 Loader 的分块
+    pub fn from_entries<I: IntoIterator<Item = (&'a str, &'a str)>>(entries: I) -> Self {
+        let mut table = Self {
+            pages: (0..PAGES).map(|_| None).collect(),
+            len: 0,
+        };
+
+        for (word, pinyin) in entries {
+            let mut chars = word.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                table.insert(c, pinyin);
+            }
+        }
+
+        table
+    }
+
+    fn insert(&mut self, c: char, pinyin: &'a str) {
+        let code = c as usize;
+        let page = self.pages[code >> PAGE_BITS].get_or_insert_with(|| Box::new([None; PAGE_SIZE]));
+        if page[code & (PAGE_SIZE - 1)].replace(pinyin).is_none() {
+            self.len += 1;
+        }
+    }
+
+    pub fn get(&self, c: char) -> Option<&'a str> {
+        let code = c as usize;
+        self.pages[code >> PAGE_BITS]
+            .as_ref()
+            .and_then(|page| page[code & (PAGE_SIZE - 1)])
+    }
+
+    // 按码位顺序遍历全部条目
+    pub fn iter(&self) -> impl Iterator<Item = (char, &'a str)> + '_ {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter_map(|(i, page)| Some((i, page.as_ref()?)))
+            .flat_map(|(i, page)| {
+                page.iter().enumerate().filter_map(move |(j, pinyin)| {
+                    let c = char::from_u32(((i << PAGE_BITS) + j) as u32)?;
+                    Some((c, (*pinyin)?))
+                })
+            })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CharTable;
+    use crate::loader::CharsLoader;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_char_table() {
+        let dict: HashMap<String, String> = [("中", "zhōng zhòng"), ("𠀀", "hē"), ("中国", "")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let table = CharTable::new(&dict);
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.get('中'), Some("zhōng zhòng"));
+        assert_eq!(table.get('𠀀'), Some("hē"));
+        assert_eq!(table.get('国'), None);
+        assert_eq!(table.get('\u{10FFFF}'), None);
+        assert_eq!(
+            table.iter().collect::<Vec<_>>(),
+            vec![('中', "zhōng zhòng"), ('𠀀', "hē")]
+        );
+
+        let loader = CharsLoader::new();
+        let table = CharTable::new(&loader);
+        assert_eq!(table.get('〇'), Some("líng yuán xīng"));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::analyzer::phonetic_units;
+use tantivy_tokenizer_api::{Token, TokenStream, Tokenizer};
+
+// tantivy 分词器：汉字按字输出不带声调的拼音与首字母，两者位置相同，
+// 偏移指向原文中的汉字，可以直接用于高亮；字母与数字按连续的一段小写输出，其余字符忽略
+// index.tokenizers().register("pinyin", PinyinTokenizer::new())
+#[derive(Debug, Clone)]
+pub struct PinyinTokenizer {
+    full_pinyin: bool,
+    first_letter: bool,
+}
+
+impl Default for PinyinTokenizer {
+    fn default() -> Self {
+        Self {
+            full_pinyin: true,
+            first_letter: true,
+        }
+    }
+}
+
+impl PinyinTokenizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 输出完整拼音 "zhong"
+    pub fn full_pinyin(mut self, enabled: bool) -> Self {
+        self.full_pinyin = enabled;
+        self
+    }
+
+    // 输出首字母 "z"
+    pub fn first_letter(mut self, enabled: bool) -> Self {
+        self.first_letter = enabled;
+        self
+    }
+
+    fn tokenize(&self, text: &str) -> Vec<Token> {
+        let mut tokens = vec![];
+        let mut push = |text: String, offset_from: usize, offset_to: usize, position: usize| {
+            tokens.push(Token {
+                offset_from,
+                offset_to,
+                position,
+                text,
+                position_length: 1,
+            })
+        };
+
+        for (position, (range, syllable)) in phonetic_units(text).into_iter().enumerate() {
+            match syllable {
+                Some(syllable) => {
+                    if self.first_letter {
+                        let first = syllable.chars().take(1).collect();
+                        push(first, range.start, range.end, position);
+                    }
+                    if self.full_pinyin {
+                        push(syllable, range.start, range.end, position);
+                    }
+                }
+                None => push(
+                    text[range.clone()].to_lowercase(),
+                    range.start,
+                    range.end,
+                    position,
+                ),
+            }
+        }
+
+        tokens
+    }
+}
+
+pub struct PinyinTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Tokenizer for PinyinTokenizer {
+    type TokenStream<'a> = PinyinTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        PinyinTokenStream {
+            tokens: self.tokenize(text),
+            index: 0,
+        }
+    }
+}
+
+impl TokenStream for PinyinTokenStream {
+    fn advance(&mut self) -> bool {
+        self.index += 1;
+        self.index <= self.tokens.len()
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PinyinTokenizer;
+    use pretty_assertions::assert_eq;
+    use tantivy_tokenizer_api::{TokenStream, Tokenizer};
+
+    fn tokens(tokenizer: &mut PinyinTokenizer, text: &str) -> Vec<(String, usize, usize, usize)> {
+        let mut stream = tokenizer.token_stream(text);
+        let mut tokens = vec![];
+        stream.process(&mut |t| {
+            tokens.push((t.text.clone(), t.offset_from, t.offset_to, t.position))
+        });
+        tokens
+    }
+
+    #[test]
+    fn test_pinyin_tokenizer() {
+        let mut tokenizer = PinyinTokenizer::new();
+        assert_eq!(
+            tokens(&mut tokenizer, "中国 Rust！"),
+            vec![
+                ("z".to_string(), 0, 3, 0),
+                ("zhong".to_string(), 0, 3, 0),
+                ("g".to_string(), 3, 6, 1),
+                ("guo".to_string(), 3, 6, 1),
+                ("rust".to_string(), 7, 11, 2),
+            ]
+        );
+
+        let mut tokenizer = PinyinTokenizer::new().first_letter(false);
+        let texts: Vec<String> = tokens(&mut tokenizer, "女儿")
+            .into_iter()
+            .map(|t| t.0)
+            .collect();
+        assert_eq!(texts, vec!["nv", "er"]);
+
+        assert!(tokens(&mut tokenizer, "，。").is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::error::PinyinError;
+use std::fmt::Display;
+
+// 声调，数值与拼音数字标调一致：First 为 1，Neutral（轻声）为 5
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "u8", into = "u8")
+)]
+pub enum Tone {
+    First = 1,
+    Second,
+    Third,
+    Fourth,
+    #[default]
+    Neutral,
+}
+
+impl Tone {
+    pub const ALL: [Tone; 5] = [
+        Tone::First,
+        Tone::Second,
+        Tone::Third,
+        Tone::Fourth,
+        Tone::Neutral,
+    ];
+
+    pub fn number(self) -> u8 {
+        self as u8
+    }
+
+    pub fn is_neutral(self) -> bool {
+        self == Tone::Neutral
+    }
+}
+
+impl TryFrom<u8> for Tone {
+    type Error = PinyinError;
+
+    fn try_from(tone: u8) -> Result<Self, Self::Error> {
+        match tone {
+            1 => Ok(Tone::First),
+            2 => Ok(Tone::Second),
+            3 => Ok(Tone::Third),
+            4 => Ok(Tone::Fourth),
+            5 => Ok(Tone::Neutral),
+            got => Err(PinyinError::InvalidTone { got }),
+        }
+    }
+}
+
+impl From<Tone> for u8 {
+    fn from(tone: Tone) -> Self {
+        tone.number()
+    }
+}
+
+impl Display for Tone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tone;
+    use crate::error::PinyinError;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_tone() {
+        for (i, tone) in Tone::ALL.into_iter().enumerate() {
+            let number = i as u8 + 1;
+            assert_eq!(Tone::try_from(number).unwrap(), tone);
+            assert_eq!(u8::from(tone), number);
+            assert_eq!(tone.to_string(), number.to_string());
+        }
+        assert!(Tone::Neutral.is_neutral());
+        assert_eq!(Tone::default(), Tone::Neutral);
+        assert!(matches!(
+            Tone::try_from(6),
+            Err(PinyinError::InvalidTone { got: 6 })
+        ));
+        assert!(matches!(
+            Tone::try_from(0),
+            Err(PinyinError::InvalidTone { got: 0 })
+        ));
+    }
+}
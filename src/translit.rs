@@ -0,0 +1,117 @@
+use unicode_normalization::char::decompose_canonical;
+
+// 把非汉字、非 ASCII 字符转写为 ASCII，用于生成 slug
+// 返回 None 表示不认识这个字符，可以用元组组合多个转写表：(Latin, Kana)
+pub trait Transliterate {
+    fn transliterate(&self, c: char) -> Option<String>;
+}
+
+impl<F: Fn(char) -> Option<String>> Transliterate for F {
+    fn transliterate(&self, c: char) -> Option<String> {
+        self(c)
+    }
+}
+
+impl<A: Transliterate, B: Transliterate> Transliterate for (A, B) {
+    fn transliterate(&self, c: char) -> Option<String> {
+        self.0.transliterate(c).or_else(|| self.1.transliterate(c))
+    }
+}
+
+// 去掉拉丁字母上的附加符号："é" -> "e"，"ß" -> "ss"
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Latin;
+
+impl Transliterate for Latin {
+    fn transliterate(&self, c: char) -> Option<String> {
+        let special = match c {
+            'ß' => "ss",
+            'æ' => "ae",
+            'Æ' => "AE",
+            'œ' => "oe",
+            'Œ' => "OE",
+            'ø' => "o",
+            'Ø' => "O",
+            'đ' | 'ð' => "d",
+            'Đ' | 'Ð' => "D",
+            'ł' => "l",
+            'Ł' => "L",
+            'þ' => "th",
+            'Þ' => "TH",
+            'ı' => "i",
+            _ => "",
+        };
+        if !special.is_empty() {
+            return Some(special.to_string());
+        }
+
+        let mut base = String::new();
+        decompose_canonical(c, |d| {
+            if d.is_ascii() {
+                base.push(d)
+            }
+        });
+        (!base.is_empty() && !c.is_ascii()).then_some(base)
+    }
+}
+
+// 平假名、片假名的平文式罗马字，逐字转写，拗音 "きょ" 会写成 "kiyo"
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Kana;
+
+// U+3041 ぁ ..= U+3096 ゖ
+const HIRAGANA: [&str; 86] = [
+    "a", "a", "i", "i", "u", "u", "e", "e", "o", "o", // ぁ-お
+    "ka", "ga", "ki", "gi", "ku", "gu", "ke", "ge", "ko", "go", // か-ご
+    "sa", "za", "shi", "ji", "su", "zu", "se", "ze", "so", "zo", // さ-ぞ
+    "ta", "da", "chi", "ji", "", "tsu", "zu", "te", "de", "to", "do", // た-ど
+    "na", "ni", "nu", "ne", "no", // な-の
+    "ha", "ba", "pa", "hi", "bi", "pi", "fu", "bu", "pu", "he", "be", "pe", "ho", "bo",
+    "po", // は-ぽ
+    "ma", "mi", "mu", "me", "mo", // ま-も
+    "ya", "ya", "yu", "yu", "yo", "yo", // ゃ-よ
+    "ra", "ri", "ru", "re", "ro", // ら-ろ
+    "wa", "wa", "i", "e", "o", "n", "vu", "ka", "ke", // ゎ-ゖ
+];
+
+impl Transliterate for Kana {
+    fn transliterate(&self, c: char) -> Option<String> {
+        let code = match c {
+            '\u{3041}'..='\u{3096}' => c as usize - 0x3041,
+            // 片假名与平假名一一对应，相差 0x60
+            '\u{30A1}'..='\u{30F6}' => c as usize - 0x30A1,
+            // 长音符
+            'ー' => return Some(String::new()),
+            _ => return None,
+        };
+
+        Some(HIRAGANA[code].to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Kana, Latin, Transliterate};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_transliterate() {
+        let latin: String = "Crème Brûlée Straße"
+            .chars()
+            .map(|c| Latin.transliterate(c).unwrap_or_else(|| c.to_string()))
+            .collect();
+        assert_eq!(latin, "Creme Brulee Strasse");
+        assert_eq!(Latin.transliterate('a'), None);
+        assert_eq!(Latin.transliterate('中'), None);
+
+        assert_eq!(Kana.transliterate('す').as_deref(), Some("su"));
+        assert_eq!(Kana.transliterate('シ').as_deref(), Some("shi"));
+        assert_eq!(Kana.transliterate('ん').as_deref(), Some("n"));
+
+        let both = (Latin, Kana);
+        assert_eq!(both.transliterate('é').as_deref(), Some("e"));
+        assert_eq!(both.transliterate('カ').as_deref(), Some("ka"));
+        let custom = |c: char| (c == 'й').then(|| "y".to_string());
+        assert_eq!(custom.transliterate('й').as_deref(), Some("y"));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::is_han;
+use crate::pinyin::PinyinWord;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+type Handler = dyn FnMut(char, usize) -> Option<String> + Send;
+
+// 为没有读音的字符提供转写，如假名、私用区字形，参数为字符与它在输入中的字节偏移
+// 返回 None 的字符按 unknown_char_policy 输出；处理函数可以带状态，如统计次数，克隆出的处理器共用同一个函数
+#[derive(Clone)]
+pub struct UnknownCharHandler(Arc<Mutex<Handler>>);
+
+impl UnknownCharHandler {
+    pub fn new<F: FnMut(char, usize) -> Option<String> + Send + 'static>(f: F) -> Self {
+        Self(Arc::new(Mutex::new(f)))
+    }
+
+    pub fn call(&self, c: char, offset: usize) -> Option<String> {
+        let mut f = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        f(c, offset)
+    }
+}
+
+impl Debug for UnknownCharHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("UnknownCharHandler(..)")
+    }
+}
+
+// 按是否为同一个处理函数比较
+impl PartialEq for UnknownCharHandler {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+// 收集转换中没有读音的汉字及出现次数，并导出为 sources/patches/chars.txt 的格式
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UnknownChars {
+    counts: HashMap<char, usize>,
+}
+
+impl UnknownChars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, c: char) {
+        *self.counts.entry(c).or_insert(0) += 1;
+    }
+
+    // 记录未命中字典的汉字，标点、字母等不需要补丁的字符会被忽略
+    pub fn collect(&mut self, words: &[PinyinWord]) {
+        for word in words.iter().filter(|w| !w.is_matched()) {
+            word.word
+                .chars()
+                .filter(|c| is_han(*c))
+                .for_each(|c| self.record(c));
+        }
+    }
+
+    pub fn count(&self, c: char) -> usize {
+        self.counts.get(&c).copied().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    // 按出现次数从多到少排列
+    pub fn sorted(&self) -> Vec<(char, usize)> {
+        let mut entries: Vec<_> = self.counts.iter().map(|(c, n)| (*c, *n)).collect();
+        entries.sort_by(|(c1, n1), (c2, n2)| n2.cmp(n1).then(c1.cmp(c2)));
+        entries
+    }
+
+    // U+9FEF:   # 鿯 ×3
+    // 读音留空由维护者补全，未补全的行会被 build.rs 跳过
+    pub fn write_patch<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for (c, count) in self.sorted() {
+            writeln!(writer, "U+{:04X}:   # {} ×{}", c as u32, c, count)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn to_patch(&self) -> String {
+        let mut buffer = vec![];
+        self.write_patch(&mut buffer)
+            .expect("writing to a Vec never fails");
+        String::from_utf8(buffer).expect("patch lines are valid UTF-8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnknownChars;
+    use crate::generator::parse_line;
+    use crate::Converter;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_unknown_chars() {
+        let mut unknown = UnknownChars::new();
+        Converter::new("你\u{9FEF}好\u{9FEF}，\u{9FEE}").convert_with_unknown(&mut unknown);
+
+        assert_eq!(unknown.count('\u{9FEF}'), 2);
+        assert_eq!(unknown.count('，'), 0);
+        assert_eq!(
+            unknown.to_patch(),
+            "U+9FEF:   # \u{9FEF} ×2\nU+9FEE:   # \u{9FEE} ×1\n"
+        );
+        assert!(unknown.to_patch().lines().all(|l| parse_line(l).is_none()));
+    }
+}
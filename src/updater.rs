@@ -0,0 +1,229 @@
+use crate::error::PinyinError;
+use crate::generator::{
+    generate_chars, generate_heteronyms, generate_jyutping, generate_pack, generate_surnames,
+    generate_words, parse_line, remove_chunks,
+};
+use std::io::Read;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sources {
+    pub chars: String,
+    pub words: String,
+    // 为空时使用内置的姓氏数据
+    pub surnames: Option<String>,
+}
+
+impl Default for Sources {
+    fn default() -> Self {
+        Self {
+            chars: "https://raw.githubusercontent.com/mozillazg/pinyin-data/master/pinyin.txt"
+                .to_string(),
+            words:
+                "https://raw.githubusercontent.com/mozillazg/phrase-pinyin-data/master/pinyin.txt"
+                    .to_string(),
+            surnames: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct UpdateReport {
+    pub chars: usize,
+    pub words: usize,
+    pub surnames: usize,
+    pub heteronyms: usize,
+}
+
+// 下载最新的上游数据，按 build.rs 的格式生成到 `dir` 下的临时目录，
+// 生成的文件校验通过后才替换 `dir` 中原有的数据，失败时原有数据保持不变
+// 生成的 `dir` 包含全部数据文件，可直接传给 set_data_dir
+pub fn update_data<P: AsRef<Path>>(sources: &Sources, dir: P) -> Result<UpdateReport, PinyinError> {
+    let chars = download(&sources.chars)?;
+    let words = download(&sources.words)?;
+    let surnames = match &sources.surnames {
+        Some(url) => download(url)?,
+        None => include_str!("../sources/surnames.txt").to_string(),
+    };
+
+    validate_source(&chars)?;
+    validate_source(&words)?;
+    validate_source(&surnames)?;
+
+    let dir = dir.as_ref();
+    let staging = dir.join(".update");
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::create_dir_all(&staging)?;
+
+    let report = UpdateReport {
+        chars: generate_chars(
+            &[&chars, include_str!("../sources/patches/chars.txt")],
+            &staging,
+        )?,
+        words: generate_words(
+            &[&words, include_str!("../sources/patches/words.txt")],
+            &staging,
+        )?,
+        surnames: generate_surnames(&surnames, &staging)?,
+        heteronyms: generate_heteronyms(include_str!("../sources/heteronyms.txt"), &staging)?,
+    };
+    // 数据目录中必须有全部文件，随包发布的扩展数据原样生成
+    for (name, source) in [
+        ("neutral", include_str!("../sources/packs/neutral.txt")),
+        ("frequency", include_str!("../sources/packs/frequency.txt")),
+        ("slang", include_str!("../sources/packs/slang.txt")),
+        ("strokes", include_str!("../sources/packs/strokes.txt")),
+    ] {
+        generate_pack(name, source, &staging)?;
+    }
+    generate_jyutping(
+        &[
+            include_str!("../sources/packs/jyutping.txt"),
+            include_str!("../sources/unihan/kCantonese.txt"),
+        ],
+        &staging,
+    )?;
+    if let Err(e) = validate_output(&staging) {
+        std::fs::remove_dir_all(&staging)?;
+        return Err(e);
+    }
+
+    remove_chunks(dir, "chars")?;
+    remove_chunks(dir, "words")?;
+    for entry in std::fs::read_dir(&staging)? {
+        let entry = entry?;
+        std::fs::rename(entry.path(), dir.join(entry.file_name()))?;
+    }
+    std::fs::remove_dir(&staging)?;
+
+    Ok(report)
+}
+
+// 校验生成到 dir 的数据：单字、词组与姓氏的每一行都必须是合法的条目，多音字表不能为空
+pub(crate) fn validate_output(dir: &Path) -> Result<(), PinyinError> {
+    for name in ["chars", "words"] {
+        let mut count = 0;
+        for i in 0.. {
+            let path = dir.join(format!("{}_{}.txt", name, i));
+            if !path.exists() {
+                break;
+            }
+            // 数据较少时后面的分块为空
+            if std::fs::metadata(&path)?.len() == 0 {
+                continue;
+            }
+            count += validate_file(&path)?;
+        }
+        if count == 0 {
+            return Err(PinyinError::InvalidData(format!("no {} generated", name)));
+        }
+    }
+    validate_file(&dir.join("surnames.txt"))?;
+
+    let heteronyms = std::fs::read_to_string(dir.join("heteronyms.txt"))?;
+    if heteronyms.trim().is_empty() {
+        return Err(PinyinError::InvalidData(
+            "no heteronyms generated".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// 生成的文件中不允许出现注释或无法解析的行
+fn validate_file(path: &Path) -> Result<usize, PinyinError> {
+    let contents = std::fs::read_to_string(path)?;
+    for (i, line) in contents.lines().enumerate() {
+        if parse_line(line).is_none() {
+            return Err(PinyinError::DictionaryParse {
+                line: i + 1,
+                content: line.to_string(),
+            });
+        }
+    }
+    validate_source(&contents)
+}
+
+// 拼音部分只允许出现小写字母与声调符号，返回有效的条目数
+pub fn validate_source(contents: &str) -> Result<usize, PinyinError> {
+    let mut count = 0;
+    for (i, line) in contents.lines().enumerate() {
+        if let Some((_, pinyin)) = parse_line(line) {
+            let invalid = pinyin
+                .chars()
+                .any(|c| c.is_ascii() && !(c.is_ascii_lowercase() || c == ' ' || c == ','));
+            if invalid {
+                return Err(PinyinError::DictionaryParse {
+                    line: i + 1,
+                    content: line.to_string(),
+                });
+            }
+            count += 1;
+        }
+    }
+
+    if count == 0 {
+        return Err(PinyinError::InvalidData("no entries found".to_string()));
+    }
+
+    Ok(count)
+}
+
+fn download(url: &str) -> Result<String, PinyinError> {
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| PinyinError::Download(format!("{}: {}", url, e)))?;
+
+    let mut contents = String::new();
+    response.into_reader().read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_output, validate_source};
+    use crate::error::PinyinError;
+    use crate::generator::{
+        generate_chars, generate_heteronyms, generate_surnames, generate_words,
+    };
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            validate_source("# comment\n中国: zhōng guó\n燕: yān").unwrap(),
+            2
+        );
+        assert!(matches!(
+            validate_source("# 注释\n中国: zhong1 guo2"),
+            Err(PinyinError::DictionaryParse { line: 2, .. })
+        ));
+        assert!(validate_source("# comment").is_err());
+    }
+
+    #[test]
+    fn test_validate_output() {
+        let dir = crate::temp_path("validate_output");
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(validate_output(&dir).is_err());
+
+        generate_chars(&["U+4E2D: zhōng zhòng  # 中"], &dir).unwrap();
+        generate_words(&["中国: zhōng guó"], &dir).unwrap();
+        generate_surnames("欧阳: ōu yáng", &dir).unwrap();
+        generate_heteronyms("中", &dir).unwrap();
+        validate_output(&dir).unwrap();
+
+        std::fs::write(dir.join("words_1.txt"), "中国: zhong1 guo2\n").unwrap();
+        assert!(matches!(
+            validate_output(&dir),
+            Err(PinyinError::DictionaryParse { line: 1, .. })
+        ));
+        std::fs::write(dir.join("words_1.txt"), "中国\n").unwrap();
+        assert!(matches!(
+            validate_output(&dir),
+            Err(PinyinError::DictionaryParse { line: 1, .. })
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+// 识别输入开头的网址、邮箱与数字，返回其字节长度，这些内容作为一个整体原样输出
+// "https://example.com/a?b=1，" -> 25，"a@b.cn是" -> 6，"3.14%" -> 5
+pub(crate) fn match_verbatim(input: &str) -> Option<usize> {
+    match_url(input)
+        .or_else(|| match_email(input))
+        .or_else(|| match_number(input))
+}
+
+// 网址结尾的标点通常属于句子而不是网址
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '\'', '"'];
+
+fn match_url(input: &str) -> Option<usize> {
+    let scheme_len = input
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '+' | '.' | '-')))
+        .unwrap_or(input.len());
+    let scheme = (scheme_len > 0
+        && input.starts_with(|c: char| c.is_ascii_alphabetic())
+        && input[scheme_len..].starts_with("://"))
+    .then_some(scheme_len + 3);
+    let start = match scheme {
+        Some(start) => start,
+        None if input
+            .get(..4)
+            .is_some_and(|s| s.eq_ignore_ascii_case("www.")) =>
+        {
+            4
+        }
+        None => return None,
+    };
+
+    let end = input[start..]
+        .find(|c: char| !c.is_ascii_graphic() || c == '<' || c == '>')
+        .map_or(input.len(), |i| start + i);
+    let url = input[..end].trim_end_matches(TRAILING_PUNCTUATION);
+    (url.len() > start).then_some(url.len())
+}
+
+fn match_email(input: &str) -> Option<usize> {
+    let is_local = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '%' | '+' | '-');
+    let at = input.find(|c: char| !is_local(c))?;
+    if at == 0 || !input[at..].starts_with('@') {
+        return None;
+    }
+
+    let domain_start = at + 1;
+    let domain_end = input[domain_start..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '-'))
+        .map_or(input.len(), |i| domain_start + i);
+    let domain = input[domain_start..domain_end].trim_end_matches('.');
+    let tld = domain.rsplit_once('.')?.1;
+    if tld.len() < 2 || !tld.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    Some(domain_start + domain.len())
+}
+
+// 1024、3.14、1,000,000、12:30、50%
+fn match_number(input: &str) -> Option<usize> {
+    let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let mut end = digits(input);
+    if end == 0 {
+        return None;
+    }
+
+    loop {
+        let rest = &input[end..];
+        match rest.chars().next() {
+            Some('.' | ',' | ':') if digits(&rest[1..]) > 0 => end += 1 + digits(&rest[1..]),
+            Some('%') => return Some(end + 1),
+            _ => return Some(end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::match_verbatim;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_match_verbatim() {
+        assert_eq!(match_verbatim("https://example.com/a?b=1，看"), Some(25));
+        assert_eq!(match_verbatim("www.example.com."), Some(15));
+        assert_eq!(match_verbatim("http://"), None);
+        assert_eq!(match_verbatim("hi@example.com是"), Some(14));
+        assert_eq!(match_verbatim("hi@localhost"), None);
+        assert_eq!(match_verbatim("1,000.5元"), Some(7));
+        assert_eq!(match_verbatim("50%的"), Some(3));
+        assert_eq!(match_verbatim("2024."), Some(4));
+        assert_eq!(match_verbatim("中"), None);
+    }
+}
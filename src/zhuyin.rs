@@ -0,0 +1,331 @@
+use crate::error::PinyinError;
+use crate::pinyin::{format_tone, Pinyin, ToneRenderer};
+use crate::syllable::is_valid_syllable;
+use crate::tone::Tone;
+
+// 注音符号的声母
+const INITIALS: [(char, &str); 21] = [
+    ('ㄅ', "b"),
+    ('ㄆ', "p"),
+    ('ㄇ', "m"),
+    ('ㄈ', "f"),
+    ('ㄉ', "d"),
+    ('ㄊ', "t"),
+    ('ㄋ', "n"),
+    ('ㄌ', "l"),
+    ('ㄍ', "g"),
+    ('ㄎ', "k"),
+    ('ㄏ', "h"),
+    ('ㄐ', "j"),
+    ('ㄑ', "q"),
+    ('ㄒ', "x"),
+    ('ㄓ', "zh"),
+    ('ㄔ', "ch"),
+    ('ㄕ', "sh"),
+    ('ㄖ', "r"),
+    ('ㄗ', "z"),
+    ('ㄘ', "c"),
+    ('ㄙ', "s"),
+];
+
+// 介音与韵母组合后的拼音韵母（带声母时的写法）
+const FINALS: [(&str, &str); 38] = [
+    ("ㄚ", "a"),
+    ("ㄛ", "o"),
+    ("ㄜ", "e"),
+    ("ㄝ", "ê"),
+    ("ㄞ", "ai"),
+    ("ㄟ", "ei"),
+    ("ㄠ", "ao"),
+    ("ㄡ", "ou"),
+    ("ㄢ", "an"),
+    ("ㄣ", "en"),
+    ("ㄤ", "ang"),
+    ("ㄥ", "eng"),
+    ("ㄦ", "er"),
+    ("ㄧ", "i"),
+    ("ㄧㄚ", "ia"),
+    ("ㄧㄛ", "io"),
+    ("ㄧㄝ", "ie"),
+    ("ㄧㄞ", "iai"),
+    ("ㄧㄠ", "iao"),
+    ("ㄧㄡ", "iu"),
+    ("ㄧㄢ", "ian"),
+    ("ㄧㄣ", "in"),
+    ("ㄧㄤ", "iang"),
+    ("ㄧㄥ", "ing"),
+    ("ㄨ", "u"),
+    ("ㄨㄚ", "ua"),
+    ("ㄨㄛ", "uo"),
+    ("ㄨㄞ", "uai"),
+    ("ㄨㄟ", "ui"),
+    ("ㄨㄢ", "uan"),
+    ("ㄨㄣ", "un"),
+    ("ㄨㄤ", "uang"),
+    ("ㄨㄥ", "ong"),
+    ("ㄩ", "ü"),
+    ("ㄩㄝ", "üe"),
+    ("ㄩㄢ", "üan"),
+    ("ㄩㄣ", "ün"),
+    ("ㄩㄥ", "iong"),
+];
+
+// 零声母时的拼写
+const ZERO_INITIAL: [(&str, &str); 8] = [
+    ("i", "yi"),
+    ("in", "yin"),
+    ("ing", "ying"),
+    ("iu", "you"),
+    ("u", "wu"),
+    ("ui", "wei"),
+    ("un", "wen"),
+    ("ong", "weng"),
+];
+
+fn is_tone_mark(c: char) -> bool {
+    matches!(c, 'ˉ' | 'ˊ' | 'ˇ' | 'ˋ' | '˙')
+}
+
+// "ㄋㄧˇㄏㄠˇ" -> [ni3, hao3]，音节之间可以有空白
+// 不标调为第一声，轻声符号 ˙ 可以写在音节前或音节后
+pub fn parse_zhuyin(input: &str) -> Result<Vec<Pinyin>, PinyinError> {
+    let chars: Vec<char> = input.chars().collect();
+    let invalid = || PinyinError::InvalidSyllable(input.to_string());
+    let mut syllables = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let mut tone = Tone::First;
+        if chars[i] == '˙' {
+            tone = Tone::Neutral;
+            i += 1;
+        }
+
+        let initial = chars
+            .get(i)
+            .and_then(|c| INITIALS.iter().find(|(z, _)| z == c))
+            .map(|(_, p)| *p);
+        if initial.is_some() {
+            i += 1;
+        }
+
+        // 介音加韵母最多两个符号，优先取长的
+        let fin = [2, 1].iter().find_map(|&len| {
+            let end = (i + len).min(chars.len());
+            let zhuyin: String = chars[i..end].iter().collect();
+            FINALS
+                .iter()
+                .find(|(z, _)| *z == zhuyin)
+                .map(|(_, p)| (*p, end - i))
+        });
+        if let Some((_, len)) = fin {
+            i += len;
+        }
+
+        if let Some(c) = chars.get(i).filter(|c| is_tone_mark(**c)) {
+            tone = match c {
+                'ˊ' => Tone::Second,
+                'ˇ' => Tone::Third,
+                'ˋ' => Tone::Fourth,
+                '˙' => Tone::Neutral,
+                _ => tone,
+            };
+            i += 1;
+        }
+
+        let pinyin = match (initial, fin.map(|(f, _)| f)) {
+            (None, None) => return Err(invalid()),
+            // ㄓ ㄔ ㄕ ㄖ ㄗ ㄘ ㄙ 单独成音节
+            (Some(initial), None) if "zh ch sh r z c s".split(' ').any(|s| s == initial) => {
+                format!("{}i", initial)
+            }
+            (Some(_), None) => return Err(invalid()),
+            (Some(initial), Some(fin)) => {
+                // j、q、x 后的 ü 写作 u
+                let fin = match initial {
+                    "j" | "q" | "x" => fin.replace('ü', "u"),
+                    _ => fin.to_string(),
+                };
+                format!("{}{}", initial, fin)
+            }
+            (None, Some(fin)) => zero_initial(fin),
+        };
+        // 声母与韵母都认识但拼不成音节，如 "ㄐㄚ"
+        if !is_valid_syllable(&pinyin) {
+            return Err(invalid());
+        }
+
+        syllables.push(Pinyin::new(&pinyin, tone));
+    }
+
+    Ok(syllables)
+}
+
+fn zero_initial(fin: &str) -> String {
+    if let Some((_, spelling)) = ZERO_INITIAL.iter().find(|(f, _)| *f == fin) {
+        return spelling.to_string();
+    }
+    if fin == "iong" {
+        return "yong".to_string();
+    }
+    if let Some(rest) = fin.strip_prefix('ü') {
+        return format!("yu{}", rest);
+    }
+    if let Some(rest) = fin.strip_prefix('i') {
+        return format!("y{}", rest);
+    }
+    if let Some(rest) = fin.strip_prefix('u') {
+        return format!("w{}", rest);
+    }
+    fin.to_string()
+}
+
+// 拼音音节转为注音："zhong", 4 -> "ㄓㄨㄥˋ"，第一声不标，轻声符号 ˙ 写在音节前
+// pinyin 中的 ü 可以写作 v；m、ng 等没有对应注音的叹词返回 None
+pub fn to_zhuyin(pinyin: &str, tone: Tone) -> Option<String> {
+    let pinyin = pinyin.to_lowercase().replace('v', "ü");
+
+    let (initial, fin) = match final_of_zero_initial(&pinyin) {
+        Some(fin) => (None, fin),
+        None => {
+            let initial = INITIALS
+                .iter()
+                .filter(|(_, p)| pinyin.starts_with(p))
+                .max_by_key(|(_, p)| p.len());
+            let rest = &pinyin[initial.map_or(0, |(_, p)| p.len())..];
+            // j、q、x 后的 u 读作 ü
+            let fin = match (initial, rest.strip_prefix('u')) {
+                (Some((_, "j" | "q" | "x")), Some(rest)) => format!("ü{}", rest),
+                _ => rest.to_string(),
+            };
+            (initial.map(|(z, _)| *z), fin)
+        }
+    };
+
+    let mut zhuyin = String::new();
+    if tone.is_neutral() {
+        zhuyin.push('˙');
+    }
+    zhuyin.extend(initial);
+    match (initial, fin.as_str()) {
+        // zhi、chi、shi、ri、zi、ci、si 只写声母
+        (Some('ㄓ' | 'ㄔ' | 'ㄕ' | 'ㄖ' | 'ㄗ' | 'ㄘ' | 'ㄙ'), "i") => {}
+        _ => zhuyin.push_str(FINALS.iter().find(|(_, p)| *p == fin)?.0),
+    }
+    match tone {
+        Tone::Second => zhuyin.push('ˊ'),
+        Tone::Third => zhuyin.push('ˇ'),
+        Tone::Fourth => zhuyin.push('ˋ'),
+        Tone::First | Tone::Neutral => {}
+    }
+
+    Some(zhuyin)
+}
+
+// zero_initial 的逆过程："yuan" -> "üan"，不是零声母音节时返回 None
+fn final_of_zero_initial(pinyin: &str) -> Option<String> {
+    if let Some((fin, _)) = ZERO_INITIAL.iter().find(|(_, p)| *p == pinyin) {
+        return Some(fin.to_string());
+    }
+    if pinyin == "yong" {
+        return Some("iong".to_string());
+    }
+    if let Some(rest) = pinyin.strip_prefix("yu") {
+        return Some(format!("ü{}", rest));
+    }
+    if let Some(rest) = pinyin.strip_prefix('y') {
+        return Some(format!("i{}", rest));
+    }
+    if let Some(rest) = pinyin.strip_prefix('w') {
+        return Some(format!("u{}", rest));
+    }
+    None
+}
+
+// 以注音输出，没有对应注音的音节仍输出带声调符号的拼音
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Zhuyin;
+
+impl ToneRenderer for Zhuyin {
+    fn render(&self, pinyin: &str, tone: Tone) -> String {
+        to_zhuyin(pinyin, tone).unwrap_or_else(|| format_tone(pinyin, tone))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_zhuyin, to_zhuyin};
+    use crate::pinyin::Pinyin;
+    use crate::tone::Tone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_zhuyin() {
+        let cases = [
+            ("ㄋㄧˇㄏㄠˇ", vec![("ni", 3), ("hao", 3)]),
+            ("ㄓㄨㄥ ㄍㄨㄛˊ", vec![("zhong", 1), ("guo", 2)]),
+            ("ㄒㄩㄝˊ ㄕˋ", vec![("xue", 2), ("shi", 4)]),
+            ("ㄌㄩˋ ㄋㄩˇ", vec![("lü", 4), ("nü", 3)]),
+            (
+                "ㄧㄡˇ ㄨㄟˊ ㄩㄢˊ ㄩㄥˇ",
+                vec![("you", 3), ("wei", 2), ("yuan", 2), ("yong", 3)],
+            ),
+            ("ㄐㄩㄥˇ ㄦˊ", vec![("jiong", 3), ("er", 2)]),
+            ("˙ㄉㄜ ㄇㄚ˙", vec![("de", 5), ("ma", 5)]),
+            ("ㄧ ㄨ ㄧㄣ", vec![("yi", 1), ("wu", 1), ("yin", 1)]),
+        ];
+        for (zhuyin, want) in cases {
+            let want: Vec<_> = want
+                .iter()
+                .map(|(p, t)| Pinyin::try_from((*p, *t)).unwrap())
+                .collect();
+            assert_eq!(parse_zhuyin(zhuyin).unwrap(), want, "{}", zhuyin);
+        }
+
+        assert!(parse_zhuyin("ㄅ").is_err());
+        assert!(parse_zhuyin("ni3").is_err());
+        assert!(parse_zhuyin("ㄐㄚ").is_err());
+        assert!(parse_zhuyin("ㄅㄩ").is_err());
+        assert!(parse_zhuyin("ㄈㄧ").is_err());
+    }
+
+    #[test]
+    fn test_to_zhuyin() {
+        let cases = [
+            ("zhong", 4, "ㄓㄨㄥˋ"),
+            ("shi", 4, "ㄕˋ"),
+            ("xue", 2, "ㄒㄩㄝˊ"),
+            ("lü", 4, "ㄌㄩˋ"),
+            ("lv", 4, "ㄌㄩˋ"),
+            ("jiong", 3, "ㄐㄩㄥˇ"),
+            ("yuan", 2, "ㄩㄢˊ"),
+            ("weng", 1, "ㄨㄥ"),
+            ("er", 2, "ㄦˊ"),
+            ("de", 5, "˙ㄉㄜ"),
+            ("ê", 2, "ㄝˊ"),
+        ];
+        for (pinyin, tone, want) in cases {
+            assert_eq!(
+                to_zhuyin(pinyin, Tone::try_from(tone).unwrap()).as_deref(),
+                Some(want),
+                "{}",
+                pinyin
+            );
+        }
+        assert_eq!(to_zhuyin("ng", Tone::Second), None);
+        assert_eq!(to_zhuyin("hm", Tone::Neutral), None);
+
+        // 与 parse_zhuyin 互逆
+        let syllables = "zhuang4 chuan2 you3 wei2 yong3 ying1 qu4 nü3 zi5 wai4 yue4 jun1";
+        for s in syllables.split(' ') {
+            let pinyin: Pinyin = s.parse().unwrap();
+            let zhuyin = to_zhuyin(&pinyin.pinyin, pinyin.tone).unwrap();
+            assert_eq!(parse_zhuyin(&zhuyin).unwrap(), vec![pinyin], "{}", zhuyin);
+        }
+    }
+}